@@ -20,6 +20,10 @@ struct TraitAutoDeriveData {
     generic_args: AngleBracketedGenericArguments,
     data_fields: Vec<Field>,
     class_fields: Vec<Field>,
+    /// `PhantomData` fields, which have no Java counterpart.
+    marker_fields: Vec<Ident>,
+    /// Whether the struct has a `#[std_convert]` attribute, asking for `std::convert` impls too.
+    std_convert: bool,
 }
 
 pub(crate) fn into_java_value_macro_derive(input: DeriveInput) -> TokenStream {
@@ -37,9 +41,30 @@ fn into_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStr
         generics,
         instance_ident,
         generic_args,
+        std_convert,
         ..
     } = get_trait_impl_components("IntoJavaValue", input);
 
+    let std_convert_impls = if std_convert {
+        quote! {
+            #[automatically_derived]
+            impl#generics ::core::convert::From<#impl_target#generic_args> for ::robusta_jni::jni::objects::JObject<'env> {
+                fn from(value: #impl_target#generic_args) -> Self {
+                    value.#instance_ident.forget()
+                }
+            }
+
+            #[automatically_derived]
+            impl#generics ::core::convert::From<&#impl_target#generic_args> for ::robusta_jni::jni::objects::JObject<'env> {
+                fn from(value: &#impl_target#generic_args) -> Self {
+                    value.#instance_ident.as_obj()
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
     Ok(quote! {
         #instance_field_type_assertion
 
@@ -48,7 +73,8 @@ fn into_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStr
             type Target = ::robusta_jni::jni::objects::JObject<'env>;
 
             fn into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> Self::Target {
-                ::robusta_jni::convert::IntoJavaValue::into(self, env)
+                // Keep the instance local reference alive once `self` is dropped.
+                self.#instance_ident.forget()
             }
         }
 
@@ -66,9 +92,11 @@ fn into_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStr
             type Target = ::robusta_jni::jni::objects::JObject<'env>;
 
             fn into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> Self::Target {
-                ::robusta_jni::convert::IntoJavaValue::into(self, env)
+                ::robusta_jni::convert::IntoJavaValue::into(&*self, env)
             }
         }
+
+        #std_convert_impls
     })
 }
 
@@ -98,7 +126,8 @@ fn tryinto_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<Token
             type Target = ::robusta_jni::jni::objects::JObject<'env>;
 
             fn try_into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<Self::Target> {
-                ::robusta_jni::convert::TryIntoJavaValue::try_into(self, env)
+                // Keep the instance local reference alive once `self` is dropped.
+                Ok(self.#instance_ident.forget())
             }
         }
 
@@ -116,7 +145,7 @@ fn tryinto_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<Token
             type Target = ::robusta_jni::jni::objects::JObject<'env>;
 
             fn try_into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<Self::Target> {
-                ::robusta_jni::convert::TryIntoJavaValue::try_into(self, env)
+                ::robusta_jni::convert::TryIntoJavaValue::try_into(&*self, env)
             }
         }
     })
@@ -140,6 +169,8 @@ fn from_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStr
         generic_args,
         data_fields,
         class_fields,
+        marker_fields,
+        ..
     } = get_trait_impl_components("FromJavaValue", input);
 
     let data_fields_struct_init: Vec<_> = data_fields
@@ -191,6 +222,7 @@ fn from_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStr
 
                 Self {
                     #instance_ident: ::robusta_jni::jni::objects::AutoLocal::new(env, source),
+                    #(#marker_fields: ::core::marker::PhantomData,)*
                     #(#data_fields_struct_init),*
                     #(#class_fields_struct_init),*
                 }
@@ -217,8 +249,25 @@ fn tryfrom_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<Token
         generic_args,
         data_fields,
         class_fields,
+        marker_fields,
+        std_convert,
     } = get_trait_impl_components("FromJavaValue", input);
 
+    let std_convert_impls = if std_convert {
+        quote! {
+            #[automatically_derived]
+            impl#generics ::core::convert::TryFrom<(::robusta_jni::jni::objects::JObject<'env>, &'borrow ::robusta_jni::jni::JNIEnv<'env>)> for #impl_target#generic_args {
+                type Error = ::robusta_jni::jni::errors::Error;
+
+                fn try_from((source, env): (::robusta_jni::jni::objects::JObject<'env>, &'borrow ::robusta_jni::jni::JNIEnv<'env>)) -> ::robusta_jni::jni::errors::Result<Self> {
+                    ::robusta_jni::convert::TryFromJavaValue::try_from(source, env)
+                }
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+
     let data_fields_struct_init: Vec<_> = data_fields
         .iter()
         .map(|f| f.ident.as_ref().unwrap())
@@ -265,11 +314,46 @@ fn tryfrom_java_value_macro_derive_impl(input: DeriveInput) -> syn::Result<Token
 
                 Ok(Self {
                     #instance_ident: ::robusta_jni::jni::objects::AutoLocal::new(env, source),
+                    #(#marker_fields: ::core::marker::PhantomData,)*
                     #(#data_fields_struct_init),*
                     #(#class_fields_struct_init),*
                 })
             }
         }
+
+        #std_convert_impls
+    })
+}
+
+pub(crate) fn java_debug_macro_derive(input: DeriveInput) -> TokenStream {
+    let input_span = input.span();
+    match java_debug_macro_derive_impl(input) {
+        Ok(t) => t,
+        Err(_) => quote_spanned! { input_span => },
+    }
+}
+
+fn java_debug_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    let TraitAutoDeriveData {
+        instance_field_type_assertion,
+        impl_target,
+        generics,
+        instance_ident,
+        generic_args,
+        ..
+    } = get_trait_impl_components("JavaDebug", input);
+
+    let struct_name = impl_target.to_string();
+
+    Ok(quote! {
+        #instance_field_type_assertion
+
+        #[automatically_derived]
+        impl#generics ::core::fmt::Debug for #impl_target#generic_args {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::robusta_jni::convert::java_debug(self.#instance_ident.as_obj(), #struct_name, f)
+            }
+        }
     })
 }
 
@@ -286,18 +370,12 @@ fn get_trait_impl_components(trait_name: &str, input: DeriveInput) -> TraitAutoD
                 abort!(input_span, "missing `#[package]` attribute")
             }
 
-            let classpath_path = package_attr
-                .unwrap()
-                .parse_args()
-                .map(|p: JavaPath| p.to_classpath_path())
-                .map(|s| {
-                    let mut s = s.clone();
-                    if !s.is_empty() {
-                        s.push('/');
-                    }
-                    s.push_str(&input_ident.to_string());
-                    s
-                })
+            let std_convert = input.attrs.iter().any(|a| {
+                a.path().get_ident().map(ToString::to_string).as_deref() == Some("std_convert")
+            });
+
+            let classpath_path = JavaPath::from_package_attribute(package_attr.unwrap())
+                .map(|p| p.class_classpath_path(&input_ident.to_string()))
                 .unwrap_or_else(|_| {
                     emit_error!(package_attr, "invalid Java class path");
                     "".to_string()
@@ -403,11 +481,18 @@ fn get_trait_impl_components(trait_name: &str, input: DeriveInput) -> TraitAutoD
 
                     let generic_args = generic_params_to_args(generics.clone());
 
+                    let marker_fields: Vec<_> = fields
+                        .iter()
+                        .filter(|f| is_phantom_data(&f.ty))
+                        .filter_map(|f| f.ident.clone())
+                        .collect();
+
                     let data_fields: Vec<_> = fields
                         .iter()
                         .filter(|f| {
                             f.ident.as_ref() != Some(instance_ident)
                                 && class_fields.iter().all(|g| g != f)
+                                && !is_phantom_data(&f.ty)
                         })
                         .cloned()
                         .collect();
@@ -421,6 +506,8 @@ fn get_trait_impl_components(trait_name: &str, input: DeriveInput) -> TraitAutoD
                         generic_args,
                         data_fields,
                         class_fields: class_fields.into_iter().cloned().collect(),
+                        marker_fields,
+                        std_convert,
                     }
                 }
             }
@@ -432,3 +519,14 @@ fn get_trait_impl_components(trait_name: &str, input: DeriveInput) -> TraitAutoD
         ),
     }
 }
+
+/// Whether a field is a `PhantomData`, which is initialized without reading anything from the Java object.
+fn is_phantom_data(ty: &Type) -> bool {
+    match ty {
+        Type::Path(TypePath { path, .. }) => path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "PhantomData"),
+        _ => false,
+    }
+}
@@ -1,3 +1,4 @@
 pub(crate) mod convert;
+pub(crate) mod record;
 pub(crate) mod signature;
 mod utils;
@@ -0,0 +1,113 @@
+use inflector::cases::camelcase::to_camel_case;
+use proc_macro2::TokenStream;
+use proc_macro_error::abort;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Data, DataStruct, DeriveInput, Fields};
+
+use crate::transformation::JavaPath;
+
+pub(crate) fn java_record_macro_derive(input: DeriveInput) -> TokenStream {
+    let input_span = input.span();
+    match java_record_macro_derive_impl(input) {
+        Ok(t) => t,
+        Err(_) => quote_spanned! { input_span => },
+    }
+}
+
+fn java_record_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
+    let input_span = input.span();
+
+    let fields = match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => fields.named.iter().collect::<Vec<_>>(),
+        _ => abort!(
+            input_span,
+            "`JavaRecord` auto-derive implemented for structs with named fields only"
+        ),
+    };
+
+    if !input.generics.params.is_empty() {
+        abort!(
+            input.generics,
+            "`JavaRecord` can't be derived for generic structs"
+        )
+    }
+
+    let package_attr = input
+        .attrs
+        .iter()
+        .find(|a| a.path().get_ident().map(ToString::to_string).as_deref() == Some("package"));
+    let classpath_path = match package_attr {
+        None => abort!(input_span, "missing `#[package]` attribute"),
+        Some(attr) => {
+            JavaPath::from_package_attribute(attr)?.class_classpath_path(&input.ident.to_string())
+        }
+    };
+
+    let struct_name = &input.ident;
+    let field_idents: Vec<_> = fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+    let field_types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+
+    /* Fields are passed to the canonical constructor in declaration order */
+    let constructor_descriptor = quote! {
+        ["(", #(<#field_types as ::robusta_jni::convert::Signature>::SIG_TYPE,)* ")V"].join("")
+    };
+
+    /* Components are read with their accessor methods, named after the fields in camelCase */
+    let accessor_calls: Vec<_> = fields
+        .iter()
+        .map(|f| {
+            let accessor_name = to_camel_case(&f.ident.as_ref().unwrap().to_string());
+            let field_type = &f.ty;
+
+            quote_spanned! { f.span() =>
+                env.call_method(source, #accessor_name, ["()", <#field_type as ::robusta_jni::convert::Signature>::SIG_TYPE].join(""), &[])?
+            }
+        })
+        .collect();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl<'env> ::robusta_jni::convert::TryIntoJavaValue<'env> for #struct_name {
+            type Target = ::robusta_jni::jni::objects::JObject<'env>;
+
+            fn try_into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<Self::Target> {
+                env.new_object(#classpath_path, #constructor_descriptor, &[
+                    #(::std::convert::Into::into(::robusta_jni::convert::TryIntoJavaValue::try_into(self.#field_idents, env)?),)*
+                ])
+            }
+        }
+
+        #[automatically_derived]
+        impl<'env> ::robusta_jni::convert::IntoJavaValue<'env> for #struct_name {
+            type Target = ::robusta_jni::jni::objects::JObject<'env>;
+
+            fn into(self, env: &::robusta_jni::jni::JNIEnv<'env>) -> Self::Target {
+                ::robusta_jni::convert::TryIntoJavaValue::try_into(self, env).unwrap()
+            }
+        }
+
+        #[automatically_derived]
+        impl<'env: 'borrow, 'borrow> ::robusta_jni::convert::TryFromJavaValue<'env, 'borrow> for #struct_name {
+            type Source = ::robusta_jni::jni::objects::JObject<'env>;
+
+            fn try_from(source: Self::Source, env: &'borrow ::robusta_jni::jni::JNIEnv<'env>) -> ::robusta_jni::jni::errors::Result<Self> {
+                Ok(Self {
+                    #(#field_idents: ::robusta_jni::convert::TryFromJavaValue::try_from(::core::convert::TryInto::try_into(::robusta_jni::convert::JValueWrapper::from(#accessor_calls))?, env)?,)*
+                })
+            }
+        }
+
+        #[automatically_derived]
+        impl<'env: 'borrow, 'borrow> ::robusta_jni::convert::FromJavaValue<'env, 'borrow> for #struct_name {
+            type Source = ::robusta_jni::jni::objects::JObject<'env>;
+
+            fn from(source: Self::Source, env: &'borrow ::robusta_jni::jni::JNIEnv<'env>) -> Self {
+                ::robusta_jni::convert::TryFromJavaValue::try_from(source, env).unwrap()
+            }
+        }
+    })
+}
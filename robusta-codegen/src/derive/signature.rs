@@ -29,22 +29,12 @@ fn signature_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
                 None => abort!(input_span, "missing `#[package()]` attribute"),
                 Some(attr) => {
                     let struct_name = input.ident;
-                    let package = attr.parse_args::<JavaPath>()?;
-                    let package_str = {
-                        let mut s = package.to_classpath_path();
-                        if !s.is_empty() {
-                            s.push('/')
-                        }
-                        s
-                    };
-
-                    let signature = [
-                        "L",
-                        package_str.as_str(),
-                        struct_name.to_string().as_str(),
-                        ";",
-                    ]
-                    .join("");
+                    let package = JavaPath::from_package_attribute(attr)?;
+                    let signature = format!(
+                        "L{};",
+                        package.class_classpath_path(&struct_name.to_string())
+                    );
+                    let array_signature = format!("[{}", signature);
                     let generics = input.generics.clone();
                     let generic_args = generic_params_to_args(input.generics);
 
@@ -54,14 +44,19 @@ fn signature_macro_derive_impl(input: DeriveInput) -> syn::Result<TokenStream> {
                             const SIG_TYPE: &'static str = #signature;
                         }
 
+                        #[automatically_derived]
+                        impl#generics ::robusta_jni::convert::ArraySignature for #struct_name#generic_args {
+                            const ARRAY_SIG_TYPE: &'static str = #array_signature;
+                        }
+
                         #[automatically_derived]
                         impl#generics ::robusta_jni::convert::Signature for &#struct_name#generic_args {
-                            const SIG_TYPE: &'static str = <#struct_name as ::robusta_jni::convert::Signature>::SIG_TYPE;
+                            const SIG_TYPE: &'static str = <#struct_name#generic_args as ::robusta_jni::convert::Signature>::SIG_TYPE;
                         }
 
                         #[automatically_derived]
                         impl#generics ::robusta_jni::convert::Signature for &mut #struct_name#generic_args {
-                            const SIG_TYPE: &'static str = <#struct_name as ::robusta_jni::convert::Signature>::SIG_TYPE;
+                            const SIG_TYPE: &'static str = <#struct_name#generic_args as ::robusta_jni::convert::Signature>::SIG_TYPE;
                         }
                     })
                 }
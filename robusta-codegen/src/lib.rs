@@ -6,10 +6,11 @@ use syn::{parse_macro_input, DeriveInput};
 use validation::JNIBridgeModule;
 
 use crate::derive::convert::{
-    from_java_value_macro_derive, into_java_value_macro_derive, tryfrom_java_value_macro_derive,
-    tryinto_java_value_macro_derive,
+    from_java_value_macro_derive, into_java_value_macro_derive, java_debug_macro_derive,
+    tryfrom_java_value_macro_derive, tryinto_java_value_macro_derive,
 };
-use crate::transformation::ModTransformer;
+use crate::transformation::{BridgeParams, ModTransformer};
+use derive::record::java_record_macro_derive;
 use derive::signature::signature_macro_derive;
 
 mod derive;
@@ -19,10 +20,14 @@ mod validation;
 
 #[proc_macro_error]
 #[proc_macro_attribute]
-pub fn bridge(_args: TokenStream, raw_input: TokenStream) -> TokenStream {
+pub fn bridge(args: TokenStream, raw_input: TokenStream) -> TokenStream {
+    let params = match BridgeParams::from_args(args.into()) {
+        Ok(params) => params,
+        Err(e) => return e.write_errors().into(),
+    };
     let module_data = parse_macro_input!(raw_input as JNIBridgeModule);
 
-    let mut transformer = ModTransformer::new(module_data);
+    let mut transformer = ModTransformer::new(module_data, params);
     let tokens = transformer.transform_module();
 
     tokens.into()
@@ -37,7 +42,7 @@ pub fn signature_derive(raw_input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_error]
-#[proc_macro_derive(IntoJavaValue, attributes(package, instance, field))]
+#[proc_macro_derive(IntoJavaValue, attributes(package, instance, field, std_convert))]
 pub fn into_java_value_derive(raw_input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(raw_input as DeriveInput);
 
@@ -61,9 +66,25 @@ pub fn from_java_value_derive(raw_input: TokenStream) -> TokenStream {
 }
 
 #[proc_macro_error]
-#[proc_macro_derive(TryFromJavaValue, attributes(package, instance, field))]
+#[proc_macro_derive(TryFromJavaValue, attributes(package, instance, field, std_convert))]
 pub fn tryfrom_java_value_derive(raw_input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(raw_input as DeriveInput);
 
     tryfrom_java_value_macro_derive(input).into()
 }
+
+#[proc_macro_error]
+#[proc_macro_derive(JavaRecord, attributes(package))]
+pub fn java_record_derive(raw_input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(raw_input as DeriveInput);
+
+    java_record_macro_derive(input).into()
+}
+
+#[proc_macro_error]
+#[proc_macro_derive(JavaDebug, attributes(package, instance, field))]
+pub fn java_debug_derive(raw_input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(raw_input as DeriveInput);
+
+    java_debug_macro_derive(input).into()
+}
@@ -1,10 +1,35 @@
-use crate::transformation::JavaPath;
-use syn::{LifetimeParam, Path};
+use crate::transformation::{BridgeParams, JavaPath, ReceiverStrategy};
+use syn::{LifetimeParam, Path, WhereClause};
 
 #[derive(Clone)]
 pub(crate) struct StructContext {
     pub(crate) struct_type: Path,
     pub(crate) struct_name: String,
     pub(crate) struct_lifetimes: Vec<LifetimeParam>,
+    /// `where` clause of the impl block, added to the generated JNI functions.
+    pub(crate) where_clause: Option<WhereClause>,
     pub(crate) package: Option<JavaPath>,
+    /// Fully qualified names of the other Java classes whose native methods are bound to this struct (`#[class_alias]`).
+    pub(crate) class_aliases: Vec<JavaPath>,
+    /// Whether classes are looked up with the loader registered at runtime for Java modules (`#[module_loader]`).
+    pub(crate) module_loader: bool,
+    /// How the receiver of exported `self` methods is reconstructed (`#[receiver]`).
+    pub(crate) receiver: ReceiverStrategy,
+    pub(crate) bridge_params: BridgeParams,
+}
+
+/// Context of a plain `Foo` struct in the default package, to be overridden with `..struct_context()` in tests.
+#[cfg(test)]
+pub(crate) fn struct_context() -> StructContext {
+    StructContext {
+        struct_type: syn::parse_quote! { Foo },
+        struct_name: "Foo".into(),
+        struct_lifetimes: vec![],
+        where_clause: None,
+        package: None,
+        class_aliases: vec![],
+        module_loader: false,
+        receiver: Default::default(),
+        bridge_params: Default::default(),
+    }
 }
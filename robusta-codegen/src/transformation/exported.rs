@@ -1,51 +1,306 @@
 use std::collections::HashSet;
 
-use proc_macro2::Ident;
+use darling::util::Flag;
+use darling::FromMeta;
+use proc_macro2::{Ident, Span, TokenStream};
 use proc_macro_error::{emit_error, emit_warning};
-use quote::ToTokens;
+use quote::{quote, ToTokens};
 use syn::fold::Fold;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Extern;
+use syn::visit::{self, Visit};
 use syn::Lifetime;
 use syn::Token;
 use syn::{parse_quote, GenericParam, Generics, LifetimeParam, TypeTuple};
 use syn::{
-    Abi, Block, Expr, FnArg, ImplItemFn, LitStr, Pat, PatIdent, PatType, ReturnType, Signature,
-    Type, Visibility,
+    Abi, AngleBracketedGenericArguments, Attribute, Block, Expr, FnArg, GenericArgument,
+    ImplItemFn, LitStr, Pat, PatIdent, PatType, PathArguments, ReturnType, Signature, Stmt, Type,
+    TypePath, TypeReference, Visibility, WhereClause,
 };
 
 use crate::transformation::context::StructContext;
+use crate::transformation::imported::returns_value;
 use crate::transformation::utils::get_call_type;
-use crate::transformation::{CallType, FreestandingTransformer, SafeParams};
-use crate::utils::{get_abi, get_env_arg, is_self_method};
+use crate::transformation::{
+    bridge_method_entry, jni_mangle, CallType, CallTypeAttribute, FreestandingTransformer,
+    JavaPath, LocalFrameParams, NullPolicy, ReceiverStrategy, SafeParams,
+};
+use crate::utils::{freestanding_tokens, get_abi, get_env_arg, is_self_method};
 use std::iter::FromIterator;
 
 pub struct ExportedMethodTransformer<'ctx> {
     pub(crate) struct_context: &'ctx StructContext,
 }
 
-impl<'ctx> Fold for ExportedMethodTransformer<'ctx> {
-    fn fold_impl_item_fn(&mut self, node: ImplItemFn) -> ImplItemFn {
-        let abi = get_abi(&node.sig);
-        match (&node.vis, &abi.as_deref()) {
-            (Visibility::Public(_), Some("jni")) => {
-                let call_type_attribute = get_call_type(&node)
+impl<'ctx> ExportedMethodTransformer<'ctx> {
+    /// Transform an exported method into its JNI functions.
+    /// Methods with a `#[call_type(both(...))]` attribute are exported twice, once for each call type,
+    /// and every JNI function is exported again for each `#[class_alias]` of the struct.
+    ///
+    /// The visibility of the method doesn't matter: JNI functions are always public.
+    pub(crate) fn transform_impl_item_fn(&mut self, node: ImplItemFn) -> Vec<ImplItemFn> {
+        let variants = match call_type_variants(&node) {
+            Some(variants) => variants,
+            None => return vec![node],
+        };
+
+        let struct_context = self.struct_context;
+        let classes = std::iter::once(None).chain(struct_context.class_aliases.iter().map(Some));
+
+        classes
+            .flat_map(|class_alias| {
+                variants
+                    .iter()
+                    .map(|(call_type, suffix)| {
+                        let mut jni_method_transformer =
+                            ExternJNIMethodTransformer::new(struct_context, call_type.clone());
+                        jni_method_transformer.method_suffix = suffix.clone();
+                        jni_method_transformer.class_alias = class_alias.cloned();
+                        jni_method_transformer.fold_impl_item_fn(node.clone())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Entries of the `bridge_methods()` function of `#[bridge(descriptors)]` modules, one for each JNI function of the method.
+    ///
+    /// The descriptor is made of the signatures of the parameter types and of the type converted to Java as return value.
+    /// Methods with a conversion context are skipped, as their types don't have to implement `Signature`.
+    pub(crate) fn bridge_methods(&self, node: &ImplItemFn) -> Vec<TokenStream> {
+        let variants = match call_type_variants(node) {
+            Some(variants) => variants,
+            None => return Vec::new(),
+        };
+
+        let struct_context = self.struct_context;
+        let mut freestanding_transformer =
+            FreestandingTransformer::new(struct_context.struct_type.clone());
+        let (signature, _) = get_env_arg(node.sig.clone());
+
+        let input_signatures: TokenStream = signature
+            .inputs
+            .iter()
+            .filter_map(|i| match i {
+                FnArg::Typed(t) => match &*t.pat {
+                    Pat::Ident(PatIdent { ident, .. }) if ident == "self" => None,
+                    _ => Some(freestanding_transformer.fold_type((*t.ty).clone())),
+                },
+                FnArg::Receiver(_) => None,
+            })
+            .map(|ty| quote! { <#ty as ::robusta_jni::convert::Signature>::SIG_TYPE, })
+            .collect();
+
+        let classes = std::iter::once(None).chain(struct_context.class_aliases.iter().map(Some));
+
+        classes
+            .flat_map(|class_alias| {
+                variants
+                    .iter()
+                    .filter(|(call_type, _)| call_type.conversion_context().is_none())
+                    .map(|(call_type, suffix)| {
+                        let output_signature = match &signature.output {
+                            ReturnType::Default => quote! { "V" },
+                            ReturnType::Type(_, ty) => {
+                                let ty = freestanding_transformer.fold_type((**ty).clone());
+                                let converted_type = match (
+                                    anyhow_result_ok_type(&ty).or_else(|| unit_result_ok_type(&ty)),
+                                    call_type,
+                                ) {
+                                    (Some(ok_type), CallType::Safe(_)) => ok_type,
+                                    (None, CallType::Safe(_)) if call_type.derive_class().is_some() => {
+                                        result_type_parts(&ty).map_or(&ty, |(ok_type, _)| ok_type)
+                                    }
+                                    _ => &ty,
+                                };
+
+                                quote! { <#converted_type as ::robusta_jni::convert::Signature>::SIG_TYPE }
+                            }
+                        };
+
+                        let class = match (class_alias, &struct_context.package) {
+                            (Some(alias), _) => alias.to_classpath_path(),
+                            (None, Some(package)) => {
+                                package.class_classpath_path(&struct_context.struct_name)
+                            }
+                            (None, None) => struct_context.struct_name.clone(),
+                        };
+                        let method_name =
+                            signature.ident.to_string() + suffix.as_deref().unwrap_or("");
+
+                        bridge_method_entry(
+                            struct_context,
+                            "Exported",
+                            &class,
+                            &struct_context.bridge_params.java_method_name(&method_name),
+                            quote! { ["(", #input_signatures ")", #output_signature].join("") },
+                            !is_self_method(&node.sig),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Arms of the `invoke` function of `#[dispatcher]` structs calling the JNI functions of the method, one for each of them
+    /// in the class of the struct.
+    ///
+    /// Arms match the Java method name and the number of arguments, including the receiver of `self` methods.
+    /// Methods with a conversion context are skipped, as their types don't have to implement `Signature`.
+    pub(crate) fn dispatch_arms(&self, node: &ImplItemFn) -> Vec<TokenStream> {
+        let variants = match call_type_variants(node) {
+            Some(variants) => variants,
+            None => return Vec::new(),
+        };
+
+        let struct_context = self.struct_context;
+        let mut freestanding_transformer =
+            FreestandingTransformer::new(struct_context.struct_type.clone());
+        let (signature, _) = get_env_arg(node.sig.clone());
+        let self_type = freestanding_tokens(
+            struct_context.struct_type.to_token_stream(),
+            &TokenStream::new(),
+        );
+
+        /* The receiver of `self` methods is an instance of the struct's class, passed first */
+        let receiver_argument = is_self_method(&node.sig).then(|| {
+            let class = match &struct_context.package {
+                Some(package) => package.class_classpath_path(&struct_context.struct_name),
+                None => struct_context.struct_name.clone(),
+            };
+            let receiver_signature = format!("L{};", class);
+            quote! { call.arg(0i32, #receiver_signature)? }
+        });
+        let class_argument = receiver_argument.is_none().then(|| quote! { class, });
+
+        let first_index = receiver_argument.is_some() as i32;
+        let parameter_arguments = signature
+            .inputs
+            .iter()
+            .filter_map(|i| match i {
+                FnArg::Typed(t) => match &*t.pat {
+                    Pat::Ident(PatIdent { ident, .. }) if ident == "self" => None,
+                    _ => Some(freestanding_transformer.fold_type((*t.ty).clone())),
+                },
+                FnArg::Receiver(_) => None,
+            })
+            .zip(first_index..)
+            .map(|(ty, index)| {
+                let ty = freestanding_tokens(ty.to_token_stream(), &self_type);
+                quote! { call.arg(#index, <#ty as ::robusta_jni::convert::Signature>::SIG_TYPE)? }
+            });
+        let arguments: Vec<TokenStream> = receiver_argument
+            .into_iter()
+            .chain(parameter_arguments)
+            .collect();
+        let arity = arguments.len() as i32;
+
+        variants
+            .iter()
+            .filter(|(call_type, _)| call_type.conversion_context().is_none())
+            .map(|(call_type, suffix)| {
+                let mut jni_method_transformer =
+                    ExternJNIMethodTransformer::new(struct_context, call_type.clone());
+                jni_method_transformer.method_suffix = suffix.clone();
+
+                let method_name = signature.ident.to_string() + suffix.as_deref().unwrap_or("");
+                let java_method_name = struct_context.bridge_params.java_method_name(&method_name);
+                let jni_function = Ident::new(
+                    &jni_method_transformer.jni_function_name(&method_name),
+                    signature.ident.span(),
+                );
+
+                let jni_call = quote! {
+                    #jni_function(call.env()?, #class_argument #(#arguments),*)
+                };
+                /* `()` is not boxed by `JavaValue`, `void` methods return `null` instead */
+                let returned = if returns_value(&signature.output) {
+                    quote! { call.returned(#jni_call) }
+                } else {
+                    quote! {{
+                        #jni_call;
+                        call.returned(::robusta_jni::jni::objects::JObject::null())
+                    }}
+                };
+
+                quote! { (#java_method_name, #arity) => #returned, }
+            })
+            .collect()
+    }
+
+    /// Name of the JNI function of the `invoke` method of `#[dispatcher]` structs.
+    pub(crate) fn dispatcher_name(&self) -> Ident {
+        let jni_method_transformer =
+            ExternJNIMethodTransformer::new(self.struct_context, CallType::Safe(None));
+
+        Ident::new(
+            &jni_method_transformer.jni_function_name("invoke"),
+            Span::call_site(),
+        )
+    }
+}
+
+/// `invoke` JNI function of a `#[dispatcher]` struct, calling the exported method selected by one of the `arms`.
+pub(crate) fn dispatcher_function(name: &Ident, arms: &[TokenStream]) -> TokenStream {
+    quote! {
+        #[no_mangle]
+        #[allow(unused_variables)]
+        pub extern "system" fn #name<'env>(
+            env: ::robusta_jni::jni::JNIEnv<'env>,
+            class: ::robusta_jni::jni::objects::JClass<'env>,
+            method: ::robusta_jni::jni::objects::JString<'env>,
+            args: ::robusta_jni::jni::sys::jobjectArray,
+        ) -> ::robusta_jni::jni::sys::jobject {
+            let invoke = || -> ::robusta_jni::jni::errors::Result<::robusta_jni::jni::objects::JObject<'env>> {
+                let call = ::robusta_jni::convert::DispatchCall::new(&env, method, args)?;
+
+                match (call.method(), call.arity()) {
+                    #(#arms)*
+                    _ => Err(call.no_such_method()),
+                }
+            };
+
+            ::robusta_jni::convert::finish_dispatch(&env, invoke())
+        }
+    }
+}
+
+/// Call types of the JNI functions exported for a method, with the suffix of their Java method names.
+/// Methods with a `#[call_type(both(...))]` attribute are exported twice, once for each call type.
+///
+/// Returns `None` if the method isn't an `extern "jni"` one.
+fn call_type_variants(node: &ImplItemFn) -> Option<Vec<(CallType, Option<String>)>> {
+    match get_abi(&node.sig).as_deref() {
+        Some("jni") => Some(match get_call_type(node) {
+            Some(CallTypeAttribute {
+                both: Some(both), ..
+            }) => vec![
+                (CallType::Safe(None), Some(both.safe_suffix)),
+                (
+                    CallType::Unchecked(Flag::default()),
+                    Some(both.unchecked_suffix),
+                ),
+            ],
+            call_type_attribute => {
+                let call_type = call_type_attribute
                     .map(|c| c.call_type)
                     .unwrap_or(CallType::Safe(None));
 
-                let mut jni_method_transformer =
-                    ExternJNIMethodTransformer::new(self.struct_context, call_type_attribute);
-                jni_method_transformer.fold_impl_item_fn(node)
+                vec![(call_type, None)]
             }
-            _ => node,
-        }
+        }),
+        _ => None,
     }
 }
 
 struct ExternJNIMethodTransformer<'ctx> {
     struct_context: &'ctx StructContext,
     call_type: CallType,
+    /// Appended to the Java method name, to tell apart the variants of `#[call_type(both(...))]` methods.
+    method_suffix: Option<String>,
+    /// Java class the JNI function is bound to in place of the struct's own one.
+    class_alias: Option<JavaPath>,
 }
 
 impl<'ctx> ExternJNIMethodTransformer<'ctx> {
@@ -53,8 +308,27 @@ impl<'ctx> ExternJNIMethodTransformer<'ctx> {
         ExternJNIMethodTransformer {
             struct_context,
             call_type,
+            method_suffix: None,
+            class_alias: None,
         }
     }
+
+    /// Name of the JNI function of the Rust method `method_name`, in the class of the struct or in the class alias.
+    fn jni_function_name(&self, method_name: &str) -> String {
+        let jni_class_name = match (&self.class_alias, &self.struct_context.package) {
+            (Some(alias), _) => jni_mangle(&alias.to_classpath_path()),
+            (None, Some(package)) => package.class_jni_name(&self.struct_context.struct_name),
+            (None, None) => jni_mangle(&self.struct_context.struct_name),
+        };
+        let java_method_name = jni_mangle(
+            &self
+                .struct_context
+                .bridge_params
+                .java_method_name(method_name),
+        );
+
+        ["Java", &jni_class_name, &java_method_name].join("_")
+    }
 }
 
 impl<'ctx> Fold for ExternJNIMethodTransformer<'ctx> {
@@ -66,14 +340,58 @@ impl<'ctx> Fold for ExternJNIMethodTransformer<'ctx> {
         );
 
         let transformed_jni_signature = jni_signature.transformed_signature();
-        let method_call = jni_signature.signature_call();
+        let method_call = {
+            let call = jni_signature.signature_call();
+
+            /* The JNI function keeps the `unsafe` qualifier, but the user function is called
+             * from an explicit `unsafe` block so that `unsafe_op_in_unsafe_fn` is satisfied.
+             */
+            if node.sig.unsafety.is_some() {
+                parse_quote_spanned! { node.sig.unsafety.span() => unsafe { #call } }
+            } else {
+                call
+            }
+        };
+
+        /* With `#[local_frame]`, the local references created by the method are deleted when it returns */
+        let local_frame_capacity = node
+            .attrs
+            .iter()
+            .find(|a| a.path().is_ident("local_frame"))
+            .and_then(|a| match LocalFrameParams::from_meta(&a.meta) {
+                Ok(LocalFrameParams { capacity }) if capacity > 0 => Some(capacity),
+                Ok(_) => {
+                    emit_error!(a, "`local_frame` capacity must be positive");
+                    None
+                }
+                Err(e) => {
+                    emit_error!(a, "invalid `local_frame` attribute options ({})", e;
+                                help = "use e.g. `#[local_frame(capacity = 256)]`");
+                    None
+                }
+            });
+
+        /* `#[hot]` methods are inlined in their JNI function (see `ImplCleaner`), and so is their conversion glue:
+         * safe methods convert in an `outer` function, while unchecked methods convert in the JNI function itself.
+         */
+        let inline_attribute: Option<Attribute> = node
+            .attrs
+            .iter()
+            .find(|a| a.path().is_ident("hot"))
+            .map(|_| parse_quote! { #[inline(always)] });
 
         let new_block: Block = match &self.call_type {
-            CallType::Unchecked { .. } => {
-                parse_quote_spanned! { node.span() => {
+            CallType::Unchecked { .. } => match local_frame_capacity {
+                Some(capacity) => parse_quote_spanned! { node.span() => {
+                    ::robusta_jni::convert::with_local_frame(&env, #capacity, || {
+                        Ok::<_, ::robusta_jni::jni::errors::Error>(::robusta_jni::convert::IntoJavaValue::into(#method_call, &env))
+                    })
+                    .unwrap()
+                }},
+                None => parse_quote_spanned! { node.span() => {
                     ::robusta_jni::convert::IntoJavaValue::into(#method_call, &env)
-                }}
-            }
+                }},
+            },
 
             CallType::Safe(exception_details) => {
                 let outer_call_inputs = {
@@ -128,10 +446,11 @@ impl<'ctx> Fold for ExternJNIMethodTransformer<'ctx> {
                     s.output = ReturnType::Type(
                         Token![->](outer_signature_span),
                         Box::new(
-                            parse_quote_spanned!(outer_output_type.span() => ::robusta_jni::jni::errors::Result<#outer_output_type>),
+                            parse_quote_spanned!(outer_output_type.span() => ::std::result::Result<#outer_output_type, ::std::boxed::Box<dyn ::std::error::Error>>),
                         ),
                     );
                     s.abi = None;
+                    s.unsafety = None;
                     s
                 };
 
@@ -139,10 +458,11 @@ impl<'ctx> Fold for ExternJNIMethodTransformer<'ctx> {
                     "java.lang.RuntimeException".parse().unwrap(),
                     "JNI call error!",
                 );
-                let (exception_class, message) = match exception_details {
+                let (exception_class, message) = match exception_details.as_deref() {
                     Some(SafeParams {
                         exception_class,
                         message,
+                        ..
                     }) => {
                         let exception_class_result =
                             exception_class.as_ref().unwrap_or(&default_exception_class);
@@ -153,29 +473,208 @@ impl<'ctx> Fold for ExternJNIMethodTransformer<'ctx> {
                     None => (&default_exception_class, default_message),
                 };
 
-                let exception_classpath_path = exception_class.to_classpath_path();
+                let exception_class_const = exception_details
+                    .as_ref()
+                    .and_then(|p| p.exception_class_const.as_ref());
+
+                let derive_class = self.call_type.derive_class();
+
+                /* A class name given through a constant is only known at runtime, so it's converted to a classpath there */
+                let exception_classpath_path: Expr = match (exception_class_const, derive_class) {
+                    (_, Some(package)) => {
+                        if exception_details.as_ref().is_some_and(|p| {
+                            p.exception_class.is_some() || p.exception_class_const.is_some()
+                        }) {
+                            emit_error!(
+                                node.sig.output,
+                                "can't have `derive_class` together with `exception_class` or `exception_class_const`"
+                            );
+                        }
 
-                parse_quote_spanned! { node.span() => {
-                    #outer_signature {
-                        ::robusta_jni::convert::TryIntoJavaValue::try_into(#method_call, &env)
+                        let package_name = package.to_string();
+                        if package_name.is_empty() || package_name.contains('$') {
+                            emit_error!(
+                                node.sig.output,
+                                "invalid `derive_class` package `{}`", package_name;
+                                help = "use the package of the exception classes, e.g. `derive_class = \"com.example.errors\"`"
+                            );
+                        }
+
+                        let error_name = match &node.sig.output {
+                            ReturnType::Type(_, ty) => result_type_parts(ty).map(|(_, e)| e),
+                            ReturnType::Default => None,
+                        };
+
+                        match error_name {
+                            Some(error_name) => {
+                                let classpath_path =
+                                    package.class_classpath_path(&error_name.to_string());
+                                parse_quote! { #classpath_path }
+                            }
+                            None => {
+                                emit_error!(
+                                    node.sig.output,
+                                    "`derive_class` requires a `Result<T, E>` return type naming the error type `E`"
+                                );
+                                parse_quote! { "" }
+                            }
+                        }
                     }
+                    (Some(path), None) => {
+                        if let Some(SafeParams {
+                            exception_class: Some(_),
+                            ..
+                        }) = exception_details.as_deref()
+                        {
+                            emit_error!(
+                                path,
+                                "can't have both `exception_class` and `exception_class_const`"
+                            );
+                        }
 
-                    match outer(#outer_call_inputs) {
-                        Ok(result) => result,
-                        Err(e) => {
-                            let r = env.throw_new(#exception_classpath_path, format!("{}. Cause: {}", #message, e));
+                        parse_quote_spanned! { path.span() => #path.replace('.', "/") }
+                    }
+                    (None, None) => {
+                        let classpath_path = exception_class.to_classpath_path();
+                        parse_quote! { #classpath_path }
+                    }
+                };
+
+                /* Capturing a backtrace is expensive, so it's only done when asked for */
+                let exception_message: Expr = if exception_details
+                    .as_ref()
+                    .is_some_and(|p| p.backtrace())
+                {
+                    parse_quote! {
+                        format!("{}. Cause: {}\nRust backtrace:\n{}", #message, e, ::std::backtrace::Backtrace::force_capture())
+                    }
+                } else {
+                    parse_quote! { format!("{}. Cause: {}", #message, e) }
+                };
+
+                /* With `log_to`, the exception is logged with the logger of the bridged class before being thrown */
+                let throw_new: Expr = match exception_details
+                    .as_ref()
+                    .and_then(|p| p.log_to.as_ref())
+                {
+                    Some(logger_factory) => {
+                        let logger_factory = logger_factory.to_classpath_path();
+                        let logger_name = match (&self.class_alias, &self.struct_context.package) {
+                            (Some(alias), _) => alias.to_string(),
+                            (None, Some(package)) => package
+                                .class_classpath_path(&self.struct_context.struct_name)
+                                .replace('/', "."),
+                            (None, None) => self.struct_context.struct_name.clone(),
+                        };
+
+                        parse_quote! {
+                            ::robusta_jni::convert::throw_new_logged(&env, &#exception_classpath_path, &#exception_message, #logger_factory, #logger_name)
+                        }
+                    }
+                    None => {
+                        parse_quote! { env.throw_new(#exception_classpath_path, #exception_message) }
+                    }
+                };
+
+                /* If an exception is already pending (e.g. raised by a Java call made during a conversion)
+                 * we must not throw a new one on top of it, so we let the pending one propagate instead.
+                 */
+                let throw_exception: Stmt = if jni_signature.check_pending() {
+                    parse_quote! {
+                        if !env.exception_check().unwrap_or(false) {
+                            let r = #throw_new;
 
                             if let Err(e) = r {
                                 println!("Error while throwing Java exception: {}", e);
                             }
+                        }
+                    }
+                } else {
+                    /* A `JavaException` error means that an exception has already been thrown (e.g. rethrowing a `JThrowable`) */
+                    parse_quote! {
+                        if !matches!(e.downcast_ref(), Some(::robusta_jni::jni::errors::Error::JavaException)) {
+                            if let Err(e) = #throw_new {
+                                println!("Error while throwing Java exception: {}", e);
+                            }
+                        }
+                    }
+                };
+
+                let (anyhow_result, unit_result) = match &node.sig.output {
+                    ReturnType::Type(_, ty) => (
+                        anyhow_result_ok_type(ty).is_some(),
+                        unit_result_ok_type(ty).is_some(),
+                    ),
+                    ReturnType::Default => (false, false),
+                };
 
-                            /* We never hand out Rust references and the object returned is ignored
-                             * by the JVM, so it should be safe to just return zeroed memory.
-                             * Also, all primitives have a valid zero representation and because objects
-                             * are represented as pointers this should not have any unsafe side effects.
-                             * (Uninitialized memory would probably work as well)
+                /* With `derive_class`, an `anyhow` or a `()` error the `Err` variant is thrown by the wrapper, and only the `Ok` value is converted */
+                let returned_value: Expr = if anyhow_result {
+                    parse_quote! {
+                        match #method_call {
+                            Ok(v) => v,
+                            Err(e) => return Err(::std::boxed::Box::new(::robusta_jni::convert::AnyhowError::from(e))),
+                        }
+                    }
+                } else if unit_result {
+                    parse_quote! {
+                        match #method_call {
+                            Ok(v) => v,
+                            Err(()) => return Err(::std::boxed::Box::from("method returned `Err(())`")),
+                        }
+                    }
+                } else if derive_class.is_some() {
+                    parse_quote! {
+                        match #method_call {
+                            Ok(v) => v,
+                            Err(e) => return Err(::std::boxed::Box::from(e)),
+                        }
+                    }
+                } else {
+                    method_call
+                };
+
+                let output_conversion: Expr = match self.call_type.conversion_context() {
+                    Some(context) => parse_quote_spanned! { context.span() =>
+                        ::robusta_jni::convert::TryIntoJavaValueCtx::try_into_ctx(#returned_value, &env, &#context)
+                    },
+                    None => parse_quote! {
+                        ::robusta_jni::convert::TryIntoJavaValue::try_into(#returned_value, &env)
+                    },
+                };
+
+                /* A failed conversion of the `Ok` value must not be thrown as one of the method's own errors */
+                let output_conversion: Expr = if anyhow_result
+                    || unit_result
+                    || derive_class.is_some()
+                {
+                    parse_quote! { ::robusta_jni::convert::report_return_conversion(&env, #output_conversion)? }
+                } else {
+                    parse_quote! { #output_conversion? }
+                };
+
+                let outer_call: Expr = match local_frame_capacity {
+                    Some(capacity) => parse_quote! {
+                        ::robusta_jni::convert::with_local_frame(&env, #capacity, || outer(#outer_call_inputs))
+                    },
+                    None => parse_quote! { outer(#outer_call_inputs) },
+                };
+
+                parse_quote_spanned! { node.span() => {
+                    #inline_attribute
+                    #outer_signature {
+                        Ok(#output_conversion)
+                    }
+
+                    match #outer_call {
+                        Ok(result) => result,
+                        Err(e) => {
+                            #throw_exception
+
+                            /* The returned value is ignored by the JVM when an exception is pending,
+                             * but it must still be a valid one: zero for primitives and `null` for objects.
                              */
-                            unsafe { ::std::mem::zeroed() }
+                            ::robusta_jni::convert::DefaultJavaValue::default_java_value()
                         }
                     }
                 }}
@@ -190,6 +689,8 @@ impl<'ctx> Fold for ExternJNIMethodTransformer<'ctx> {
             let discarded_known_attributes: HashSet<&str> = {
                 let mut h = HashSet::new();
                 h.insert("call_type");
+                h.insert("hot");
+                h.insert("local_frame");
                 h
             };
 
@@ -223,26 +724,14 @@ impl<'ctx> Fold for ExternJNIMethodTransformer<'ctx> {
             emit_error!(sig.ident, "JNI methods cannot contain `_` character");
         }
 
-        let jni_method_name = {
-            let snake_case_package = self
-                .struct_context
-                .package
-                .as_ref()
-                .map(|s| s.to_snake_case())
-                .unwrap_or_else(|| "".into());
-
-            [
-                "Java",
-                &snake_case_package,
-                &self.struct_context.struct_name,
-                &sig.ident.to_string(),
-            ]
-            .iter()
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_owned())
-            .collect::<Vec<_>>()
-            .join("_")
-        };
+        if let Some(asyncness) = &node.asyncness {
+            emit_error!(asyncness, "JNI methods must be synchronous, `async fn` can't be exported";
+                help = "return a `java.util.concurrent.CompletableFuture` and complete it from another thread instead");
+        }
+
+        let jni_method_name = self.jni_function_name(
+            &(sig.ident.to_string() + self.method_suffix.as_deref().unwrap_or("")),
+        );
 
         sig.inputs = {
             let mut res = Punctuated::new();
@@ -273,7 +762,8 @@ mod test {
     use proc_macro2::TokenStream;
 
     use super::*;
-    use crate::transformation::JavaPath;
+    use crate::transformation::context::struct_context;
+    use crate::transformation::BridgeParams;
 
     fn setup_package(
         package: Option<JavaPath>,
@@ -288,15 +778,17 @@ mod test {
         let struct_context = StructContext {
             struct_type: parse_quote! { #struct_name_token_stream },
             struct_name,
-            struct_lifetimes: vec![],
             package,
+            ..struct_context()
         };
         let mut transformer = ExternJNIMethodTransformer {
             struct_context: &struct_context,
             call_type: CallType::Safe(None),
+            method_suffix: None,
+            class_alias: None,
         };
 
-        transformer.fold_impl_item_method(method)
+        transformer.fold_impl_item_fn(method)
     }
 
     #[test]
@@ -322,6 +814,62 @@ mod test {
             output_with_package.sig.ident.to_string(),
             format!("Java_com_bar_quux_Foo_foo")
         );
+
+        let output_default_package = setup_package(
+            Some(JavaPath::from_str("").unwrap()),
+            "Foo".into(),
+            "foo".into(),
+        );
+        assert_eq!(
+            output_default_package.sig.ident.to_string(),
+            format!("Java_Foo_foo")
+        );
+    }
+
+    #[test]
+    fn jni_method_naming_scheme_for_kotlin_classes() {
+        let output_file_class = setup_package(
+            Some(JavaPath::from_str("com.example").unwrap()),
+            "FooKt".into(),
+            "foo".into(),
+        );
+        assert_eq!(
+            output_file_class.sig.ident.to_string(),
+            "Java_com_example_FooKt_foo"
+        );
+
+        let output_companion = setup_package(
+            Some(JavaPath::from_str("com.example.Foo$").unwrap()),
+            "Companion".into(),
+            "foo".into(),
+        );
+        assert_eq!(
+            output_companion.sig.ident.to_string(),
+            "Java_com_example_Foo_00024Companion_foo"
+        );
+    }
+
+    #[test]
+    fn jni_method_name_has_symbol_affixes() {
+        let method: ImplItemFn = parse_quote! { pub extern "jni" fn foo() {} };
+        let struct_context = StructContext {
+            package: Some(JavaPath::from_str("com.bar").unwrap()),
+            bridge_params: BridgeParams {
+                symbol_prefix: Some("libA_".into()),
+                symbol_suffix: Some("V2".into()),
+                ..Default::default()
+            },
+            ..struct_context()
+        };
+        let mut transformer = ExternJNIMethodTransformer {
+            struct_context: &struct_context,
+            call_type: CallType::Safe(None),
+            method_suffix: None,
+            class_alias: None,
+        };
+
+        let output = transformer.fold_impl_item_fn(method);
+        assert_eq!(output.sig.ident.to_string(), "Java_com_bar_Foo_libA_1fooV2");
     }
 
     #[test]
@@ -350,15 +898,17 @@ mod test {
         let struct_context = StructContext {
             struct_type: parse_quote! { #struct_name_token_stream },
             struct_name,
-            struct_lifetimes: vec![],
             package,
+            ..struct_context()
         };
         let mut transformer = ExternJNIMethodTransformer {
             struct_context: &struct_context,
             call_type: CallType::Safe(None),
+            method_suffix: None,
+            class_alias: None,
         };
 
-        transformer.fold_impl_item_method(method)
+        transformer.fold_impl_item_fn(method)
     }
 
     #[test]
@@ -447,24 +997,545 @@ mod test {
             _ => assert!(false),
         }
     }
+
+    fn setup_with_call_type(params: TokenStream, call_type: CallType) -> ImplItemFn {
+        let method: ImplItemFn = parse_quote! {
+            pub extern "jni" fn foo(#params) -> i32 {}
+        };
+
+        let struct_context = struct_context();
+        let mut transformer = ExternJNIMethodTransformer {
+            struct_context: &struct_context,
+            call_type,
+            method_suffix: None,
+            class_alias: None,
+        };
+
+        transformer.fold_impl_item_fn(method)
+    }
+
+    #[test]
+    fn env_is_passed_only_when_declared() {
+        use quote::quote;
+
+        let with_env =
+            setup_with_call_type(quote! { env: &JNIEnv<'env>, a: i32 }, CallType::Safe(None));
+        let without_env = setup_with_call_type(quote! { a: i32 }, CallType::Safe(None));
+
+        assert!(with_env
+            .block
+            .to_token_stream()
+            .to_string()
+            .contains("Foo :: foo (& env , a)"));
+        assert!(without_env
+            .block
+            .to_token_stream()
+            .to_string()
+            .contains("Foo :: foo (a)"));
+    }
+
+    #[test]
+    fn reference_parameters_borrow_the_converted_value() {
+        use quote::quote;
+
+        let output = setup_with_call_type(quote! { a: &Bar, b: i32 }, CallType::Safe(None));
+        let block = output.block.to_token_stream().to_string();
+        let inputs = output.sig.inputs.to_token_stream().to_string();
+
+        assert!(block.contains("Foo :: foo (& a , b)"));
+        assert!(inputs.contains("a : < Bar as :: robusta_jni :: convert :: TryFromJavaValue"));
+    }
+
+    #[test]
+    fn critical_arrays_are_pinned_together() {
+        use quote::quote;
+
+        let output = setup_with_call_type(
+            quote! { a: CriticalByteArray<'borrow>, n: i32, b: CriticalByteArray<'borrow> },
+            CallType::Safe(None),
+        );
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains(
+            "let [a , b] = :: robusta_jni :: convert :: CriticalByteArray :: pin_all (& env , [a , b]) ?"
+        ));
+        assert!(
+            !block.contains("let a = :: robusta_jni :: convert :: TryFromJavaValue :: try_from (a")
+        );
+        assert!(
+            block.contains("let n = :: robusta_jni :: convert :: TryFromJavaValue :: try_from (n")
+        );
+        assert!(block.contains("Foo :: foo (a , n , b)"));
+    }
+
+    #[test]
+    fn critical_array_is_pinned_last() {
+        use quote::quote;
+
+        let output = setup_with_call_type(
+            quote! { a: CriticalByteArray<'borrow>, n: i32 },
+            CallType::Safe(None),
+        );
+        let block = output.block.to_token_stream().to_string();
+
+        let convert_n = block
+            .find("let n = :: robusta_jni :: convert :: TryFromJavaValue :: try_from (n")
+            .unwrap();
+        let exception_check = block.find("exception_check").unwrap();
+        let pin_a = block
+            .find("let [a] = :: robusta_jni :: convert :: CriticalByteArray :: pin_all (& env , [a]) ?")
+            .unwrap();
+        assert!(convert_n < exception_check);
+        assert!(exception_check < pin_a);
+        assert!(
+            !block.contains("let a = :: robusta_jni :: convert :: TryFromJavaValue :: try_from (a")
+        );
+        assert!(block.contains("Foo :: foo (a , n)"));
+    }
+
+    #[test]
+    fn safe_method_checks_pending_exceptions_by_default() {
+        use quote::quote;
+
+        let output = setup_with_call_type(quote! { a: i32 }, CallType::Safe(None));
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains("exception_check"));
+    }
+
+    #[test]
+    fn safe_method_skips_pending_exception_checks() {
+        use quote::quote;
+
+        let call_type = CallType::Safe(Some(Box::new(SafeParams {
+            check_pending: Some(false),
+            ..Default::default()
+        })));
+        let output = setup_with_call_type(quote! { a: i32 }, call_type);
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(!block.contains("exception_check"));
+        assert!(block.contains("Error :: JavaException"));
+    }
+
+    #[test]
+    fn safe_method_appends_backtrace_only_when_enabled() {
+        use quote::quote;
+
+        let output = setup_with_call_type(quote! { a: i32 }, CallType::Safe(None));
+        let block = output.block.to_token_stream().to_string();
+        assert!(!block.contains("Backtrace"));
+
+        let call_type = CallType::Safe(Some(Box::new(SafeParams {
+            backtrace: Some(true),
+            ..Default::default()
+        })));
+        let output = setup_with_call_type(quote! { a: i32 }, call_type);
+        let block = output.block.to_token_stream().to_string();
+        assert!(block.contains(":: std :: backtrace :: Backtrace :: force_capture ()"));
+    }
+
+    #[test]
+    fn safe_method_exception_class_from_constant() {
+        use quote::quote;
+
+        let call_type = CallType::Safe(Some(Box::new(SafeParams {
+            exception_class_const: Some(parse_quote! { crate::errors::CLASS }),
+            ..Default::default()
+        })));
+        let output = setup_with_call_type(quote! { a: i32 }, call_type);
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains("throw_new (crate :: errors :: CLASS . replace"));
+    }
+
+    #[test]
+    fn safe_method_logs_exceptions_before_throwing() {
+        use quote::quote;
+
+        let call_type = CallType::Safe(Some(Box::new(SafeParams {
+            log_to: Some("org.slf4j.LoggerFactory".parse().unwrap()),
+            ..Default::default()
+        })));
+        let output = setup_with_call_type(quote! { a: i32 }, call_type);
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains(
+            "throw_new_logged (& env , & \"java/lang/RuntimeException\" , & format ! (\"{}. Cause: {}\" , \"JNI call error!\" , e) , \"org/slf4j/LoggerFactory\" , \"Foo\")"
+        ));
+        assert!(!block.contains("env . throw_new"));
+    }
+
+    #[test]
+    fn safe_method_with_conversion_context() {
+        use quote::quote;
+
+        let call_type = CallType::Safe(Some(Box::new(SafeParams {
+            with_context: Some(parse_quote! { crate::CONTEXT }),
+            ..Default::default()
+        })));
+        let output = setup_with_call_type(quote! { a: i32, #[unchecked] b: i32 }, call_type);
+        let block = output.block.to_token_stream().to_string();
+        let signature = output.sig.to_token_stream().to_string();
+
+        assert!(
+            block.contains("TryFromJavaValueCtx :: try_from_ctx (a , & env , & crate :: CONTEXT)")
+        );
+        assert!(block.contains("FromJavaValue :: from (b , & env)"));
+        assert!(block.contains("TryIntoJavaValueCtx :: try_into_ctx"));
+        assert!(signature.contains("a : < i32 as :: robusta_jni :: convert :: TryFromJavaValueCtx"));
+        assert!(signature.contains(
+            "-> < i32 as :: robusta_jni :: convert :: TryIntoJavaValueCtx < 'env > > :: Target"
+        ));
+    }
+
+    #[test]
+    fn safe_method_with_derived_exception_class() {
+        let method: ImplItemFn = parse_quote! {
+            pub extern "jni" fn foo(a: i32) -> Result<i32, errors::ParseError> {}
+        };
+        let struct_context = struct_context();
+        let call_type = CallType::Safe(Some(Box::new(SafeParams {
+            derive_class: Some("com.example.errors".parse().unwrap()),
+            ..Default::default()
+        })));
+        let mut transformer = ExternJNIMethodTransformer::new(&struct_context, call_type);
+
+        let output = transformer.fold_impl_item_fn(method);
+        let block = output.block.to_token_stream().to_string();
+        let signature = output.sig.to_token_stream().to_string();
+
+        assert!(block.contains("throw_new (\"com/example/errors/ParseError\""));
+        assert!(block.contains("Err (e) => return Err (:: std :: boxed :: Box :: from (e))"));
+        assert!(block.contains(":: robusta_jni :: convert :: report_return_conversion (& env ,"));
+        assert!(signature.contains(
+            "-> < i32 as :: robusta_jni :: convert :: TryIntoJavaValue < 'env > > :: Target"
+        ));
+    }
+
+    #[test]
+    fn safe_method_with_anyhow_error() {
+        let struct_context = struct_context();
+
+        for method in [
+            parse_quote! { pub extern "jni" fn foo(a: i32) -> anyhow::Result<i32> {} },
+            parse_quote! { pub extern "jni" fn foo(a: i32) -> Result<i32, anyhow::Error> {} },
+        ] {
+            let mut transformer =
+                ExternJNIMethodTransformer::new(&struct_context, CallType::Safe(None));
+
+            let output = transformer.fold_impl_item_fn(method);
+            let block = output.block.to_token_stream().to_string();
+            let signature = output.sig.to_token_stream().to_string();
+
+            assert!(block.contains("throw_new (\"java/lang/RuntimeException\""));
+            assert!(
+                block.contains("Box :: new (:: robusta_jni :: convert :: AnyhowError :: from (e))")
+            );
+            assert!(signature.contains(
+                "-> < i32 as :: robusta_jni :: convert :: TryIntoJavaValue < 'env > > :: Target"
+            ));
+        }
+    }
+
+    #[test]
+    fn safe_method_with_unit_error() {
+        let struct_context = struct_context();
+
+        let mut transformer =
+            ExternJNIMethodTransformer::new(&struct_context, CallType::Safe(None));
+
+        let output = transformer.fold_impl_item_fn(
+            parse_quote! { pub extern "jni" fn foo(a: i32) -> Result<i32, ()> {} },
+        );
+        let block = output.block.to_token_stream().to_string();
+        let signature = output.sig.to_token_stream().to_string();
+
+        assert!(block.contains("throw_new (\"java/lang/RuntimeException\""));
+        assert!(block.contains("Err (()) => return Err"));
+        assert!(signature.contains(
+            "-> < i32 as :: robusta_jni :: convert :: TryIntoJavaValue < 'env > > :: Target"
+        ));
+    }
+
+    #[test]
+    fn safe_method_conversion_errors_name_the_argument() {
+        use quote::quote;
+
+        let output = setup_with_call_type(quote! { a: i32, count: i32 }, CallType::Safe(None));
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains("ArgumentConversionError :: new (\"count\" , 2usize , e)"));
+    }
+
+    #[test]
+    fn safe_method_with_defaulted_argument() {
+        use quote::quote;
+
+        let output = setup_with_call_type(
+            quote! { a: i32, #[default_on_error] b: i32 },
+            CallType::Safe(None),
+        );
+        let block = output.block.to_token_stream().to_string();
+        let inputs = output.sig.inputs.to_token_stream().to_string();
+
+        assert!(block.contains("ArgumentConversionError :: new (\"a\" , 1usize , e)"));
+        assert!(!block.contains("ArgumentConversionError :: new (\"b\""));
+        assert!(block.contains("try_from (b , & env) . unwrap_or_else"));
+        assert!(block.contains("env . exception_clear ()"));
+        assert!(!inputs.contains("default_on_error"));
+    }
+
+    #[test]
+    fn safe_method_with_unchecked_argument() {
+        use quote::quote;
+
+        let output =
+            setup_with_call_type(quote! { a: i32, #[unchecked] b: i32 }, CallType::Safe(None));
+        let block = output.block.to_token_stream().to_string();
+        let inputs = output.sig.inputs.to_token_stream().to_string();
+
+        assert!(block.contains("TryFromJavaValue :: try_from (a , & env)"));
+        assert!(block.contains("FromJavaValue :: from (b , & env)"));
+        assert!(!block.contains("try_from (b"));
+        assert!(inputs.contains("b : < i32 as :: robusta_jni :: convert :: FromJavaValue"));
+        assert!(!inputs.contains("unchecked"));
+    }
+
+    #[test]
+    fn hot_method_inlines_conversion_glue() {
+        let method: ImplItemFn = parse_quote! {
+            #[hot]
+            pub extern "jni" fn foo(a: i32) -> i32 {}
+        };
+        let struct_context = struct_context();
+        let mut transformer = ExternJNIMethodTransformer {
+            struct_context: &struct_context,
+            call_type: CallType::Safe(None),
+            method_suffix: None,
+            class_alias: None,
+        };
+
+        let output = transformer.fold_impl_item_fn(method);
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains("# [inline (always)] fn outer"));
+        assert!(!output.attrs.iter().any(|a| a.path().is_ident("hot")));
+        assert_eq!(output.sig.ident.to_string(), "Java_Foo_foo");
+    }
+
+    #[test]
+    fn local_frame_method_runs_in_its_own_frame() {
+        let struct_context = struct_context();
+
+        for call_type in [CallType::Safe(None), CallType::Unchecked(Flag::default())] {
+            let method: ImplItemFn = parse_quote! {
+                #[local_frame(capacity = 256)]
+                pub extern "jni" fn foo(a: i32) -> i32 {}
+            };
+            let mut transformer = ExternJNIMethodTransformer {
+                struct_context: &struct_context,
+                call_type,
+                method_suffix: None,
+                class_alias: None,
+            };
+
+            let output = transformer.fold_impl_item_fn(method);
+            let block = output.block.to_token_stream().to_string();
+
+            assert!(block.contains("with_local_frame (& env , 256i32 , | |"));
+            assert!(!output
+                .attrs
+                .iter()
+                .any(|a| a.path().is_ident("local_frame")));
+        }
+    }
+
+    #[test]
+    fn safe_method_returns_default_value_after_throwing() {
+        use quote::quote;
+
+        let output = setup_with_call_type(quote! { a: i32 }, CallType::Safe(None));
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains(
+            "Err (e) => { if ! env . exception_check () . unwrap_or (false) { let r = env . throw_new"
+        ));
+        assert!(block
+            .contains(":: robusta_jni :: convert :: DefaultJavaValue :: default_java_value ()"));
+        assert!(!block.contains("zeroed"));
+    }
+
+    #[test]
+    fn unsafe_method_keeps_unsafe_qualifier() {
+        let method: ImplItemFn = parse_quote! {
+            pub unsafe extern "jni" fn foo(a: i32) -> i32 {}
+        };
+        let struct_context = struct_context();
+        let mut transformer = ExternJNIMethodTransformer {
+            struct_context: &struct_context,
+            call_type: CallType::Safe(None),
+            method_suffix: None,
+            class_alias: None,
+        };
+
+        let output = transformer.fold_impl_item_fn(method);
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(output.sig.unsafety.is_some());
+        assert!(block.contains("fn outer"));
+        assert!(!block.contains("unsafe fn outer"));
+        assert!(block.contains("unsafe { { let a ="));
+    }
+
+    #[test]
+    fn self_return_type_is_replaced_with_struct_type() {
+        let method: ImplItemFn = parse_quote! {
+            pub extern "jni" fn foo(self, other: Self) -> JniResult<Self> {}
+        };
+
+        let struct_context = struct_context();
+        let mut transformer = ExternJNIMethodTransformer {
+            struct_context: &struct_context,
+            call_type: CallType::Safe(None),
+            method_suffix: None,
+            class_alias: None,
+        };
+
+        let output = transformer.fold_impl_item_fn(method);
+        let signature = output.sig.to_token_stream().to_string();
+
+        assert!(!signature.contains("Self"));
+        assert!(
+            signature.contains("other : < Foo as :: robusta_jni :: convert :: TryFromJavaValue")
+        );
+        assert!(signature.contains("-> < JniResult < Foo > as :: robusta_jni :: convert :: TryIntoJavaValue < 'env > > :: Target"));
+    }
+
+    #[test]
+    fn receiver_conversion_error_names_the_receiver() {
+        let method: ImplItemFn = parse_quote! {
+            pub extern "jni" fn foo(&self) -> i32 {}
+        };
+
+        let struct_context = StructContext {
+            receiver: ReceiverStrategy::NativePointer("handle".into()),
+            ..struct_context()
+        };
+        let mut transformer = ExternJNIMethodTransformer {
+            struct_context: &struct_context,
+            call_type: CallType::Safe(None),
+            method_suffix: None,
+            class_alias: None,
+        };
+
+        let output = transformer.fold_impl_item_fn(method);
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains("ArgumentConversionError :: new (\"receiver\" , 0 , e)"));
+
+        let pojo = setup_with_call_type(quote::quote! { self }, CallType::Safe(None));
+        let block = pojo.block.to_token_stream().to_string();
+
+        assert!(block.contains("ArgumentConversionError :: new (\"receiver\" , 0usize , e)"));
+        assert!(!block.contains("\"self\""));
+    }
+
+    #[test]
+    fn both_call_types_export_two_variants() {
+        let method: ImplItemFn = parse_quote! {
+            #[call_type(both(safe_suffix = "Checked", unchecked_suffix = "Fast"))]
+            pub extern "jni" fn foo(a: i32) -> i32 {}
+        };
+        let struct_context = struct_context();
+        let mut transformer = ExportedMethodTransformer {
+            struct_context: &struct_context,
+        };
+
+        let output = transformer.transform_impl_item_fn(method);
+        assert_eq!(output.len(), 2);
+
+        let (checked, fast) = (&output[0], &output[1]);
+        assert_eq!(checked.sig.ident.to_string(), "Java_Foo_fooChecked");
+        assert_eq!(fast.sig.ident.to_string(), "Java_Foo_fooFast");
+        assert!(checked
+            .block
+            .to_token_stream()
+            .to_string()
+            .contains("TryIntoJavaValue :: try_into"));
+        assert!(fast
+            .block
+            .to_token_stream()
+            .to_string()
+            .contains("IntoJavaValue :: into"));
+        assert!(output.iter().all(|f| f
+            .block
+            .to_token_stream()
+            .to_string()
+            .contains("Foo :: foo (")));
+    }
+
+    #[test]
+    fn jni_signature_is_synchronous() {
+        let signature: Signature = parse_quote! { async extern "jni" fn foo(a: i32) -> i32 };
+        let jni_signature = JNISignature::new(signature, &struct_context(), CallType::Safe(None));
+
+        assert!(jni_signature.transformed_signature.asyncness.is_none());
+    }
+
+    #[test]
+    fn env_reference_lifetime_in_return_type() {
+        let top_level: Type = parse_quote! { &'env str };
+        let in_result: Type = parse_quote! { JniResult<&'env str> };
+        let in_option: Type = parse_quote! { Option<&'env Foo> };
+        let slice_in_path: Type = parse_quote! { Vec<&'env [i32]> };
+
+        for ty in [top_level, in_result, in_option, slice_in_path] {
+            assert_eq!(
+                env_reference_lifetime(&ty).map(|l| l.ident.to_string()),
+                Some("env".to_string())
+            );
+        }
+
+        let borrowed: Type = parse_quote! { JniResult<&'borrow str> };
+        let env_object: Type = parse_quote! { JObject<'env> };
+        assert!(env_reference_lifetime(&borrowed).is_none());
+        assert!(env_reference_lifetime(&env_object).is_none());
+    }
 }
 
 struct JNISignatureTransformer {
     struct_freestanding_transformer: FreestandingTransformer,
     struct_lifetimes: Vec<LifetimeParam>,
+    struct_where_clause: Option<WhereClause>,
     call_type: CallType,
+    receiver_strategy: ReceiverStrategy,
+    unchecked_args: HashSet<Ident>,
+    defaulted_args: HashSet<Ident>,
+    borrowed_args: HashSet<Ident>,
+    critical_args: Vec<Ident>,
+    receiver_kind: Option<ReceiverKind>,
 }
 
 impl JNISignatureTransformer {
     fn new(
         struct_freestanding_transformer: FreestandingTransformer,
         struct_lifetimes: Vec<LifetimeParam>,
+        struct_where_clause: Option<WhereClause>,
         call_type: CallType,
+        receiver_strategy: ReceiverStrategy,
     ) -> Self {
         JNISignatureTransformer {
             struct_freestanding_transformer,
             struct_lifetimes,
+            struct_where_clause,
             call_type,
+            receiver_strategy,
+            unchecked_args: HashSet::new(),
+            defaulted_args: HashSet::new(),
+            borrowed_args: HashSet::new(),
+            critical_args: Vec::new(),
+            receiver_kind: None,
         }
     }
 
@@ -477,6 +1548,15 @@ impl JNISignatureTransformer {
                 .map(GenericParam::Lifetime),
         );
 
+        /* Bounds of the impl block must hold for the JNI functions as well, as they call its methods */
+        if let Some(struct_where_clause) = self.struct_where_clause.clone() {
+            let predicates = struct_where_clause
+                .predicates
+                .into_iter()
+                .map(|p| self.struct_freestanding_transformer.fold_where_predicate(p));
+            generics.make_where_clause().predicates.extend(predicates);
+        }
+
         let (env_lifetime, borrow_lifetime) = generics.params.iter_mut().fold((None, None), |acc, l| {
             match l {
                 GenericParam::Lifetime(l) => {
@@ -553,14 +1633,75 @@ impl JNISignatureTransformer {
 
 impl Fold for JNISignatureTransformer {
     fn fold_fn_arg(&mut self, arg: FnArg) -> FnArg {
+        /* With a `#[receiver]` strategy other than `pojo`, the receiver is passed as the raw object and reconstructed by the wrapper */
+        let receiver_kind = match (&self.receiver_strategy, &arg) {
+            (ReceiverStrategy::Pojo, _) => None,
+            (_, FnArg::Receiver(r)) => Some(match &*r.ty {
+                Type::Reference(TypeReference {
+                    mutability: Some(_),
+                    ..
+                }) => ReceiverKind::Mut,
+                Type::Reference(_) => ReceiverKind::Shared,
+                _ => ReceiverKind::Owned,
+            }),
+            _ => None,
+        };
+
         match self.struct_freestanding_transformer.fold_fn_arg(arg) {
             FnArg::Receiver(_) => panic!("Bug -- please report to library author. Found receiver input after freestanding conversion"),
+            FnArg::Typed(t) if receiver_kind.is_some() => {
+                self.receiver_kind = receiver_kind;
+
+                FnArg::Typed(PatType {
+                    ty: Box::new(parse_quote_spanned! { t.ty.span() => ::robusta_jni::jni::objects::JObject<'env> }),
+                    ..t
+                })
+            }
             FnArg::Typed(mut t) => {
-                let original_input_type = t.ty;
+                let original_input_type = match *t.ty {
+                    /* Shared references are converted as the referenced type, and borrowed from a local for the call */
+                    Type::Reference(TypeReference {
+                        mutability: None,
+                        elem,
+                        ..
+                    }) if matches!(*elem, Type::Path(_)) => {
+                        if let Pat::Ident(PatIdent { ident, .. }) = t.pat.as_ref() {
+                            self.borrowed_args.insert(ident.clone());
+                        }
+
+                        elem
+                    }
+                    ty => Box::new(ty),
+                };
+
+                if is_critical_byte_array(&original_input_type) {
+                    if let Pat::Ident(PatIdent { ident, .. }) = t.pat.as_ref() {
+                        self.critical_args.push(ident.clone());
+                    }
+                }
+
+                // `#[unchecked]` arguments are converted with `FromJavaValue` even in safe methods
+                let unchecked = t.attrs.iter().any(is_unchecked_attribute);
+                if unchecked {
+                    if let Pat::Ident(PatIdent { ident, .. }) = t.pat.as_ref() {
+                        self.unchecked_args.insert(ident.clone());
+                    }
+                }
+
+                // `#[default_on_error]` arguments that fail to convert are replaced with `Default::default()`
+                if let Some(attr) = t.attrs.iter().find(|a| is_default_on_error_attribute(a)) {
+                    if unchecked {
+                        emit_error!(attr, "can't have both `#[default_on_error]` and `#[unchecked]` on an argument";
+                            note = "`#[unchecked]` arguments panic on conversion failure")
+                    } else if let Pat::Ident(PatIdent { ident, .. }) = t.pat.as_ref() {
+                        self.defaulted_args.insert(ident.clone());
+                    }
+                }
 
                 let jni_conversion_type: Type = match self.call_type {
-                    CallType::Safe(_) => parse_quote_spanned! { original_input_type.span() => <#original_input_type as ::robusta_jni::convert::TryFromJavaValue<'env, 'borrow>>::Source },
-                    CallType::Unchecked { .. } => parse_quote_spanned! { original_input_type.span() => <#original_input_type as ::robusta_jni::convert::FromJavaValue<'env, 'borrow>>::Source },
+                    CallType::Safe(_) if !unchecked && self.call_type.conversion_context().is_some() => parse_quote_spanned! { original_input_type.span() => <#original_input_type as ::robusta_jni::convert::TryFromJavaValueCtx<'env, 'borrow>>::Source },
+                    CallType::Safe(_) if !unchecked => parse_quote_spanned! { original_input_type.span() => <#original_input_type as ::robusta_jni::convert::TryFromJavaValue<'env, 'borrow>>::Source },
+                    _ => parse_quote_spanned! { original_input_type.span() => <#original_input_type as ::robusta_jni::convert::FromJavaValue<'env, 'borrow>>::Source },
                 };
 
                 if let Pat::Ident(PatIdent { mutability, .. }) = t.pat.as_mut() {
@@ -568,7 +1709,11 @@ impl Fold for JNISignatureTransformer {
                 }
 
                 FnArg::Typed(PatType {
-                    attrs: t.attrs,
+                    attrs: t
+                        .attrs
+                        .into_iter()
+                        .filter(|a| !is_unchecked_attribute(a) && !is_default_on_error_attribute(a))
+                        .collect(),
                     pat: t.pat,
                     colon_token: t.colon_token,
                     ty: Box::new(jni_conversion_type),
@@ -578,9 +1723,54 @@ impl Fold for JNISignatureTransformer {
     }
 
     fn fold_return_type(&mut self, return_type: ReturnType) -> ReturnType {
+        let return_type = match return_type {
+            ReturnType::Type(arrow, rtype) => ReturnType::Type(
+                arrow,
+                Box::new(self.struct_freestanding_transformer.fold_type(*rtype)),
+            ),
+            ReturnType::Default => ReturnType::Default,
+        };
+
+        if let ReturnType::Type(_, rtype) = &return_type {
+            if let Some(l) = env_reference_lifetime(rtype) {
+                emit_warning!(l, "returning a reference with JNI-reserved `'env` lifetime";
+                    note = "`'env` is the lifetime of the `JNIEnv`, returned references can't outlive the call and should use `'borrow` instead";
+                    help = "replace `'env` with `'borrow`")
+            }
+        }
+
+        /* With `derive_class`, an `anyhow` or a `()` error the error is thrown by the generated wrapper, so the JNI function returns the `Ok` type */
+        let return_type = match (return_type, &self.call_type) {
+            (ReturnType::Type(arrow, rtype), CallType::Safe(_)) => {
+                let ok_type =
+                    match anyhow_result_ok_type(&rtype).or_else(|| unit_result_ok_type(&rtype)) {
+                        Some(ok_type) => Some(ok_type),
+                        None if self.call_type.derive_class().is_some() => {
+                            result_type_parts(&rtype).map(|(ok_type, _)| ok_type)
+                        }
+                        None => None,
+                    };
+
+                match ok_type {
+                    Some(ok_type) => ReturnType::Type(arrow, Box::new(ok_type.clone())),
+                    None => ReturnType::Type(arrow, rtype),
+                }
+            }
+            (r, _) => r,
+        };
+
+        let with_context = self.call_type.conversion_context().is_some();
+
         match return_type {
             ReturnType::Default => return_type,
             ReturnType::Type(ref arrow, ref rtype) => match (&**rtype, self.call_type.clone()) {
+                (Type::Path(_) | Type::Reference(_), CallType::Safe(_)) if with_context => {
+                    ReturnType::Type(
+                        *arrow,
+                        parse_quote_spanned! { rtype.span() => <#rtype as ::robusta_jni::convert::TryIntoJavaValueCtx<'env>>::Target },
+                    )
+                }
+
                 (Type::Path(p), CallType::Unchecked { .. }) => ReturnType::Type(
                     *arrow,
                     parse_quote_spanned! { p.span() => <#p as ::robusta_jni::convert::IntoJavaValue<'env>>::Target },
@@ -623,6 +1813,8 @@ impl Fold for JNISignatureTransformer {
                 .collect(),
             variadic: node.variadic.map(|v| self.fold_variadic(v)),
             output: self.fold_return_type(node.output),
+            /* `async` methods are rejected, the JNI function is kept synchronous so that no further errors are reported about futures */
+            asyncness: None,
             ..node
         }
     }
@@ -634,6 +1826,25 @@ struct JNISignature {
     struct_name: String,
     self_method: bool,
     env_arg: Option<FnArg>,
+    unchecked_args: HashSet<Ident>,
+    defaulted_args: HashSet<Ident>,
+    borrowed_args: HashSet<Ident>,
+    /// `CriticalByteArray` parameters, pinned together when there are several of them.
+    critical_args: Vec<Ident>,
+    null_policy: NullPolicy,
+    /// `#[receiver]` strategy of `self` methods not using the `pojo` one, with how the receiver is taken.
+    receiver: Option<(ReceiverStrategy, ReceiverKind)>,
+}
+
+/// How an exported `self` method takes its receiver.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ReceiverKind {
+    /// `self`
+    Owned,
+    /// `&self`
+    Shared,
+    /// `&mut self`
+    Mut,
 }
 
 impl JNISignature {
@@ -647,7 +1858,9 @@ impl JNISignature {
         let mut jni_signature_transformer = JNISignatureTransformer::new(
             freestanding_transformer,
             struct_context.struct_lifetimes.clone(),
+            struct_context.where_clause.clone(),
             call_type.clone(),
+            struct_context.receiver.clone(),
         );
 
         let self_method = is_self_method(&signature);
@@ -661,6 +1874,14 @@ impl JNISignature {
             struct_name: struct_context.struct_name.clone(),
             self_method,
             env_arg,
+            unchecked_args: jni_signature_transformer.unchecked_args,
+            defaulted_args: jni_signature_transformer.defaulted_args,
+            borrowed_args: jni_signature_transformer.borrowed_args,
+            critical_args: jni_signature_transformer.critical_args,
+            null_policy: struct_context.bridge_params.null_policy(),
+            receiver: jni_signature_transformer
+                .receiver_kind
+                .map(|kind| (struct_context.receiver.clone(), kind)),
         }
     }
 
@@ -672,23 +1893,121 @@ impl JNISignature {
             })
     }
 
+    /// Whether the generated wrapper checks for a pending Java exception after input conversions.
+    fn check_pending(&self) -> bool {
+        match &self.call_type {
+            CallType::Safe(Some(params)) => params.check_pending(),
+            CallType::Safe(None) => true,
+            CallType::Unchecked(_) => false,
+        }
+    }
+
     fn signature_call(&self) -> Expr {
-        let method_call_inputs: Punctuated<Expr, Token![,]> = {
-            let mut result: Vec<_> = self.args_iter()
-                .map(|p| {
-                    match p.pat.as_ref() {
-                        Pat::Ident(PatIdent { ident, .. }) => {
-                            let input_param: Expr = {
-                                match self.call_type {
-                                    CallType::Safe(_) => parse_quote_spanned! { ident.span() => ::robusta_jni::convert::TryFromJavaValue::try_from(#ident, &env)? },
-                                    CallType::Unchecked { .. } => parse_quote_spanned! { ident.span() => ::robusta_jni::convert::FromJavaValue::from(#ident, &env) }
-                                }
+        let input_conversions: Vec<(&Ident, Expr)> = self.args_iter()
+            .enumerate()
+            .map(|(i, p)| {
+                match p.pat.as_ref() {
+                    Pat::Ident(PatIdent { ident, .. }) => {
+                        // Arguments are numbered from 1, the receiver of self methods being #0
+                        let arg_index = if self.self_method { i } else { i + 1 };
+                        let arg_name = ident.to_string();
+
+                        /* A defaulted argument also clears the exception its failed conversion may have left pending */
+                        let handle_error = |conversion: Expr| -> Expr {
+                            let on_null: Option<Expr> = match self.null_policy {
+                                NullPolicy::Error => None,
+                                NullPolicy::Panic => Some(parse_quote_spanned! { ident.span() =>
+                                    panic!("`null` passed for argument `{}` (#{})", #arg_name, #arg_index)
+                                }),
+                                NullPolicy::Default => Some(parse_quote_spanned! { ident.span() =>
+                                    ::std::default::Default::default()
+                                }),
                             };
-                            input_param
+
+                            match on_null {
+                                _ if self.defaulted_args.contains(ident) => parse_quote_spanned! { ident.span() =>
+                                    #conversion.unwrap_or_else(|_| {
+                                        let _ = env.exception_clear();
+                                        ::std::default::Default::default()
+                                    })
+                                },
+                                Some(on_null) => parse_quote_spanned! { ident.span() =>
+                                    match #conversion {
+                                        Ok(v) => v,
+                                        Err(::robusta_jni::jni::errors::Error::NullPtr(_)) => #on_null,
+                                        Err(e) => return Err(::robusta_jni::convert::ArgumentConversionError::new(#arg_name, #arg_index, e).into()),
+                                    }
+                                },
+                                None => parse_quote_spanned! { ident.span() =>
+                                    #conversion.map_err(|e| ::robusta_jni::convert::ArgumentConversionError::new(#arg_name, #arg_index, e))?
+                                },
+                            }
+                        };
+
+                        if let (0, true, Some((strategy, kind))) = (i, self.self_method, &self.receiver) {
+                            return (ident, self.receiver_conversion(ident, strategy, *kind));
                         }
-                        _ => panic!("Bug -- please report to library author. Found non-ident FnArg pattern")
+
+                        let input_param: Expr = {
+                            match (&self.call_type, self.call_type.conversion_context()) {
+                                (CallType::Safe(_), Some(context)) if !self.unchecked_args.contains(ident) => handle_error(parse_quote_spanned! { ident.span() =>
+                                    ::robusta_jni::convert::TryFromJavaValueCtx::try_from_ctx(#ident, &env, &#context)
+                                }),
+                                (CallType::Safe(_), None) if !self.unchecked_args.contains(ident) => handle_error(parse_quote_spanned! { ident.span() =>
+                                    ::robusta_jni::convert::TryFromJavaValue::try_from(#ident, &env)
+                                }),
+                                _ => parse_quote_spanned! { ident.span() => ::robusta_jni::convert::FromJavaValue::from(#ident, &env) }
+                            }
+                        };
+                        (ident, input_param)
+                    }
+                    _ => panic!("Bug -- please report to library author. Found non-ident FnArg pattern")
+                }
+        }).collect();
+
+        /* When checking for pending exceptions, inputs are converted upfront so that
+         * the user function is never called with a Java exception pending.
+         */
+        let check_pending = self.check_pending() && !input_conversions.is_empty();
+
+        /* Pinning an array forbids any other JNI call, so critical arrays are pinned together
+         * after all the other inputs have been converted.
+         */
+        let pin_critical_args: Option<Stmt> = if !self.critical_args.is_empty() {
+            let critical_args = &self.critical_args;
+            let pin_all: Expr = parse_quote! {
+                ::robusta_jni::convert::CriticalByteArray::pin_all(&env, [#(#critical_args),*])
+            };
+
+            Some(match &self.call_type {
+                CallType::Safe(_) => parse_quote! { let [#(#critical_args),*] = #pin_all?; },
+                CallType::Unchecked(_) => {
+                    parse_quote! { let [#(#critical_args),*] = #pin_all.unwrap(); }
+                }
+            })
+        } else {
+            None
+        };
+        let convert_upfront = check_pending || pin_critical_args.is_some();
+
+        let method_call_inputs: Punctuated<Expr, Token![,]> = {
+            let mut result: Vec<Expr> = input_conversions
+                .iter()
+                .map(|(ident, conversion)| {
+                    let value: Expr = if convert_upfront {
+                        parse_quote_spanned! { ident.span() => #ident }
+                    } else {
+                        conversion.clone()
+                    };
+
+                    /* A borrowed conversion result lives until the end of the call */
+                    if self.borrowed_args.contains(*ident) {
+                        parse_quote_spanned! { ident.span() => &#value }
+                    } else {
+                        value
                     }
-            }).collect();
+                })
+                .collect();
 
             if let Some(ref e) = self.env_arg {
                 // because `self` is kept in the transformed JNI signature, if this is a `self` method we put `env` *after* self, otherwise the env parameter must be first
@@ -704,8 +2023,84 @@ impl JNISignature {
         let struct_name = Ident::new(&self.struct_name, signature_span);
         let method_name = self.transformed_signature.ident.clone();
 
-        parse_quote_spanned! { signature_span =>
-            #struct_name::#method_name(#method_call_inputs)
+        if convert_upfront {
+            let (idents, conversions): (Vec<_>, Vec<_>) = input_conversions
+                .into_iter()
+                .filter(|(ident, _)| !self.critical_args.contains(ident))
+                .unzip();
+
+            let exception_check: Option<Stmt> = if check_pending {
+                Some(parse_quote! {
+                    if env.exception_check()? {
+                        return Err(::robusta_jni::jni::errors::Error::JavaException.into());
+                    }
+                })
+            } else {
+                None
+            };
+
+            parse_quote_spanned! { signature_span => {
+                #(let #idents = #conversions;)*
+
+                #exception_check
+                #pin_critical_args
+
+                #struct_name::#method_name(#method_call_inputs)
+            }}
+        } else {
+            parse_quote_spanned! { signature_span =>
+                #struct_name::#method_name(#method_call_inputs)
+            }
+        }
+    }
+
+    /// Reconstruct the receiver of a `self` method from the raw object `ident`.
+    fn receiver_conversion(
+        &self,
+        ident: &Ident,
+        strategy: &ReceiverStrategy,
+        kind: ReceiverKind,
+    ) -> Expr {
+        let conversion: Expr = match (strategy, kind) {
+            (ReceiverStrategy::NativePointer(field), ReceiverKind::Owned) => {
+                parse_quote_spanned! { ident.span() =>
+                    unsafe { ::robusta_jni::convert::take_native_pointer(&env, #ident, #field) }
+                }
+            }
+            (ReceiverStrategy::NativePointer(field), ReceiverKind::Shared) => {
+                parse_quote_spanned! { ident.span() =>
+                    unsafe { ::robusta_jni::convert::borrow_native_pointer(&env, #ident, #field) }
+                }
+            }
+            (ReceiverStrategy::NativePointer(field), ReceiverKind::Mut) => {
+                parse_quote_spanned! { ident.span() =>
+                    unsafe { ::robusta_jni::convert::borrow_native_pointer_mut(&env, #ident, #field) }
+                }
+            }
+            (ReceiverStrategy::With(function), _) => parse_quote_spanned! { ident.span() =>
+                #function(&env, #ident)
+            },
+            (ReceiverStrategy::Pojo, _) => {
+                unreachable!("`pojo` receivers are converted as the other arguments")
+            }
+        };
+
+        let value: Expr = match &self.call_type {
+            CallType::Safe(_) => parse_quote_spanned! { ident.span() =>
+                #conversion.map_err(|e| ::robusta_jni::convert::ArgumentConversionError::new("receiver", 0, e))?
+            },
+            CallType::Unchecked(_) => parse_quote_spanned! { ident.span() => #conversion.unwrap() },
+        };
+
+        /* Native pointers are borrowed in place, values returned by a custom function are borrowed for the call */
+        match (strategy, kind) {
+            (ReceiverStrategy::With(_), ReceiverKind::Shared) => {
+                parse_quote_spanned! { ident.span() => &#value }
+            }
+            (ReceiverStrategy::With(_), ReceiverKind::Mut) => {
+                parse_quote_spanned! { ident.span() => &mut #value }
+            }
+            _ => value,
         }
     }
 
@@ -713,3 +2108,130 @@ impl JNISignature {
         &self.transformed_signature
     }
 }
+
+fn is_unchecked_attribute(attr: &Attribute) -> bool {
+    attr.path().is_ident("unchecked")
+}
+
+fn is_default_on_error_attribute(attr: &Attribute) -> bool {
+    attr.path().is_ident("default_on_error")
+}
+
+fn is_critical_byte_array(ty: &Type) -> bool {
+    match ty {
+        Type::Path(TypePath { path, .. }) => path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "CriticalByteArray"),
+        _ => false,
+    }
+}
+
+/// `Ok` type of an `anyhow::Result<T>` or `Result<T, anyhow::Error>` return type, whose errors are thrown with their context chain.
+///
+/// Only these spellings are recognized: an `anyhow::Error` imported under another name is a regular error type.
+fn anyhow_result_ok_type(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(TypePath { path, .. }) => path,
+        _ => return None,
+    };
+
+    let ends_with = |path: &syn::Path, module: &str, name: &str| {
+        let mut segments = path.segments.iter().rev();
+        matches!(
+            (segments.next(), segments.next()),
+            (Some(last), Some(previous)) if last.ident == name && previous.ident == module
+        )
+    };
+
+    let result_segment = path.segments.last()?;
+    let args = match &result_segment.arguments {
+        PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. })
+            if result_segment.ident == "Result" =>
+        {
+            args
+        }
+        _ => return None,
+    };
+
+    match (args.iter().next()?, args.iter().nth(1)) {
+        (GenericArgument::Type(ok_type), None) if ends_with(path, "anyhow", "Result") => {
+            Some(ok_type)
+        }
+        (GenericArgument::Type(ok_type), Some(GenericArgument::Type(Type::Path(e))))
+            if ends_with(&e.path, "anyhow", "Error") =>
+        {
+            Some(ok_type)
+        }
+        _ => None,
+    }
+}
+
+/// `Ok` type of a `Result<T, ()>` return type, whose `Err(())` carries no details and throws the default exception.
+fn unit_result_ok_type(ty: &Type) -> Option<&Type> {
+    let result_segment = match ty {
+        Type::Path(TypePath { path, .. }) => path.segments.last()?,
+        _ => return None,
+    };
+
+    match &result_segment.arguments {
+        PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. })
+            if result_segment.ident == "Result" =>
+        {
+            match (args.iter().next()?, args.iter().nth(1)?) {
+                (GenericArgument::Type(ok_type), GenericArgument::Type(Type::Tuple(t)))
+                    if t.elems.is_empty() =>
+                {
+                    Some(ok_type)
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// `Ok` type and name of the error type of a `Result<T, E>` return type, from which `derive_class` names the thrown class.
+fn result_type_parts(ty: &Type) -> Option<(&Type, &Ident)> {
+    let result_segment = match ty {
+        Type::Path(TypePath { path, .. }) => path.segments.last()?,
+        _ => return None,
+    };
+
+    if result_segment.ident != "Result" {
+        return None;
+    }
+
+    match &result_segment.arguments {
+        PathArguments::AngleBracketed(AngleBracketedGenericArguments { args, .. }) => {
+            match (args.iter().next()?, args.iter().nth(1)?) {
+                (
+                    GenericArgument::Type(ok_type),
+                    GenericArgument::Type(Type::Path(TypePath { path, .. })),
+                ) => path.segments.last().map(|s| (ok_type, &s.ident)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// First reference bound to the `'env` lifetime in `ty`, including references nested in type arguments (e.g. `JniResult<&'env str>`).
+fn env_reference_lifetime(ty: &Type) -> Option<&Lifetime> {
+    struct EnvReferenceFinder<'ast> {
+        lifetime: Option<&'ast Lifetime>,
+    }
+
+    impl<'ast> Visit<'ast> for EnvReferenceFinder<'ast> {
+        fn visit_type_reference(&mut self, node: &'ast TypeReference) {
+            match &node.lifetime {
+                Some(l) if l.ident == "env" && self.lifetime.is_none() => self.lifetime = Some(l),
+                _ => visit::visit_type_reference(self, node),
+            }
+        }
+    }
+
+    let mut finder = EnvReferenceFinder { lifetime: None };
+    finder.visit_type(ty);
+    finder.lifetime
+}
@@ -1,20 +1,25 @@
 use inflector::cases::camelcase::to_camel_case;
-use proc_macro2::{TokenStream, TokenTree};
+use proc_macro2::{Ident, TokenStream, TokenTree};
 use proc_macro_error::{abort, emit_error, emit_warning};
-use quote::{quote_spanned, ToTokens};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::fold::Fold;
 use syn::spanned::Spanned;
-use syn::{parse_quote, GenericArgument, PathArguments, Type, TypePath};
-use syn::{FnArg, ImplItemFn, Lit, Pat, PatIdent, ReturnType, Signature};
+use syn::{parse_quote, Attribute, GenericArgument, PathArguments, PathSegment, Type, TypePath};
+use syn::{
+    Expr, ExprLit, FnArg, ImplItemFn, Lit, Meta, MetaNameValue, Pat, PatIdent, ReturnType,
+    Signature, Stmt,
+};
 
 use crate::transformation::context::StructContext;
 use crate::transformation::utils::get_call_type;
-use crate::transformation::{CallType, CallTypeAttribute, SafeParams};
+use crate::transformation::{bridge_method_entry, CallType, CallTypeAttribute, SafeParams};
 use crate::utils::{get_abi, get_class_arg_if_any, get_env_arg, is_self_method};
 use std::collections::HashSet;
 
 pub struct ImportedMethodTransformer<'ctx> {
     pub(crate) struct_context: &'ctx StructContext,
+    /// `bridge_methods()` entries of the transformed methods, with `#[bridge(descriptors)]`.
+    pub(crate) bridge_methods: Vec<TokenStream>,
 }
 
 impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
@@ -42,6 +47,8 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     }
                 };
 
+                let is_static_field = node.attrs.iter().any(|a| a.path().is_ident("static_field"));
+
                 if !node.block.stmts.is_empty() {
                     emit_error!(
                         node.block,
@@ -58,6 +65,8 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     let discarded_known_attributes: HashSet<&str> = {
                         let mut h = HashSet::new();
                         h.insert("call_type");
+                        h.insert("descriptor");
+                        h.insert("static_field");
 
                         if is_constructor {
                             h.insert("constructor");
@@ -96,6 +105,64 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     return dummy;
                 }
 
+                if is_static_field {
+                    let invalid_usage = if self_method {
+                        Some("`#[static_field]` can't be used in self methods")
+                    } else if is_constructor {
+                        Some("`#[static_field]` can't be used in constructors")
+                    } else if !signature.inputs.is_empty() {
+                        Some("`#[static_field]` functions can only take a `&JNIEnv` parameter")
+                    } else if let ReturnType::Default = signature.output {
+                        Some("`#[static_field]` functions must return the type of the field")
+                    } else {
+                        None
+                    };
+
+                    if let Some(message) = invalid_usage {
+                        emit_error!(original_signature, message);
+                        return dummy;
+                    }
+                }
+
+                let this_args: Vec<_> = node
+                    .sig
+                    .inputs
+                    .iter()
+                    .filter_map(|i| match i {
+                        FnArg::Typed(t) => t
+                            .attrs
+                            .iter()
+                            .find(|a| is_this_attribute(a))
+                            .map(|a| (t, a)),
+                        FnArg::Receiver(_) => None,
+                    })
+                    .collect();
+
+                if let Some((_, attr)) = this_args.get(1) {
+                    emit_error!(attr, "only one parameter can be marked with `#[this]`");
+                    return dummy;
+                }
+
+                let this_ident = match this_args.first() {
+                    Some((_, attr)) if self_method => {
+                        emit_error!(attr, "`#[this]` can't be used in self methods";
+                            help = "the receiver of self methods is always `self`");
+                        return dummy;
+                    }
+                    Some((_, attr)) if is_constructor => {
+                        emit_error!(attr, "`#[this]` can't be used in constructors");
+                        return dummy;
+                    }
+                    Some((t, _)) => match &*t.pat {
+                        Pat::Ident(PatIdent { ident, .. }) => Some(ident.clone()),
+                        _ => {
+                            emit_error!(t.pat, "`#[this]` parameter must be an identifier");
+                            return dummy;
+                        }
+                    },
+                    None => None,
+                };
+
                 if env_arg.is_none() {
                     if !self_method {
                         emit_error!(
@@ -117,7 +184,14 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     .map(|c| &c.call_type)
                     .unwrap_or(&CallType::Safe(None));
 
-                if let Some(CallTypeAttribute { attr, .. }) = &call_type_attribute {
+                if let Some(CallTypeAttribute { attr, both, .. }) = &call_type_attribute {
+                    if both.is_some() {
+                        abort!(
+                            attr,
+                            "`call_type(both(...))` is only supported on exported methods"
+                        )
+                    }
+
                     if let CallType::Safe(Some(params)) = call_type {
                         if let SafeParams {
                             message: Some(_), ..
@@ -125,28 +199,29 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                         | SafeParams {
                             exception_class: Some(_),
                             ..
-                        } = params
+                        }
+                        | SafeParams {
+                            exception_class_const: Some(_),
+                            ..
+                        }
+                        | SafeParams {
+                            derive_class: Some(_),
+                            ..
+                        } = &**params
                         {
                             abort!(attr, "can't have exception message or exception class for imported methods")
                         }
+
+                        if params.log_to.is_some() {
+                            abort!(attr, "`log_to` is only supported on exported methods")
+                        }
                     }
                 }
 
-                let jni_package_path = self
-                    .struct_context
-                    .package
-                    .as_ref()
-                    .map(|p| p.to_string())
-                    .filter(|s| !s.is_empty())
-                    .unwrap_or_else(|| "".into())
-                    .replace('.', "/");
-
-                let java_class_path = [jni_package_path, self.struct_context.struct_name.clone()]
-                    .iter()
-                    .filter(|s| !s.is_empty())
-                    .map(|s| s.to_owned())
-                    .collect::<Vec<_>>()
-                    .join("/");
+                let java_class_path = match &self.struct_context.package {
+                    Some(package) => package.class_classpath_path(&self.struct_context.struct_name),
+                    None => self.struct_context.struct_name.clone(),
+                };
                 let java_method_name = to_camel_case(&signature.ident.to_string());
 
                 let input_types_conversions = signature
@@ -156,6 +231,7 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     .filter_map(|i| match i {
                         FnArg::Typed(t) => match &*t.pat {
                             Pat::Ident(PatIdent { ident, .. }) if ident == "self" => None,
+                            _ if t.attrs.iter().any(is_this_attribute) => None,
                             _ => Some((&t.ty, t.ty.span(), &mut t.attrs))
                         },
                         FnArg::Receiver(_) => None,
@@ -199,13 +275,26 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     }
                 };
 
+                /* Java exceptions can only be observed through a `Result`, so safe `void` methods must return `JniResult<()>` */
+                if let (ReturnType::Default, CallType::Safe(_), false) =
+                    (&signature.output, call_type, is_constructor)
+                {
+                    emit_error!(original_signature, "imported methods returning `void` must return `JniResult<()>` with a safe `call_type`";
+                        help = "add `-> JniResult<()>`, or use `#[call_type(unchecked)]` to panic when the method throws");
+                    return dummy;
+                }
+
                 let output_conversion = match signature.output {
-                    ReturnType::Default => quote_spanned!(signature.output.span() => ),
+                    ReturnType::Default => quote_spanned!(signature.output.span() => "V"),
                     ReturnType::Type(_arrow, ref ty) => {
                         if is_constructor {
                             quote_spanned! { output_type_span => "V" }
                         } else {
                             match call_type {
+                                CallType::Safe(_) if optional_result_inner_type(ty).is_some() => {
+                                    let inner_ty = optional_result_inner_type(ty).unwrap();
+                                    quote_spanned! { output_type_span => <::std::option::Option<#inner_ty> as ::robusta_jni::convert::TryIntoJavaValue>::SIG_TYPE }
+                                }
                                 CallType::Safe(_) => {
                                     let inner_result_ty = match &**ty {
                                         Type::Path(TypePath { path, .. }) => {
@@ -255,11 +344,79 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     }
                 };
 
-                let java_signature = quote_spanned! { signature.span() => ["(", #input_types_conversions ")", #output_conversion].join("") };
+                /* An explicit `#[descriptor = "..."]` replaces the computed descriptor (e.g. to pick an overload) */
+                let descriptor_attribute =
+                    node.attrs.iter().find(|a| a.path().is_ident("descriptor"));
+                let explicit_descriptor = descriptor_attribute.and_then(|a| match &a.meta {
+                    Meta::NameValue(MetaNameValue {
+                        value:
+                            Expr::Lit(ExprLit {
+                                lit: Lit::Str(s), ..
+                            }),
+                        ..
+                    }) => Some(s.clone()),
+                    _ => {
+                        emit_error!(a, "expected a string literal descriptor";
+                            help = "use `#[descriptor = \"(Ljava/lang/String;I)V\"]`");
+                        None
+                    }
+                });
+
+                if let Some(descriptor) = &explicit_descriptor {
+                    let java_args_count = signature
+                        .inputs
+                        .iter()
+                        .filter(|i| match i {
+                            FnArg::Typed(t) => {
+                                !matches!(&*t.pat, Pat::Ident(PatIdent { ident, .. }) if ident == "self")
+                                    && !t.attrs.iter().any(is_this_attribute)
+                            }
+                            FnArg::Receiver(_) => false,
+                        })
+                        .count();
+
+                    match descriptor_arity(&descriptor.value()) {
+                        None => emit_error!(descriptor, "invalid JNI method descriptor"),
+                        Some(arity) if arity != java_args_count => emit_error!(
+                            descriptor,
+                            "descriptor has {} parameters, but the method has {}",
+                            arity,
+                            java_args_count
+                        ),
+                        Some(_) => {}
+                    }
+                }
+
+                let java_signature = match explicit_descriptor {
+                    Some(descriptor) => descriptor.to_token_stream(),
+                    None => {
+                        quote_spanned! { signature.span() => ["(", #input_types_conversions ")", #output_conversion].join("") }
+                    }
+                };
+
+                if self.struct_context.bridge_params.descriptors.is_present() && !is_static_field {
+                    let method_name = if is_constructor {
+                        "<init>"
+                    } else {
+                        &java_method_name
+                    };
+                    let is_static = !is_constructor && !self_method && this_ident.is_none();
+
+                    self.bridge_methods.push(bridge_method_entry(
+                        self.struct_context,
+                        "Imported",
+                        &java_class_path,
+                        method_name,
+                        java_signature.clone(),
+                        is_static,
+                    ));
+                }
 
                 let input_conversions = signature.inputs.iter().fold(TokenStream::new(), |mut tok, input| {
                     match input {
                         FnArg::Receiver(_) => { tok }
+                        FnArg::Typed(t) if matches!(&*t.pat, Pat::Ident(PatIdent { ident, .. }) if ident == "self") => { tok }
+                        FnArg::Typed(t) if t.attrs.iter().any(is_this_attribute) => { tok }
                         FnArg::Typed(t) => {
                             let ty = &t.ty;
                             let pat: TokenStream = {
@@ -282,6 +439,11 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     }
                 });
 
+                let optional_result_inner_ty = match &signature.output {
+                    ReturnType::Type(_, ty) if !is_constructor => optional_result_inner_type(ty),
+                    _ => None,
+                };
+
                 let return_expr = match call_type {
                     CallType::Safe(_) => {
                         if is_constructor {
@@ -323,11 +485,12 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                 let sig_discarded_known_attributes: HashSet<&str> = {
                     let mut h = HashSet::new();
                     h.insert("input_type");
+                    h.insert("this");
 
                     h
                 };
 
-                let class_arg_ident = if let Some(class_ref_arg) = class_ref_arg {
+                let mut class_arg_ident = if let Some(class_ref_arg) = class_ref_arg {
                     match class_ref_arg {
                         FnArg::Typed(t) => {
                             match *t.pat {
@@ -341,6 +504,37 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     None
                 };
 
+                /* In `#[bridge(class_loader)]` modules and for `#[module_loader]` structs, classes of static methods and constructors
+                 * are looked up with the registered class loader (unless given as a parameter)
+                 */
+                let class_loader_function = if self.struct_context.module_loader {
+                    Some(quote! { load_module_class })
+                } else if self.struct_context.bridge_params.class_loader.is_present() {
+                    Some(quote! { load_class })
+                } else {
+                    None
+                };
+
+                let class_lookup: Option<Stmt> = match class_loader_function {
+                    Some(class_loader_function)
+                        if class_arg_ident.is_none() && !self_method && this_ident.is_none() =>
+                    {
+                        let class_ident = Ident::new("class", node.sig.span());
+                        let lookup = quote! { ::robusta_jni::class_loader::#class_loader_function(#env_ident, #java_class_path) };
+                        class_arg_ident = Some(class_ident.clone());
+
+                        match call_type {
+                            CallType::Safe(_) => {
+                                Some(parse_quote! { let #class_ident = #lookup?; })
+                            }
+                            CallType::Unchecked(_) => {
+                                Some(parse_quote! { let #class_ident = #lookup.unwrap(); })
+                            }
+                        }
+                    }
+                    _ => None,
+                };
+
                 original_signature.inputs.iter_mut().for_each(|i| match i {
                     FnArg::Typed(t) => match &*t.pat {
                         Pat::Ident(PatIdent { ident, .. }) if ident == "self" => {}
@@ -366,25 +560,49 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     FnArg::Receiver(_) => {}
                 });
 
-                ImplItemFn {
+                let mut imported = ImplItemFn {
                     sig: Signature {
                         abi: None,
                         ..original_signature
                     },
-                    block: if self_method {
+                    /* Static fields are read with the descriptor of the return type, and named exactly like the function */
+                    block: if is_static_field {
+                        let field_name = signature.ident.to_string();
+                        let class = match &class_arg_ident {
+                            Some(class_arg_ident) => class_arg_ident.to_token_stream(),
+                            None => java_class_path.to_token_stream(),
+                        };
+
+                        match call_type {
+                            CallType::Safe(_) => parse_quote! {{
+                                let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
+                                let res = env.get_static_field(#class, #field_name, #output_conversion);
+                                #return_expr
+                            }},
+                            CallType::Unchecked(_) => parse_quote! {{
+                                let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
+                                let res = env.get_static_field(#class, #field_name, #output_conversion).unwrap();
+                                #return_expr
+                            }},
+                        }
+                    } else if self_method || this_ident.is_some() {
                         let self_span = node.sig.inputs.iter().next().unwrap().span();
+                        let receiver = match &this_ident {
+                            Some(ident) => ident.to_token_stream(),
+                            None => quote_spanned! { self_span => self },
+                        };
                         match call_type {
                             CallType::Safe(_) => {
                                 parse_quote_spanned! { self_span => {
                                     let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
-                                    let res = env.call_method(::robusta_jni::convert::JavaValue::autobox(::robusta_jni::convert::TryIntoJavaValue::try_into(self, &env)?, &env), #java_method_name, #java_signature, &[#input_conversions]);
+                                    let res = env.call_method(::robusta_jni::convert::JavaValue::autobox(::robusta_jni::convert::TryIntoJavaValue::try_into(#receiver, &env)?, &env), #java_method_name, #java_signature, &[#input_conversions]);
                                     #return_expr
                                 }}
                             }
                             CallType::Unchecked(_) => {
                                 parse_quote_spanned! { self_span => {
                                     let env: &'_ ::robusta_jni::jni::JNIEnv<'_> = #env_ident;
-                                    let res = env.call_method(::robusta_jni::convert::JavaValue::autobox(::robusta_jni::convert::IntoJavaValue::into(self, &env), &env), #java_method_name, #java_signature, &[#input_conversions]).unwrap();
+                                    let res = env.call_method(::robusta_jni::convert::JavaValue::autobox(::robusta_jni::convert::IntoJavaValue::into(#receiver, &env), &env), #java_method_name, #java_signature, &[#input_conversions]).unwrap();
                                     #return_expr
                                 }}
                             }
@@ -457,10 +675,313 @@ impl<'ctx> Fold for ImportedMethodTransformer<'ctx> {
                     },
                     attrs: impl_item_attributes,
                     ..node
+                };
+
+                if let Some(class_lookup) = class_lookup {
+                    imported.block.stmts.insert(0, class_lookup);
                 }
+
+                if self.struct_context.bridge_params.must_use.is_present()
+                    && returns_value(&imported.sig.output)
+                    && !imported.attrs.iter().any(|a| a.path().is_ident("must_use"))
+                {
+                    imported.attrs.push(parse_quote! {
+                        #[must_use = "ignoring the value returned by the Java method"]
+                    });
+                }
+
+                /* `Option<Result<T>>` is converted as a `Result<Option<T>>` and then transposed:
+                 * a thrown exception is checked first and yields `Some(Err(_))`, then a `null` return value yields `None`.
+                 */
+                if let Some(inner_ty) = optional_result_inner_ty {
+                    let block = imported.block;
+                    imported.block = parse_quote_spanned! { output_type_span => {
+                        let res: ::robusta_jni::jni::errors::Result<::std::option::Option<#inner_ty>> = (|| #block)();
+                        res.transpose()
+                    }};
+                }
+
+                imported
             }
 
             _ => node,
         }
     }
 }
+
+/// Whether a method returns something else than `()` or a `Result<()>` (including aliases like `JniResult<()>`).
+pub(crate) fn returns_value(output: &ReturnType) -> bool {
+    let ty = match output {
+        ReturnType::Default => return false,
+        ReturnType::Type(_, ty) => ty,
+    };
+    let is_unit = |ty: &Type| matches!(ty, Type::Tuple(t) if t.elems.is_empty());
+
+    match &**ty {
+        Type::Path(TypePath { path, .. }) => match path.segments.last() {
+            Some(PathSegment {
+                ident,
+                arguments: PathArguments::AngleBracketed(a),
+            }) if ident.to_string().ends_with("Result") => {
+                !matches!(a.args.first(), Some(GenericArgument::Type(ok_type)) if is_unit(ok_type))
+            }
+            _ => true,
+        },
+        ty => !is_unit(ty),
+    }
+}
+
+fn is_this_attribute(attr: &Attribute) -> bool {
+    attr.path().is_ident("this")
+}
+
+/// Number of parameters of a JNI method descriptor such as `(Ljava/lang/String;I)V`, or `None` if it's malformed.
+fn descriptor_arity(descriptor: &str) -> Option<usize> {
+    /// Skip a single field descriptor, returning the rest of the input.
+    fn skip_field_type(s: &str) -> Option<&str> {
+        let s = s.trim_start_matches('[');
+        match s.chars().next()? {
+            'Z' | 'B' | 'C' | 'S' | 'I' | 'J' | 'F' | 'D' => Some(&s[1..]),
+            'L' => match s.find(';')? {
+                1 => None,
+                end => Some(&s[end + 1..]),
+            },
+            _ => None,
+        }
+    }
+
+    let mut rest = descriptor.strip_prefix('(')?;
+    let mut arity = 0;
+    while !rest.starts_with(')') {
+        rest = skip_field_type(rest)?;
+        arity += 1;
+    }
+
+    match &rest[1..] {
+        "V" => Some(arity),
+        return_type => skip_field_type(return_type)
+            .filter(|r| r.is_empty())
+            .map(|_| arity),
+    }
+}
+
+/// If `ty` is an `Option<Result<T>>`, returns `T`.
+fn optional_result_inner_type(ty: &Type) -> Option<&Type> {
+    fn first_type_argument<'a>(ty: &'a Type, ident: &str) -> Option<&'a Type> {
+        match ty {
+            Type::Path(TypePath { path, .. }) => {
+                let segment = path.segments.last().filter(|s| s.ident == ident)?;
+                match &segment.arguments {
+                    PathArguments::AngleBracketed(a) => match a.args.first() {
+                        Some(GenericArgument::Type(t)) => Some(t),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    first_type_argument(ty, "Option").and_then(|t| first_type_argument(t, "Result"))
+}
+
+#[cfg(test)]
+mod test {
+    use darling::util::Flag;
+
+    use super::*;
+    use crate::transformation::context::struct_context;
+    use crate::transformation::BridgeParams;
+
+    #[test]
+    fn this_parameter_is_the_receiver() {
+        let struct_context = struct_context();
+        let mut transformer = ImportedMethodTransformer {
+            struct_context: &struct_context,
+            bridge_methods: Vec::new(),
+        };
+
+        let method: ImplItemFn = parse_quote! {
+            pub extern "java" fn bar(env: &JNIEnv, v: i32, #[this] foo: &Foo) -> JniResult<i32> {}
+        };
+        let output = transformer.fold_impl_item_fn(method);
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains("env . call_method (:: robusta_jni :: convert :: JavaValue :: autobox (:: robusta_jni :: convert :: TryIntoJavaValue :: try_into (foo , & env) ? , & env) , \"bar\""));
+        assert!(block.contains(
+            "[\"(\" , < i32 as :: robusta_jni :: convert :: TryIntoJavaValue > :: SIG_TYPE , \")\""
+        ));
+        assert!(output.sig.inputs.iter().all(|i| match i {
+            FnArg::Typed(t) => t.attrs.is_empty(),
+            FnArg::Receiver(_) => true,
+        }));
+    }
+
+    #[test]
+    fn static_field_reads_field_of_struct_class() {
+        let struct_context = StructContext {
+            package: Some("com.example".parse().unwrap()),
+            ..struct_context()
+        };
+        let mut transformer = ImportedMethodTransformer {
+            struct_context: &struct_context,
+            bridge_methods: Vec::new(),
+        };
+
+        let method: ImplItemFn = parse_quote! {
+            #[static_field]
+            pub extern "java" fn MAX_VALUE(env: &JNIEnv) -> JniResult<i32> {}
+        };
+        let output = transformer.fold_impl_item_fn(method);
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains("env . get_static_field (\"com/example/Foo\" , \"MAX_VALUE\" , < i32 as :: robusta_jni :: convert :: TryIntoJavaValue > :: SIG_TYPE)"));
+        assert!(!block.contains("call_static_method"));
+        assert!(output
+            .attrs
+            .iter()
+            .all(|a| !a.path().is_ident("static_field")));
+    }
+
+    #[test]
+    fn unchecked_void_method_has_void_descriptor() {
+        let struct_context = struct_context();
+        let mut transformer = ImportedMethodTransformer {
+            struct_context: &struct_context,
+            bridge_methods: Vec::new(),
+        };
+
+        let method: ImplItemFn = parse_quote! {
+            #[call_type(unchecked)]
+            pub extern "java" fn reset(&self, env: &JNIEnv, v: i32) {}
+        };
+        let output = transformer.fold_impl_item_fn(method);
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains(
+            "[\"(\" , < i32 as :: robusta_jni :: convert :: IntoJavaValue > :: SIG_TYPE , \")\" , \"V\"] . join (\"\")"
+        ));
+    }
+
+    #[test]
+    fn descriptor_arities() {
+        assert_eq!(descriptor_arity("()V"), Some(0));
+        assert_eq!(descriptor_arity("(Ljava/lang/String;I)V"), Some(2));
+        assert_eq!(
+            descriptor_arity("([[I[Ljava/lang/Object;J)Ljava/lang/String;"),
+            Some(3)
+        );
+        assert_eq!(descriptor_arity("(I)"), None);
+        assert_eq!(descriptor_arity("(L;)V"), None);
+        assert_eq!(descriptor_arity("(Q)V"), None);
+        assert_eq!(descriptor_arity("()II"), None);
+    }
+
+    #[test]
+    fn explicit_descriptor_overrides_computed_one() {
+        let struct_context = struct_context();
+        let mut transformer = ImportedMethodTransformer {
+            struct_context: &struct_context,
+            bridge_methods: Vec::new(),
+        };
+
+        let method: ImplItemFn = parse_quote! {
+            #[descriptor = "(Ljava/lang/Object;)I"]
+            pub extern "java" fn bar(env: &JNIEnv, s: String) -> JniResult<i32> {}
+        };
+        let output = transformer.fold_impl_item_fn(method);
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains("\"bar\" , \"(Ljava/lang/Object;)I\""));
+        assert!(output.attrs.is_empty());
+    }
+
+    #[test]
+    fn class_loader_option_looks_up_classes() {
+        let struct_context = StructContext {
+            package: Some("com.example".parse().unwrap()),
+            bridge_params: BridgeParams {
+                class_loader: Flag::present(),
+                ..Default::default()
+            },
+            ..struct_context()
+        };
+        let mut transformer = ImportedMethodTransformer {
+            struct_context: &struct_context,
+            bridge_methods: Vec::new(),
+        };
+
+        let method: ImplItemFn = parse_quote! {
+            pub extern "java" fn bar(env: &JNIEnv, v: i32) -> JniResult<i32> {}
+        };
+        let output = transformer.fold_impl_item_fn(method);
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains("let class = :: robusta_jni :: class_loader :: load_class (env , \"com/example/Foo\") ?"));
+        assert!(block.contains("env . call_static_method (class , \"bar\""));
+    }
+
+    #[test]
+    fn must_use_option_marks_methods_returning_values() {
+        let struct_context = StructContext {
+            package: Some("com.example".parse().unwrap()),
+            bridge_params: BridgeParams {
+                must_use: Flag::present(),
+                ..Default::default()
+            },
+            ..struct_context()
+        };
+        let mut transformer = ImportedMethodTransformer {
+            struct_context: &struct_context,
+            bridge_methods: Vec::new(),
+        };
+        let must_use =
+            |output: &ImplItemFn| output.attrs.iter().any(|a| a.path().is_ident("must_use"));
+
+        let output = transformer.fold_impl_item_fn(parse_quote! {
+            pub extern "java" fn bar(env: &JNIEnv, v: i32) -> JniResult<i32> {}
+        });
+        assert!(must_use(&output));
+
+        let output = transformer.fold_impl_item_fn(parse_quote! {
+            #[call_type(unchecked)]
+            pub extern "java" fn baz(env: &JNIEnv) -> String {}
+        });
+        assert!(must_use(&output));
+
+        let output = transformer.fold_impl_item_fn(parse_quote! {
+            pub extern "java" fn qux(env: &JNIEnv, v: i32) -> JniResult<()> {}
+        });
+        assert!(!must_use(&output));
+
+        let output = transformer.fold_impl_item_fn(parse_quote! {
+            #[call_type(unchecked)]
+            pub extern "java" fn quux(env: &JNIEnv) {}
+        });
+        assert!(!must_use(&output));
+    }
+
+    #[test]
+    fn module_loader_structs_look_up_classes_with_module_loader() {
+        let struct_context = StructContext {
+            package: Some("com.example".parse().unwrap()),
+            module_loader: true,
+            ..struct_context()
+        };
+        let mut transformer = ImportedMethodTransformer {
+            struct_context: &struct_context,
+            bridge_methods: Vec::new(),
+        };
+
+        let method: ImplItemFn = parse_quote! {
+            #[constructor]
+            pub extern "java" fn new(env: &'borrow JNIEnv<'env>) -> JniResult<Self> {}
+        };
+        let output = transformer.fold_impl_item_fn(method);
+        let block = output.block.to_token_stream().to_string();
+
+        assert!(block.contains("let class = :: robusta_jni :: class_loader :: load_module_class (env , \"com/example/Foo\") ?"));
+        assert!(block.contains("env . new_object (class"));
+    }
+}
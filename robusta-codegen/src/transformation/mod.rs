@@ -1,29 +1,30 @@
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 
+use darling::ast::NestedMeta;
 use darling::util::Flag;
 use darling::FromMeta;
-use proc_macro2::{Ident, TokenStream};
+use proc_macro2::{Ident, Span, TokenStream};
 use proc_macro_error::{emit_error, emit_warning};
-use quote::ToTokens;
-use syn::fold::Fold;
+use quote::{quote, quote_spanned, ToTokens};
+use syn::fold::{self, Fold};
 use syn::parse::{Parse, ParseBuffer, ParseStream, Parser};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::visit::Visit;
 use syn::{
     parse_quote, Attribute, FnArg, GenericArgument, GenericParam, ImplItemFn, Item, ItemImpl,
-    ItemMod, ItemStruct, Lit, Pat, PatIdent, PatType, Path, PathArguments, PathSegment, Type,
-    TypePath, TypeReference, Visibility,
+    ExprPath, ItemMod, ItemStruct, Lit, LitStr, Meta, Pat, PatIdent, PatType, Path, PathArguments, PathSegment, Type,
+    TypePath, TypeReference,
 };
 use syn::{Error, ImplItem, Token};
 
 use imported::ImportedMethodTransformer;
 
 use crate::transformation::context::StructContext;
-use crate::transformation::exported::ExportedMethodTransformer;
-use crate::utils::{canonicalize_path, get_abi};
+use crate::transformation::exported::{dispatcher_function, ExportedMethodTransformer};
+use crate::utils::{canonicalize_path, freestanding_tokens, get_abi};
 use crate::validation::JNIBridgeModule;
 use std::fmt;
 
@@ -42,11 +43,25 @@ pub(crate) enum ImplItemType {
 
 pub(crate) struct ModTransformer {
     module: JNIBridgeModule,
+    params: BridgeParams,
+    /// `bridge_methods()` entries of the module being transformed, with `#[bridge(descriptors)]`.
+    bridge_methods: Vec<TokenStream>,
+    /// Nested modules of the module being transformed, whose `bridge_methods()` are included in its own.
+    nested_bridges: Vec<Ident>,
+    /// `invoke` functions of the `#[dispatcher]` structs whose impl blocks are in the module being transformed,
+    /// with their match arms, by struct name.
+    dispatchers: BTreeMap<String, (Ident, Vec<TokenStream>)>,
 }
 
 impl ModTransformer {
-    pub(crate) fn new(module: JNIBridgeModule) -> Self {
-        ModTransformer { module }
+    pub(crate) fn new(module: JNIBridgeModule, params: BridgeParams) -> Self {
+        ModTransformer {
+            module,
+            params,
+            bridge_methods: Vec::new(),
+            nested_bridges: Vec::new(),
+            dispatchers: BTreeMap::new(),
+        }
     }
 
     pub(crate) fn transform_module(&mut self) -> TokenStream {
@@ -65,7 +80,16 @@ impl ModTransformer {
                 .to_token_stream()
                 .to_string()
                 .replace(" ", ""); // TODO: Replace String-based struct name matching with something more robust
-            let struct_package = self.module.package_map.get(&struct_name).cloned().flatten();
+            let package_declaration = self.module.package_map.get(&struct_name);
+            let struct_package = package_declaration.map(|d| d.path.clone());
+            let class_aliases = package_declaration
+                .map(|d| d.class_aliases.clone())
+                .unwrap_or_default();
+            let module_loader = package_declaration.is_some_and(|d| d.module_loader);
+            let receiver = package_declaration
+                .map(|d| d.receiver.clone())
+                .unwrap_or_default();
+            let dispatcher = package_declaration.is_some_and(|d| d.dispatcher);
 
             if struct_package.is_none() {
                 emit_error!(p.path, "can't find package for struct `{}`", struct_name);
@@ -107,7 +131,12 @@ impl ModTransformer {
                 struct_type: p.path.clone(),
                 struct_name,
                 struct_lifetimes,
+                where_clause: node.generics.where_clause.clone(),
                 package: struct_package,
+                class_aliases,
+                module_loader,
+                receiver,
+                bridge_params: self.params.clone(),
             };
 
             let mut exported_fns_transformer = ExportedMethodTransformer {
@@ -115,6 +144,7 @@ impl ModTransformer {
             };
             let mut imported_fns_transformer = ImportedMethodTransformer {
                 struct_context: &context,
+                bridge_methods: Vec::new(),
             };
             let mut impl_cleaner = ImplCleaner;
 
@@ -132,6 +162,59 @@ impl ModTransformer {
                 })
                 .collect();
 
+            /* Types of generic impl blocks can't be named outside of them */
+            if self.params.descriptors.is_present() && node.generics.type_params().next().is_none() {
+                self.bridge_methods
+                    .extend(imported_fns_transformer.bridge_methods);
+                self.bridge_methods.extend(
+                    impl_export_visitor
+                        .items
+                        .iter()
+                        .filter_map(|(i, t)| match (i, t) {
+                            (ImplItem::Fn(f), ImplItemType::Exported) => Some(f),
+                            _ => None,
+                        })
+                        .flat_map(|f| exported_fns_transformer.bridge_methods(f)),
+                );
+            }
+
+            /* All the impl blocks of a struct share the same `invoke` function */
+            if dispatcher && node.generics.type_params().next().is_none() {
+                if !self.dispatchers.contains_key(&context.struct_name) {
+                    if self.params.descriptors.is_present() {
+                        let class = match &context.package {
+                            Some(package) => package.class_classpath_path(&context.struct_name),
+                            None => context.struct_name.clone(),
+                        };
+                        self.bridge_methods.push(bridge_method_entry(
+                            &context,
+                            "Exported",
+                            &class,
+                            &self.params.java_method_name("invoke"),
+                            quote! { "(Ljava/lang/String;[Ljava/lang/Object;)Ljava/lang/Object;" },
+                            true,
+                        ));
+                    }
+
+                    self.dispatchers.insert(
+                        context.struct_name.clone(),
+                        (exported_fns_transformer.dispatcher_name(), Vec::new()),
+                    );
+                }
+
+                let (_, arms) = self.dispatchers.get_mut(&context.struct_name).unwrap();
+                arms.extend(
+                    impl_export_visitor
+                        .items
+                        .iter()
+                        .filter_map(|(i, t)| match (i, t) {
+                            (ImplItem::Fn(f), ImplItemType::Exported) => Some(f),
+                            _ => None,
+                        })
+                        .flat_map(|f| exported_fns_transformer.dispatch_arms(f)),
+                );
+            }
+
             let transformed = impl_export_visitor
                 .items
                 .into_iter()
@@ -140,7 +223,14 @@ impl ModTransformer {
                     _ => None,
                 })
                 .cloned()
-                .map(|i| exported_fns_transformer.fold_impl_item(i))
+                .flat_map(|i| match i {
+                    ImplItem::Fn(f) => exported_fns_transformer
+                        .transform_impl_item_fn(f)
+                        .into_iter()
+                        .map(ImplItem::Fn)
+                        .collect(),
+                    _ => vec![i],
+                })
                 .collect();
 
             (preserved, transformed)
@@ -185,7 +275,13 @@ impl Fold for ModTransformer {
             Item::Macro(m) => Item::Macro(self.fold_item_macro(m)),
             Item::Mod(m) => Item::Mod(self.fold_item_mod(m)),
             Item::Static(s) => Item::Static(self.fold_item_static(s)),
-            Item::Struct(s) => Item::Struct(self.fold_item_struct(s)),
+            Item::Struct(s) => match send_assertion(&s) {
+                Some(assertion) => {
+                    let s = self.fold_item_struct(s);
+                    Item::Verbatim(quote! { #s #assertion })
+                }
+                None => Item::Struct(self.fold_item_struct(s)),
+            },
             Item::Trait(t) => Item::Trait(self.fold_item_trait(t)),
             Item::TraitAlias(t) => Item::TraitAlias(self.fold_item_trait_alias(t)),
             Item::Type(t) => Item::Type(self.fold_item_type(t)),
@@ -197,9 +293,51 @@ impl Fold for ModTransformer {
     }
 
     fn fold_item_mod(&mut self, mut node: ItemMod) -> ItemMod {
-        let allow_non_snake_case: Attribute = parse_quote! { #![allow(non_snake_case)] };
+        /* Exported methods are named after Java methods, so camelCase names are allowed unless opted out.
+         * Generated JNI functions don't need this, as `#[no_mangle]` functions are exempt from the lint.
+         */
+        if !self.params.no_default_allows.is_present() {
+            let allow_non_snake_case: Attribute = parse_quote! { #![allow(non_snake_case)] };
+            node.attrs.push(allow_non_snake_case);
+        }
 
-        node.attrs.extend_from_slice(&[allow_non_snake_case]);
+        let outer_bridge_methods = std::mem::take(&mut self.bridge_methods);
+        let outer_nested_bridges = std::mem::take(&mut self.nested_bridges);
+        let outer_dispatchers = std::mem::take(&mut self.dispatchers);
+
+        let content = node.content.map(|(brace, items)| {
+            let mut items: Vec<Item> = items.into_iter().map(|i| self.fold_item(i)).collect();
+            items.extend(
+                self.dispatchers
+                    .values()
+                    .map(|(name, arms)| Item::Verbatim(dispatcher_function(name, arms))),
+            );
+
+            /* Each module lists its own methods and those of its nested modules */
+            if self.params.descriptors.is_present() {
+                let entries = &self.bridge_methods;
+                let nested_bridges = &self.nested_bridges;
+                items.push(parse_quote! {
+                    /// Methods of this module and of its nested modules, to be checked against compiled Java classes
+                    /// with [`robusta_jni::classfile::validate`].
+                    pub fn bridge_methods() -> ::std::vec::Vec<::robusta_jni::classfile::BridgeMethod> {
+                        #[allow(unused_mut)]
+                        let mut methods = ::std::vec![#(#entries),*];
+                        #(methods.extend(#nested_bridges::bridge_methods());)*
+                        methods
+                    }
+                });
+            }
+
+            (brace, items)
+        });
+
+        self.bridge_methods = outer_bridge_methods;
+        self.nested_bridges = outer_nested_bridges;
+        self.dispatchers = outer_dispatchers;
+        if content.is_some() {
+            self.nested_bridges.push(node.ident.clone());
+        }
 
         ItemMod {
             attrs: node.attrs,
@@ -207,12 +345,7 @@ impl Fold for ModTransformer {
             unsafety: node.unsafety,
             mod_token: node.mod_token,
             ident: self.fold_ident(node.ident),
-            content: node.content.map(|(brace, items)| {
-                (
-                    brace,
-                    items.into_iter().map(|i| self.fold_item(i)).collect(),
-                )
-            }),
+            content,
             semi: node.semi,
         }
     }
@@ -235,6 +368,7 @@ impl Fold for ModTransformer {
                 "TryFromJavaValue",
                 "IntoJavaValue",
                 "TryIntoJavaValue",
+                "JavaRecord",
             ]);
 
             let has_package_trait = node.attrs.iter().any(|a| {
@@ -253,13 +387,21 @@ impl Fold for ModTransformer {
                 is_derive && needs_package_attr
             });
 
+            /* `#[class_alias]`, `#[module_loader]`, `#[receiver]`, `#[assert_send]` and `#[dispatcher]` are only used by the bridge, so they are always discarded */
+            let attributes = attributes.into_iter().filter(|a| {
+                !a.path().is_ident("class_alias")
+                    && !a.path().is_ident("module_loader")
+                    && !a.path().is_ident("receiver")
+                    && !a.path().is_ident("assert_send")
+                    && !a.path().is_ident("dispatcher")
+            });
+
             if !has_package_trait {
                 attributes
-                    .into_iter()
                     .filter(|a| a.path().to_token_stream().to_string().as_str() != "package")
                     .collect()
             } else {
-                attributes
+                attributes.collect()
             }
         };
 
@@ -275,6 +417,27 @@ impl Fold for ModTransformer {
     }
 }
 
+/// Compile-time assertion that a struct marked with `#[assert_send]` is `Send`, reported at the attribute.
+fn send_assertion(node: &ItemStruct) -> Option<TokenStream> {
+    let attr = node.attrs.iter().find(|a| a.path().is_ident("assert_send"))?;
+    if let Err(e) = attr.meta.require_path_only() {
+        emit_error!(e.span(), "`assert_send` doesn't take arguments");
+    }
+
+    let ident = &node.ident;
+    let (impl_generics, ty_generics, where_clause) = node.generics.split_for_impl();
+    Some(quote_spanned! { attr.span() =>
+        const _: () = {
+            fn assert_send<T: ?Sized + ::std::marker::Send>() {}
+
+            #[allow(dead_code)]
+            fn assert_struct_is_send #impl_generics () #where_clause {
+                assert_send::<#ident #ty_generics>();
+            }
+        };
+    })
+}
+
 #[derive(Default)]
 pub struct ImplExportVisitor<'ast> {
     pub(crate) items: Vec<(&'ast ImplItem, ImplItemType)>,
@@ -320,21 +483,76 @@ impl FromStr for JavaPath {
 }
 
 impl JavaPath {
-    pub fn to_snake_case(&self) -> String {
-        self.0.replace('.', "_")
-    }
-
     pub fn to_classpath_path(&self) -> String {
         self.0.replace('.', "/")
     }
+
+    /// Classpath path of the class `class_name` declared in this package.
+    /// A package ending with `$` is an outer class, e.g. `com.example.Foo$` and `Companion` give `com/example/Foo$Companion`.
+    pub fn class_classpath_path(&self, class_name: &str) -> String {
+        let mut s = self.to_classpath_path();
+        if !s.is_empty() && !s.ends_with('$') {
+            s.push('/');
+        }
+        s.push_str(class_name);
+        s
+    }
+
+    /// JNI-mangled name of the class `class_name` declared in this package, as used in native method symbols.
+    pub fn class_jni_name(&self, class_name: &str) -> String {
+        jni_mangle(&self.class_classpath_path(class_name))
+    }
+
+    /// Parse the package of a `#[package]` attribute.
+    /// A bare `#[package]`, `#[package()]` and `#[package("")]` all refer to the default (unnamed) package.
+    pub(crate) fn from_package_attribute(attr: &Attribute) -> syn::Result<Self> {
+        match attr.meta {
+            Meta::Path(_) => Ok(JavaPath(String::new())),
+            _ => attr.parse_args(),
+        }
+    }
+}
+
+/// Mangle a classpath path (or a path segment) following the JNI naming scheme.
+pub(crate) fn jni_mangle(path: &str) -> String {
+    path.replace('_', "_1")
+        .replace('$', "_00024")
+        .replace('/', "_")
+}
+
+/// Parse identifiers separated by `.` (packages) or `$` (nested classes), with an optional trailing separator.
+fn parse_java_path_segments(input: ParseStream<'_>) -> syn::Result<String> {
+    let mut path = String::new();
+
+    while !input.is_empty() {
+        let segment: Ident = input.parse()?;
+        path.push_str(&segment.to_string());
+
+        if input.peek(Token![.]) {
+            input.parse::<Token![.]>()?;
+            path.push('.');
+        } else if input.peek(Token![$]) {
+            input.parse::<Token![$]>()?;
+            path.push('$');
+        } else if !input.is_empty() {
+            return Err(input.error("expected `.` or `$`"));
+        }
+    }
+
+    Ok(path)
 }
 
 impl Parse for JavaPath {
     fn parse<'a>(input: &'a ParseBuffer<'a>) -> syn::Result<Self> {
-        let tokens = Punctuated::<Ident, Token![.]>::parse_terminated(input)?.to_token_stream();
-        let package = tokens.to_string();
+        let (package, span) = if input.peek(LitStr) {
+            let literal: LitStr = input.parse()?;
+            (literal.parse_with(parse_java_path_segments)?, literal.span())
+        } else {
+            let span = input.span();
+            (parse_java_path_segments(input)?, span)
+        };
 
-        JavaPath::from_str(&package).map_err(|e| Error::new_spanned(tokens, e))
+        JavaPath::from_str(&package).map_err(|e| Error::new(span, e))
     }
 }
 
@@ -359,10 +577,9 @@ impl FromMeta for JavaPath {
         } else {
             let tokens = TokenStream::from_str(&path)
                 .map_err(|_| Error::custom("cannot create token stream for java path parsing"))?;
-            let _parsed: Punctuated<Ident, Token![.]> =
-                Punctuated::<Ident, Token![.]>::parse_separated_nonempty
-                    .parse(tokens.into())
-                    .map_err(|e| Error::custom(format!("cannot parse java path ({})", e)))?;
+            let _parsed = parse_java_path_segments
+                .parse2(tokens)
+                .map_err(|e| Error::custom(format!("cannot parse java path ({})", e)))?;
 
             Ok(JavaPath(path.into()))
         }
@@ -401,18 +618,37 @@ impl Fold for ImplCleaner {
             .as_ref()
             .and_then(|l| l.name.as_ref().map(|n| n.value()));
 
-        match (&node.vis, &abi.as_deref()) {
-            (Visibility::Public(_), Some("jni")) => {
+        match abi.as_deref() {
+            Some("jni") => {
                 node.sig.abi = None;
                 node.attrs = node
                     .attrs
                     .into_iter()
-                    .filter(|a| a.path().get_ident().map_or(false, |i| i != "call_type"))
+                    .filter(|a| {
+                        a.path()
+                            .get_ident()
+                            .map_or(false, |i| i != "call_type" && i != "local_frame")
+                    })
+                    .map(|a| {
+                        if a.path().is_ident("hot") {
+                            parse_quote! { #[inline(always)] }
+                        } else {
+                            a
+                        }
+                    })
                     .collect();
+                node.sig.inputs.iter_mut().for_each(|i| {
+                    if let FnArg::Typed(t) = i {
+                        t.attrs.retain(|a| {
+                            !a.path().is_ident("unchecked")
+                                && !a.path().is_ident("default_on_error")
+                        });
+                    }
+                });
 
                 node
             }
-            (_, _) => node,
+            _ => node,
         }
     }
 }
@@ -499,28 +735,232 @@ impl Fold for FreestandingTransformer {
                         ty: Box::new(parse_quote! { #self_type }),
                     })
                 }
-                _ => FnArg::Typed(t),
+                _ => FnArg::Typed(PatType {
+                    ty: Box::new(self.fold_type(*t.ty)),
+                    ..t
+                }),
             },
         }
     }
+
+    /* `Self` isn't available outside of the impl block, so it's replaced with the struct type */
+    fn fold_type_path(&mut self, node: TypePath) -> TypePath {
+        if node.qself.is_none() && node.path.is_ident("Self") {
+            TypePath {
+                qself: None,
+                path: self.struct_type.clone(),
+            }
+        } else {
+            fold::fold_type_path(self, node)
+        }
+    }
 }
 
 #[derive(Clone, Default, FromMeta)]
 #[darling(default)]
 pub struct SafeParams {
     pub(crate) exception_class: Option<JavaPath>,
+    pub(crate) exception_class_const: Option<ExprPath>,
     pub(crate) message: Option<String>,
+    pub(crate) check_pending: Option<bool>,
+    pub(crate) backtrace: Option<bool>,
+    pub(crate) with_context: Option<ExprPath>,
+    pub(crate) derive_class: Option<JavaPath>,
+    pub(crate) log_to: Option<JavaPath>,
+}
+
+impl SafeParams {
+    /// Whether the generated wrapper should check for pending Java exceptions. Defaults to `true`.
+    pub(crate) fn check_pending(&self) -> bool {
+        self.check_pending.unwrap_or(true)
+    }
+
+    /// Whether the message of thrown exceptions should include a Rust backtrace. Defaults to `false`.
+    pub(crate) fn backtrace(&self) -> bool {
+        self.backtrace.unwrap_or(false)
+    }
+}
+
+/// Options of `#[call_type(both(...))]`, which exports a safe and an unchecked variant of the same method,
+/// bound to the Java native methods named after the Rust method with the respective suffix.
+#[derive(Clone, FromMeta)]
+pub struct BothParams {
+    pub(crate) safe_suffix: String,
+    pub(crate) unchecked_suffix: String,
+}
+
+impl BothParams {
+    fn validate(&self) -> Result<(), String> {
+        for suffix in [&self.safe_suffix, &self.unchecked_suffix] {
+            if suffix.is_empty() || !suffix.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return Err(format!(
+                    "invalid method suffix `{}`: only ASCII letters and digits are allowed",
+                    suffix
+                ));
+            }
+        }
+
+        if self.safe_suffix == self.unchecked_suffix {
+            return Err("`safe_suffix` and `unchecked_suffix` must be different".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+/// Options of the `#[local_frame]` attribute, which runs an exported method in its own local reference frame.
+#[derive(Clone, FromMeta)]
+pub struct LocalFrameParams {
+    pub(crate) capacity: i32,
+}
+
+/// Options of the `#[bridge]` attribute.
+#[derive(Clone, Default, FromMeta)]
+#[darling(default)]
+pub struct BridgeParams {
+    pub(crate) symbol_prefix: Option<String>,
+    pub(crate) symbol_suffix: Option<String>,
+    pub(crate) class_loader: Flag,
+    pub(crate) no_default_allows: Flag,
+    pub(crate) null_policy: Option<NullPolicy>,
+    /// Generate a `bridge_methods()` function listing the bridged methods and their descriptors.
+    pub(crate) descriptors: Flag,
+    /// Mark imported methods returning a value with `#[must_use]`.
+    pub(crate) must_use: Flag,
+}
+
+/// What the safe conversions of exported methods do when Java passes `null` for an argument that doesn't accept it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, FromMeta)]
+pub(crate) enum NullPolicy {
+    /// Throw the exception of the method, like for any other conversion error.
+    #[default]
+    Error,
+    /// Panic, naming the argument.
+    Panic,
+    /// Use the `Default::default()` value of the argument type.
+    Default,
+}
+
+/// How exported `self` methods reconstruct their receiver from the Java object, chosen with the `#[receiver]` struct attribute.
+#[derive(Clone, Debug, Default)]
+pub(crate) enum ReceiverStrategy {
+    /// Convert the object with the `(Try)FromJavaValue` implementation of the struct, e.g. derived field by field.
+    #[default]
+    Pojo,
+    /// Use the boxed value whose address is stored in the given `long` field of the object.
+    NativePointer(String),
+    /// Call the given `fn(&JNIEnv<'env>, JObject<'env>) -> jni::errors::Result<Self>`.
+    With(ExprPath),
+}
+
+impl FromMeta for ReceiverStrategy {
+    fn from_list(items: &[NestedMeta]) -> darling::Result<Self> {
+        match items {
+            [NestedMeta::Meta(Meta::Path(p))] if p.is_ident("pojo") => Ok(ReceiverStrategy::Pojo),
+            [NestedMeta::Meta(Meta::NameValue(nv))] if nv.path.is_ident("native_pointer") => {
+                String::from_expr(&nv.value).map(ReceiverStrategy::NativePointer)
+            }
+            [NestedMeta::Meta(Meta::NameValue(nv))] if nv.path.is_ident("with") => {
+                ExprPath::from_expr(&nv.value).map(ReceiverStrategy::With)
+            }
+            _ => Err(darling::Error::custom(
+                "expected exactly one of `pojo`, `native_pointer` or `with`",
+            )),
+        }
+    }
+}
+
+impl BridgeParams {
+    /// Parse the arguments of a `#[bridge]` attribute.
+    pub(crate) fn from_args(args: TokenStream) -> darling::Result<Self> {
+        let params = Self::from_list(&darling::ast::NestedMeta::parse_meta_list(args)?)?;
+
+        for affix in params.symbol_prefix.iter().chain(params.symbol_suffix.iter()) {
+            if affix.is_empty() || !affix.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(darling::Error::custom(format!(
+                    "invalid symbol affix `{}`: only ASCII letters, digits and `_` are allowed",
+                    affix
+                )));
+            }
+        }
+
+        Ok(params)
+    }
+
+    /// Null-handling policy of the module's exported methods. Defaults to [`NullPolicy::Error`].
+    pub(crate) fn null_policy(&self) -> NullPolicy {
+        self.null_policy.unwrap_or_default()
+    }
+
+    /// Name of the Java native method bound to the Rust method `method_name`.
+    pub(crate) fn java_method_name(&self, method_name: &str) -> String {
+        [
+            self.symbol_prefix.as_deref().unwrap_or(""),
+            method_name,
+            self.symbol_suffix.as_deref().unwrap_or(""),
+        ]
+        .concat()
+    }
+}
+
+/// Entry of the `bridge_methods()` function of `#[bridge(descriptors)]` modules.
+///
+/// The function is outside of the impl blocks, so the lifetimes of the types in `descriptor` are replaced with `'static`
+/// and `Self` with the struct type.
+pub(crate) fn bridge_method_entry(
+    struct_context: &StructContext,
+    kind: &str,
+    class: &str,
+    name: &str,
+    descriptor: TokenStream,
+    is_static: bool,
+) -> TokenStream {
+    let kind = Ident::new(kind, Span::call_site());
+    let self_type = freestanding_tokens(
+        struct_context.struct_type.to_token_stream(),
+        &TokenStream::new(),
+    );
+    let descriptor = freestanding_tokens(descriptor, &self_type);
+
+    quote! {
+        ::robusta_jni::classfile::BridgeMethod {
+            kind: ::robusta_jni::classfile::MethodKind::#kind,
+            class: ::std::string::ToString::to_string(#class),
+            name: ::std::string::ToString::to_string(#name),
+            descriptor: ::std::string::ToString::to_string(&#descriptor),
+            is_static: #is_static,
+        }
+    }
 }
 
 #[derive(Clone, FromMeta)]
 pub enum CallType {
-    Safe(Option<SafeParams>),
+    Safe(Option<Box<SafeParams>>),
     Unchecked(Flag),
 }
 
+impl CallType {
+    /// Path of the context given to the conversions of a `call_type(safe(with_context = ...))` method, if any.
+    pub(crate) fn conversion_context(&self) -> Option<&ExprPath> {
+        match self {
+            CallType::Safe(Some(params)) => params.with_context.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Package of the exception classes named after the error type with `call_type(safe(derive_class = ...))`, if any.
+    pub(crate) fn derive_class(&self) -> Option<&JavaPath> {
+        match self {
+            CallType::Safe(Some(params)) => params.derive_class.as_ref(),
+            _ => None,
+        }
+    }
+}
+
 pub struct CallTypeAttribute {
     pub(crate) attr: Attribute,
     pub(crate) call_type: CallType,
+    pub(crate) both: Option<BothParams>,
 }
 
 impl Parse for CallTypeAttribute {
@@ -546,9 +986,32 @@ impl Parse for CallTypeAttribute {
         // Special-case `call_type(safe)` without further parentheses
         // TODO: Find out if it's possible to use darling to allow `call_type(safe)` *and* `call_type(safe(message = "foo"))` etc.
         if attr_meta.to_token_stream().to_string() == "call_type(safe)" {
+            return Ok(CallTypeAttribute {
+                attr: attribute,
+                call_type: CallType::Safe(None),
+                both: None,
+            });
+        }
+
+        // `call_type(both(...))` is not a call type by itself, but a request to export one method per call type
+        if let Some(both_meta) = attr_meta
+            .require_list()
+            .and_then(|l| l.parse_args::<Meta>())
+            .ok()
+            .filter(|m| m.path().is_ident("both"))
+        {
+            let both = BothParams::from_meta(&both_meta).map_err(|e| {
+                Error::new(
+                    both_meta.span(),
+                    format!("invalid `call_type` attribute options ({})", e),
+                )
+            })?;
+            both.validate().map_err(|e| Error::new(both_meta.span(), e))?;
+
             Ok(CallTypeAttribute {
                 attr: attribute,
                 call_type: CallType::Safe(None),
+                both: Some(both),
             })
         } else {
             CallType::from_meta(&attr_meta)
@@ -561,7 +1024,425 @@ impl Parse for CallTypeAttribute {
                 .map(|c| CallTypeAttribute {
                     attr: attribute,
                     call_type: c,
+                    both: None,
                 })
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn package_of(attr: Attribute) -> String {
+        JavaPath::from_package_attribute(&attr).unwrap().to_string()
+    }
+
+    #[test]
+    fn default_package_attributes() {
+        assert_eq!(package_of(parse_quote! { #[package] }), "");
+        assert_eq!(package_of(parse_quote! { #[package()] }), "");
+        assert_eq!(package_of(parse_quote! { #[package("")] }), "");
+    }
+
+    #[test]
+    fn named_package_attributes() {
+        assert_eq!(package_of(parse_quote! { #[package(com.example)] }), "com.example");
+        assert_eq!(package_of(parse_quote! { #[package("com.example")] }), "com.example");
+        assert_eq!(package_of(parse_quote! { #[package(Foo)] }), "Foo");
+    }
+
+    #[test]
+    fn kotlin_class_paths() {
+        let file_class = JavaPath::from_package_attribute(&parse_quote! { #[package(com.example)] }).unwrap();
+        assert_eq!(file_class.class_classpath_path("FooKt"), "com/example/FooKt");
+        assert_eq!(file_class.class_jni_name("FooKt"), "com_example_FooKt");
+
+        let companion = JavaPath::from_package_attribute(&parse_quote! { #[package(com.example.Foo$)] }).unwrap();
+        assert_eq!(companion.to_string(), "com.example.Foo$");
+        assert_eq!(companion.class_classpath_path("Companion"), "com/example/Foo$Companion");
+        assert_eq!(companion.class_jni_name("Companion"), "com_example_Foo_00024Companion");
+
+        let companion = JavaPath::from_package_attribute(&parse_quote! { #[package("com.example.Foo$")] }).unwrap();
+        assert_eq!(companion.class_jni_name("Companion"), "com_example_Foo_00024Companion");
+    }
+
+    #[test]
+    fn bridge_params_symbol_affixes() {
+        let params = BridgeParams::from_args(quote::quote! { symbol_prefix = "libA_" }).unwrap();
+        assert_eq!(params.java_method_name("foo"), "libA_foo");
+
+        let params = BridgeParams::from_args(TokenStream::new()).unwrap();
+        assert_eq!(params.java_method_name("foo"), "foo");
+
+        assert!(BridgeParams::from_args(quote::quote! { symbol_suffix = "a-b" }).is_err());
+    }
+
+    #[test]
+    fn unit_and_tuple_structs_are_bridged() {
+        let module: JNIBridgeModule = syn::parse2(quote::quote! {
+            mod jni {
+                #[package(com.example)]
+                pub struct Marker;
+
+                impl Marker {
+                    pub extern "jni" fn answer() -> i32 {
+                        42
+                    }
+                }
+
+                #[package(com.example)]
+                pub struct Counter(i32);
+
+                impl Counter {
+                    pub extern "jni" fn increment(v: i32) -> i32 {
+                        v + 1
+                    }
+                }
+            }
+        })
+        .unwrap();
+        assert_eq!(module.package_map["Marker"].path.to_string(), "com.example");
+        assert_eq!(module.package_map["Counter"].path.to_string(), "com.example");
+
+        let output = ModTransformer::new(module, BridgeParams::default())
+            .transform_module()
+            .to_string();
+        assert!(output.contains("Java_com_example_Marker_answer"));
+        assert!(output.contains("Java_com_example_Counter_increment"));
+        assert!(output.contains("pub struct Marker ;"));
+        assert!(output.contains("pub struct Counter (i32) ;"));
+    }
+
+    #[test]
+    fn class_aliases_export_methods_for_each_class() {
+        let module: JNIBridgeModule = syn::parse2(quote::quote! {
+            mod jni {
+                #[package(com.example)]
+                #[class_alias(com.example.FooV2)]
+                #[class_alias(com.legacy.Foo)]
+                pub struct FooV1;
+
+                impl FooV1 {
+                    pub extern "jni" fn answer() -> i32 {
+                        42
+                    }
+                }
+            }
+        })
+        .unwrap();
+
+        let output = ModTransformer::new(module, BridgeParams::default())
+            .transform_module()
+            .to_string();
+        assert!(output.contains("Java_com_example_FooV1_answer"));
+        assert!(output.contains("Java_com_example_FooV2_answer"));
+        assert!(output.contains("Java_com_legacy_Foo_answer"));
+        assert!(!output.contains("class_alias"));
+    }
+
+    #[test]
+    fn descriptors_list_bridged_methods() {
+        let module = || -> JNIBridgeModule {
+            syn::parse2(quote::quote! {
+                mod jni {
+                    #[package(com.example)]
+                    pub struct Foo;
+
+                    impl Foo {
+                        pub extern "jni" fn answer(v: i32) -> i32 {
+                            v
+                        }
+
+                        pub extern "java" fn hello(env: &JNIEnv) -> JniResult<()> {}
+                    }
+                }
+            })
+            .unwrap()
+        };
+
+        let params = BridgeParams::from_args(quote::quote! { descriptors }).unwrap();
+        let output = ModTransformer::new(module(), params)
+            .transform_module()
+            .to_string();
+        assert!(output.contains("pub fn bridge_methods ()"));
+        assert!(output.contains("MethodKind :: Exported"));
+        assert!(output.contains("MethodKind :: Imported"));
+        assert!(output.contains("\"answer\""));
+        assert!(output.contains("\"hello\""));
+
+        let output = ModTransformer::new(module(), BridgeParams::default())
+            .transform_module()
+            .to_string();
+        assert!(!output.contains("bridge_methods"));
+    }
+
+    #[test]
+    fn dispatcher_routes_to_exported_methods() {
+        let module: JNIBridgeModule = syn::parse2(quote::quote! {
+            mod jni {
+                #[package(com.example)]
+                #[dispatcher]
+                pub struct Foo;
+
+                impl Foo {
+                    pub extern "jni" fn answer(v: i32) -> i32 {
+                        v
+                    }
+
+                    pub extern "jni" fn reset(self) {}
+                }
+
+                impl Foo {
+                    pub extern "jni" fn greet(name: String) -> String {
+                        name
+                    }
+                }
+            }
+        })
+        .unwrap();
+
+        let output = ModTransformer::new(module, BridgeParams::default())
+            .transform_module()
+            .to_string();
+        assert_eq!(
+            output.matches("fn Java_com_example_Foo_invoke").count(),
+            1
+        );
+        assert!(output.contains("(\"answer\" , 1i32) => call . returned (Java_com_example_Foo_answer (call . env () ? , class , call . arg (0i32 , < i32 as :: robusta_jni :: convert :: Signature > :: SIG_TYPE) ?))"));
+        assert!(output.contains("(\"reset\" , 1i32) => { Java_com_example_Foo_reset (call . env () ? , call . arg (0i32 , \"Lcom/example/Foo;\") ?) ;"));
+        assert!(output.contains("(\"greet\" , 1i32) =>"));
+        assert!(!output.contains("# [dispatcher]"));
+    }
+
+    #[test]
+    fn hot_methods_are_inlined_for_both_call_types() {
+        let module: JNIBridgeModule = syn::parse2(quote::quote! {
+            mod jni {
+                #[package(com.example)]
+                pub struct Foo;
+
+                impl Foo {
+                    #[hot]
+                    pub extern "jni" fn safe(v: i32) -> i32 {
+                        v
+                    }
+
+                    #[hot]
+                    #[call_type(unchecked)]
+                    pub extern "jni" fn unchecked(v: i32) -> i32 {
+                        v
+                    }
+                }
+            }
+        })
+        .unwrap();
+
+        let output = ModTransformer::new(module, BridgeParams::default())
+            .transform_module()
+            .to_string();
+        assert!(output.contains("# [inline (always)] pub fn safe (v : i32) -> i32"));
+        assert!(output.contains("# [inline (always)] pub fn unchecked (v : i32) -> i32"));
+        assert!(output.contains("# [inline (always)] fn outer"));
+        assert!(!output.contains("# [hot]"));
+    }
+
+    #[test]
+    fn receiver_strategies_reconstruct_self() {
+        let module: JNIBridgeModule = syn::parse2(quote::quote! {
+            mod jni {
+                #[package(com.example)]
+                #[receiver(native_pointer = "handle")]
+                pub struct Tally;
+
+                impl Tally {
+                    pub extern "jni" fn total(&self) -> i32 { 0 }
+                    pub extern "jni" fn add(&mut self, v: i32) -> i32 { v }
+                    pub extern "jni" fn close(self) {}
+                }
+
+                #[package(com.example)]
+                #[receiver(pojo)]
+                pub struct Point;
+
+                impl Point {
+                    pub extern "jni" fn length(&self) -> i32 { 0 }
+                }
+
+                #[package(com.example)]
+                #[receiver(with = crate::interval_from_bounds)]
+                pub struct Interval;
+
+                impl Interval {
+                    pub extern "jni" fn length(&self) -> i32 { 0 }
+                }
+            }
+        })
+        .unwrap();
+
+        let output = ModTransformer::new(module, BridgeParams::default())
+            .transform_module()
+            .to_string();
+        assert!(output.contains("borrow_native_pointer (& env , receiver , \"handle\")"));
+        assert!(output.contains("borrow_native_pointer_mut (& env , receiver , \"handle\")"));
+        assert!(output.contains("take_native_pointer (& env , receiver , \"handle\")"));
+        assert!(output.contains("TryFromJavaValue :: try_from (receiver , & env)"));
+        assert!(output.contains("& crate :: interval_from_bounds (& env , receiver)"));
+        assert!(output.contains("receiver : :: robusta_jni :: jni :: objects :: JObject < 'env >"));
+        assert!(!output.contains("# [receiver"));
+    }
+
+    #[test]
+    fn assert_send_structs_are_checked() {
+        let module: JNIBridgeModule = syn::parse2(quote::quote! {
+            mod jni {
+                #[package(com.example)]
+                #[assert_send]
+                pub struct Foo<'env: 'borrow, 'borrow> {
+                    raw: AutoLocal<'env, 'borrow>,
+                }
+
+                impl<'env: 'borrow, 'borrow> Foo<'env, 'borrow> {
+                    pub extern "jni" fn answer() -> i32 {
+                        42
+                    }
+                }
+            }
+        })
+        .unwrap();
+
+        let output = ModTransformer::new(module, BridgeParams::default())
+            .transform_module()
+            .to_string();
+        assert!(output.contains("fn assert_send < T : ? Sized + :: std :: marker :: Send > ()"));
+        assert!(output.contains("assert_send :: < Foo < 'env , 'borrow > > ()"));
+        assert!(!output.contains("# [assert_send]"));
+    }
+
+    #[test]
+    fn private_methods_are_exported() {
+        let module: JNIBridgeModule = syn::parse2(quote::quote! {
+            mod jni {
+                #[package(com.example)]
+                pub struct Foo;
+
+                impl Foo {
+                    extern "jni" fn private(v: i32) -> i32 {
+                        v
+                    }
+
+                    pub(crate) extern "jni" fn restricted(v: i32) -> i32 {
+                        v
+                    }
+                }
+            }
+        })
+        .unwrap();
+
+        let output = ModTransformer::new(module, BridgeParams::default())
+            .transform_module()
+            .to_string();
+        assert!(output.contains("pub extern \"system\" fn Java_com_example_Foo_private"));
+        assert!(output.contains("pub extern \"system\" fn Java_com_example_Foo_restricted"));
+        assert!(output.contains("fn private (v : i32)"));
+        assert!(output.contains("pub (crate) fn restricted (v : i32)"));
+        assert!(!output.contains("extern \"jni\""));
+    }
+
+    #[test]
+    fn default_allows_can_be_disabled() {
+        let module = || -> JNIBridgeModule {
+            syn::parse2(quote::quote! {
+                mod jni {
+                    #[package(com.example)]
+                    pub struct Foo;
+
+                    impl Foo {
+                        pub extern "jni" fn getValue() -> i32 {
+                            0
+                        }
+                    }
+                }
+            })
+            .unwrap()
+        };
+        let params = BridgeParams::from_args(quote::quote! { no_default_allows }).unwrap();
+
+        let default_output = ModTransformer::new(module(), BridgeParams::default())
+            .transform_module()
+            .to_string();
+        let output = ModTransformer::new(module(), params)
+            .transform_module()
+            .to_string();
+
+        assert!(default_output.contains("# ! [allow (non_snake_case)]"));
+        assert!(!output.contains("allow"));
+    }
+
+    #[test]
+    fn null_policy_handles_null_arguments() {
+        let module = || -> JNIBridgeModule {
+            syn::parse2(quote::quote! {
+                mod jni {
+                    #[package(com.example)]
+                    pub struct Foo;
+
+                    impl Foo {
+                        pub extern "jni" fn length(s: String) -> i32 {
+                            s.len() as i32
+                        }
+                    }
+                }
+            })
+            .unwrap()
+        };
+        let output_with = |args| {
+            let params = BridgeParams::from_args(args).unwrap();
+            ModTransformer::new(module(), params)
+                .transform_module()
+                .to_string()
+        };
+
+        let error_output = output_with(TokenStream::new());
+        let panic_output = output_with(quote::quote! { null_policy = "panic" });
+        let default_output = output_with(quote::quote! { null_policy = "default" });
+
+        assert!(!error_output.contains("NullPtr"));
+        assert!(panic_output
+            .contains("Err (:: robusta_jni :: jni :: errors :: Error :: NullPtr (_)) => panic !"));
+        assert!(default_output.contains("Err (:: robusta_jni :: jni :: errors :: Error :: NullPtr (_)) => :: std :: default :: Default :: default ()"));
+        assert!(BridgeParams::from_args(quote::quote! { null_policy = "ignore" }).is_err());
+    }
+
+    #[test]
+    fn where_clauses_are_kept_on_jni_functions() {
+        let module: JNIBridgeModule = syn::parse2(quote::quote! {
+            mod jni {
+                #[package(com.example)]
+                pub struct Foo<'env: 'borrow, 'borrow> {
+                    raw: AutoLocal<'env, 'borrow>,
+                }
+
+                impl<'env, 'borrow> Foo<'env, 'borrow>
+                where
+                    'env: 'borrow,
+                    Self: Clone,
+                {
+                    pub extern "jni" fn getValue(self) -> i32 {
+                        0
+                    }
+                }
+            }
+        })
+        .unwrap();
+
+        let output = ModTransformer::new(module, BridgeParams::default())
+            .transform_module()
+            .to_string();
+
+        assert!(output.contains(
+            "impl < 'env , 'borrow > Foo < 'env , 'borrow > where 'env : 'borrow , Self : Clone"
+        ));
+        assert!(output.contains("-> < i32 as :: robusta_jni :: convert :: TryIntoJavaValue < 'env > > :: Target where 'env : 'borrow , Foo < 'env , 'borrow > : Clone {"));
+    }
+}
@@ -1,5 +1,6 @@
 use std::iter;
 
+use proc_macro2::{Group, Ident, TokenStream, TokenTree};
 use proc_macro_error::emit_error;
 use syn::{
     parse_quote, FnArg, Pat, PatIdent, PatType, Path, PathArguments, Signature, Type, TypeReference,
@@ -19,6 +20,33 @@ pub fn canonicalize_path(path: &Path) -> Path {
     result
 }
 
+/// Make the types of `tokens` nameable outside of their impl block: lifetimes are replaced with `'static`,
+/// and `Self` with `self_type`.
+pub fn freestanding_tokens(tokens: TokenStream, self_type: &TokenStream) -> TokenStream {
+    let mut after_quote = false;
+
+    tokens
+        .into_iter()
+        .flat_map(|token| {
+            let tokens: Vec<TokenTree> = match token {
+                TokenTree::Group(g) => {
+                    let mut group =
+                        Group::new(g.delimiter(), freestanding_tokens(g.stream(), self_type));
+                    group.set_span(g.span());
+                    vec![TokenTree::Group(group)]
+                }
+                TokenTree::Ident(i) if after_quote => {
+                    vec![TokenTree::Ident(Ident::new("static", i.span()))]
+                }
+                TokenTree::Ident(i) if i == "Self" => self_type.clone().into_iter().collect(),
+                t => vec![t],
+            };
+            after_quote = matches!(tokens.last(), Some(TokenTree::Punct(p)) if p.as_char() == '\'');
+            tokens
+        })
+        .collect()
+}
+
 pub fn is_self_method(signature: &Signature) -> bool {
     signature.inputs.iter().any(|i| match i {
         FnArg::Receiver(_) => true,
@@ -2,14 +2,19 @@ use core::option::Option::{None, Some};
 use core::result::Result::{Err, Ok};
 use std::collections::BTreeMap;
 
+use darling::FromMeta;
+use proc_macro2::Span;
 use proc_macro_error::{emit_error, emit_warning};
 use quote::ToTokens;
 use syn::parse::{Parse, ParseBuffer};
 use syn::spanned::Spanned;
 use syn::visit::Visit;
-use syn::{Attribute, Error, GenericParam, Item, ItemImpl, ItemMod, ItemStruct, Result, Type};
+use syn::{
+    Attribute, Error, GenericParam, ImplItem, Item, ItemImpl, ItemMod, ItemStruct, Result, Type,
+};
 
-use crate::transformation::JavaPath;
+use crate::transformation::{JavaPath, ReceiverStrategy};
+use crate::utils::{get_abi, is_self_method};
 
 struct AttribItemChecker {
     valid: bool,
@@ -153,9 +158,22 @@ impl<'ast> Visit<'ast> for StructDeclVisitor<'ast> {
     }
 }
 
+/// Java package of a bridged struct, along with the span of its `#[package]` attribute,
+/// the classes given with `#[class_alias]` attributes, whether it has a `#[module_loader]` attribute,
+/// the `#[receiver]` strategy of its exported `self` methods and whether it has a `#[dispatcher]` attribute.
+#[derive(Clone)]
+pub(crate) struct PackageDeclaration {
+    pub(crate) path: JavaPath,
+    pub(crate) span: Span,
+    pub(crate) class_aliases: Vec<JavaPath>,
+    pub(crate) module_loader: bool,
+    pub(crate) receiver: ReceiverStrategy,
+    pub(crate) dispatcher: bool,
+}
+
 pub(crate) struct JNIBridgeModule {
     pub(crate) module_decl: ItemMod,
-    pub(crate) package_map: BTreeMap<String, Option<JavaPath>>,
+    pub(crate) package_map: BTreeMap<String, PackageDeclaration>,
 }
 
 impl Parse for JNIBridgeModule {
@@ -263,23 +281,120 @@ impl Parse for JNIBridgeModule {
                 valid_input = false;
             });
 
-        let package_map: BTreeMap<String, Option<JavaPath>> = bridged_structs
-            .iter()
-            .map(|s| {
-                let name = s.ident.to_string();
-                let package_path = s
-                    .attrs
-                    .iter()
-                    .filter(|a| a.path().segments.last().unwrap().ident == "package")
-                    .map(|a| a.parse_args::<JavaPath>().unwrap())
-                    .next()
-                    .unwrap();
+        /* Exported self methods convert the receiver from a `JObject<'env>`, so lifetime-parametrized structs must use the `'env` lifetime.
+         * Check this here to report the error at the struct definition instead of deep into the generated code.
+         */
+        bridged_impls.iter().for_each(|(struct_item, item_impl)| {
+            let struct_lifetimes: Vec<_> = struct_item.generics.lifetimes().collect();
+            let has_env_lifetime = struct_lifetimes.iter().any(|l| l.lifetime.ident == "env");
 
-                let package = Some(package_path);
+            if struct_lifetimes.is_empty() || has_env_lifetime {
+                return;
+            }
 
-                (name, package)
-            })
-            .collect();
+            let exported_self_method = item_impl.items.iter().find_map(|i| match i {
+                ImplItem::Fn(f)
+                    if get_abi(&f.sig).as_deref() == Some("jni") && is_self_method(&f.sig) =>
+                {
+                    Some(f)
+                }
+                _ => None,
+            });
+
+            if let Some(method) = exported_self_method {
+                emit_error!(struct_item.generics, "struct `{}` must declare an `'env` lifetime to support exported `self` methods", struct_item.ident;
+                    help = "rename one of the struct lifetimes to `'env` (e.g. `struct {}<'env: 'borrow, 'borrow>`)", struct_item.ident;
+                    note = method.sig.ident.span() => "`self` method declared here");
+                valid_input = false;
+            }
+        });
+
+        let mut package_map: BTreeMap<String, PackageDeclaration> = BTreeMap::new();
+        bridged_structs.iter().for_each(|s| {
+            let name = s.ident.to_string();
+            let package_attribute = s
+                .attrs
+                .iter()
+                .find(|a| a.path().segments.last().unwrap().ident == "package")
+                .unwrap();
+            let class_aliases = s
+                .attrs
+                .iter()
+                .filter(|a| a.path().is_ident("class_alias"))
+                .filter_map(|a| match a.parse_args::<JavaPath>() {
+                    Ok(alias) if !alias.to_classpath_path().is_empty() => Some(alias),
+                    Ok(_) => {
+                        emit_error!(a, "`class_alias` requires the fully qualified name of a Java class";
+                            help = "e.g. `#[class_alias(com.example.FooV2)]`");
+                        valid_input = false;
+                        None
+                    }
+                    Err(e) => {
+                        emit_error!(e.span(), "{}", e);
+                        valid_input = false;
+                        None
+                    }
+                })
+                .collect();
+            let module_loader = s
+                .attrs
+                .iter()
+                .filter(|a| a.path().is_ident("module_loader"))
+                .map(|a| {
+                    if let Err(e) = a.meta.require_path_only() {
+                        emit_error!(e.span(), "`module_loader` doesn't take arguments";
+                            help = "register the loader at runtime with `robusta_jni::class_loader::register_module_loader`");
+                        valid_input = false;
+                    }
+                })
+                .count()
+                > 0;
+            let dispatcher = s
+                .attrs
+                .iter()
+                .filter(|a| a.path().is_ident("dispatcher"))
+                .map(|a| {
+                    if let Err(e) = a.meta.require_path_only() {
+                        emit_error!(e.span(), "`dispatcher` doesn't take arguments";
+                            help = "declare the dispatcher in Java as `public static native Object invoke(String method, Object[] args)`");
+                        valid_input = false;
+                    }
+                })
+                .count()
+                > 0;
+            let mut receiver_attributes = s.attrs.iter().filter(|a| a.path().is_ident("receiver"));
+            let receiver = match receiver_attributes.next() {
+                Some(a) => ReceiverStrategy::from_meta(&a.meta).unwrap_or_else(|e| {
+                    emit_error!(a, "invalid `receiver` attribute ({})", e;
+                        help = "use one of `#[receiver(pojo)]`, `#[receiver(native_pointer = \"field\")]` or `#[receiver(with = path::to::function)]`");
+                    valid_input = false;
+                    ReceiverStrategy::default()
+                }),
+                None => ReceiverStrategy::default(),
+            };
+            if let Some(a) = receiver_attributes.next() {
+                emit_error!(a, "struct `{}` has more than one `receiver` attribute", s.ident);
+                valid_input = false;
+            }
+            let declaration = PackageDeclaration {
+                path: JavaPath::from_package_attribute(package_attribute).unwrap(),
+                span: package_attribute.span(),
+                class_aliases,
+                module_loader,
+                receiver,
+                dispatcher,
+            };
+
+            /* Impl blocks are matched with structs by name, so structs declared in different nested modules must have different names */
+            if let Some(previous) = package_map.get(&name) {
+                emit_error!(declaration.span, "struct `{}` is bridged more than once", name;
+                    note = previous.span => "previously declared here";
+                    help = "rename one of the structs");
+                valid_input = false;
+            } else {
+                package_map.insert(name, declaration);
+            }
+        });
 
         if !valid_input {
             Err(Error::new(
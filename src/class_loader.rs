@@ -0,0 +1,109 @@
+//! Class lookups through an application class loader.
+//!
+//! `FindClass` resolves classes with the class loader of the calling native method, or with the system class loader
+//! from native threads attached to the JVM, which can't see application classes (e.g. on Android or in plugin systems).
+//!
+//! Registering an application `ClassLoader` with [`register_class_loader`], usually in `JNI_OnLoad` or in an
+//! initialization native method, makes [`load_class`] resolve classes with its `loadClass` method instead.
+//! Imported static methods and constructors of a `#[bridge(class_loader)]` module look up their class with [`load_class`].
+//!
+//! ## Module class loaders
+//! On the Java module system, classes of a module layer created by the application (e.g. a plugin layer) are only visible through
+//! the class loaders of that layer. A loader given at runtime with [`register_module_loader`], e.g. the result of
+//! `ModuleLayer.findLoader("com.example.plugin")` passed to an initialization native method, is used by [`load_module_class`]:
+//!
+//! ```ignore
+//! pub extern "jni" fn init(env: &JNIEnv, loader: JObject<'env>) -> JniResult<()> {
+//!     robusta_jni::class_loader::register_module_loader(env, loader)
+//! }
+//! ```
+//!
+//! Imported static methods and constructors of structs marked with `#[module_loader]` (next to `#[package]`) look up their class
+//! with [`load_module_class`], in any bridge module.
+
+use std::sync::{PoisonError, RwLock};
+
+use jni::errors::{Error, Result};
+use jni::objects::{GlobalRef, JClass, JObject, JValue};
+use jni::JNIEnv;
+
+static CLASS_LOADER: RwLock<Option<GlobalRef>> = RwLock::new(None);
+static MODULE_LOADER: RwLock<Option<GlobalRef>> = RwLock::new(None);
+
+/// Register the class loader of `class` (e.g. `"com/example/Foo"`) as the one used by [`load_class`].
+pub fn register_class_loader(env: &JNIEnv, class: &str) -> Result<()> {
+    let class = env.find_class(class)?;
+    let loader = env
+        .call_method(class, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])?
+        .l()?;
+    let loader = env.new_global_ref(loader)?;
+
+    *CLASS_LOADER.write().unwrap_or_else(PoisonError::into_inner) = Some(loader);
+    Ok(())
+}
+
+/// Register `loader`, a `java.lang.ClassLoader`, as the one used by [`load_module_class`].
+pub fn register_module_loader(env: &JNIEnv, loader: JObject) -> Result<()> {
+    if loader.is_null() {
+        return Err(Error::NullPtr("module loader"));
+    }
+
+    if !env.is_instance_of(loader, "java/lang/ClassLoader")? {
+        return Err(Error::WrongJValueType("java.lang.ClassLoader", "object"));
+    }
+
+    let loader = env.new_global_ref(loader)?;
+
+    *MODULE_LOADER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = Some(loader);
+    Ok(())
+}
+
+/// Look up the class with the given classpath path (e.g. `"com/example/Foo"`) with the registered class loader,
+/// or with `FindClass` if none was registered.
+pub fn load_class<'env>(env: &JNIEnv<'env>, classpath_path: &str) -> Result<JClass<'env>> {
+    let loader = registered_loader(&CLASS_LOADER);
+
+    match loader {
+        Some(loader) => load_class_with(env, &loader, classpath_path),
+        None => env.find_class(classpath_path),
+    }
+}
+
+/// Look up the class with the given classpath path with the loader registered with [`register_module_loader`],
+/// or with [`load_class`] if none was registered.
+pub fn load_module_class<'env>(env: &JNIEnv<'env>, classpath_path: &str) -> Result<JClass<'env>> {
+    let loader = registered_loader(&MODULE_LOADER);
+
+    match loader {
+        Some(loader) => load_class_with(env, &loader, classpath_path),
+        None => load_class(env, classpath_path),
+    }
+}
+
+/// Clone the registered loader out of `lock`, so that the lock isn't held while `loadClass` runs Java code
+/// (e.g. a static initializer registering another loader).
+fn registered_loader(lock: &RwLock<Option<GlobalRef>>) -> Option<GlobalRef> {
+    lock.read().unwrap_or_else(PoisonError::into_inner).clone()
+}
+
+fn load_class_with<'env>(
+    env: &JNIEnv<'env>,
+    loader: &GlobalRef,
+    classpath_path: &str,
+) -> Result<JClass<'env>> {
+    // The loader is a global reference, so it outlives the local frame of `env`
+    let loader = unsafe { JObject::from_raw(loader.as_obj().into_raw()) };
+    let name = env.new_string(classpath_path.replace('/', "."))?;
+    let class = env
+        .call_method(
+            loader,
+            "loadClass",
+            "(Ljava/lang/String;)Ljava/lang/Class;",
+            &[JValue::Object(name.into())],
+        )?
+        .l()?;
+
+    Ok(JClass::from(class))
+}
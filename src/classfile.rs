@@ -0,0 +1,680 @@
+//! Validation of bridged methods against compiled Java classes.
+//!
+//! A `#[bridge(descriptors)]` module has a `bridge_methods()` function listing its exported and imported methods,
+//! with the descriptors computed from their signatures. [`validate`] checks them against `.class` files, e.g. from a test
+//! or a build script, so that a Java declaration drifting away from its Rust counterpart is caught before an
+//! `UnsatisfiedLinkError` or a `NoSuchMethodError` is raised at runtime:
+//!
+//! ```ignore
+//! let mismatches = robusta_jni::classfile::validate(&jni::bridge_methods(), &["build/classes/java/main/com/example/Foo.class"])?;
+//! for mismatch in &mismatches {
+//!     eprintln!("{}", mismatch);
+//! }
+//! assert!(mismatches.is_empty());
+//! ```
+//!
+//! Exported methods must be declared as `native` methods of their class, with the same descriptor and static-ness;
+//! untyped `JObject` parameters and return types match any reference type.
+//! Imported methods are only checked against the classes found among the given files, and only when a method with the same name
+//! is declared there, since they can be inherited from classes that aren't given (e.g. `Object.toString`).
+//! Methods with a `with_context` conversion context and methods of generic impl blocks aren't listed.
+
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// `ACC_STATIC` access flag of class file methods.
+const ACC_STATIC: u16 = 0x0008;
+/// `ACC_NATIVE` access flag of class file methods.
+const ACC_NATIVE: u16 = 0x0100;
+
+/// Whether a bridged method is implemented in Rust or in Java.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MethodKind {
+    /// An `extern "jni"` method, implementing a Java `native` method.
+    Exported,
+    /// An `extern "java"` method, calling a Java method.
+    Imported,
+}
+
+/// A bridged method, as listed by the `bridge_methods()` function of `#[bridge(descriptors)]` modules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BridgeMethod {
+    pub kind: MethodKind,
+    /// Classpath name of the Java class, e.g. `com/example/Foo`.
+    pub class: String,
+    /// Name of the Java method, `<init>` for constructors.
+    pub name: String,
+    /// Method descriptor, e.g. `(Ljava/lang/String;I)V`.
+    pub descriptor: String,
+    pub is_static: bool,
+}
+
+impl Display for BridgeMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let kind = match self.kind {
+            MethodKind::Exported => "exported",
+            MethodKind::Imported => "imported",
+        };
+        let modifier = if self.is_static { "static " } else { "" };
+
+        write!(
+            f,
+            "{} {}method `{}.{}{}`",
+            kind,
+            modifier,
+            self.class.replace('/', "."),
+            self.name,
+            self.descriptor
+        )
+    }
+}
+
+/// A method declared in a class file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassMethod {
+    pub access_flags: u16,
+    pub name: String,
+    pub descriptor: String,
+}
+
+impl ClassMethod {
+    pub fn is_static(&self) -> bool {
+        self.access_flags & ACC_STATIC != 0
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.access_flags & ACC_NATIVE != 0
+    }
+}
+
+/// The parts of a class file needed to validate bridged methods: class names and declared methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassFile {
+    /// Classpath name of the class, e.g. `com/example/Foo`.
+    pub name: String,
+    /// Classpath name of the superclass, `None` for `java/lang/Object`.
+    pub super_class: Option<String>,
+    pub methods: Vec<ClassMethod>,
+}
+
+/// Error while reading a class file.
+#[derive(Debug)]
+pub enum ClassFileError {
+    Io(PathBuf, std::io::Error),
+    /// The file isn't a valid class file, with the offset at which parsing failed.
+    Malformed(Option<PathBuf>, usize),
+}
+
+impl Display for ClassFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ClassFileError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            ClassFileError::Malformed(Some(path), offset) => {
+                write!(
+                    f,
+                    "{}: malformed class file at offset {}",
+                    path.display(),
+                    offset
+                )
+            }
+            ClassFileError::Malformed(None, offset) => {
+                write!(f, "malformed class file at offset {}", offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ClassFileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ClassFileError::Io(_, e) => Some(e),
+            ClassFileError::Malformed(..) => None,
+        }
+    }
+}
+
+/// Big-endian reader over the bytes of a class file.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], ClassFileError> {
+        let end = self
+            .offset
+            .checked_add(n)
+            .filter(|&end| end <= self.bytes.len());
+        match end {
+            Some(end) => {
+                let taken = &self.bytes[self.offset..end];
+                self.offset = end;
+                Ok(taken)
+            }
+            None => Err(ClassFileError::Malformed(None, self.offset)),
+        }
+    }
+
+    fn u8(&mut self) -> Result<u8, ClassFileError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, ClassFileError> {
+        let b = self.take(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, ClassFileError> {
+        let b = self.take(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn malformed(&self) -> ClassFileError {
+        ClassFileError::Malformed(None, self.offset)
+    }
+}
+
+/// Constant pool entries, keeping only what names are made of.
+#[derive(Clone)]
+enum Constant {
+    Utf8(String),
+    Class(u16),
+    Other,
+}
+
+impl ClassFile {
+    /// Parse the contents of a class file.
+    pub fn parse(bytes: &[u8]) -> Result<Self, ClassFileError> {
+        let mut r = Reader { bytes, offset: 0 };
+
+        if r.u32()? != 0xCAFE_BABE {
+            return Err(ClassFileError::Malformed(None, 0));
+        }
+        r.take(4)?; // minor and major versions
+
+        /* Constant pool indices start at 1, and `long` and `double` constants take two slots */
+        let constant_pool_count = r.u16()? as usize;
+        let mut constants = vec![Constant::Other; constant_pool_count.max(1)];
+        let mut i = 1;
+        while i < constant_pool_count {
+            let (constant, slots) = match r.u8()? {
+                1 => {
+                    let len = r.u16()? as usize;
+                    let s = String::from_utf8_lossy(r.take(len)?).into_owned();
+                    (Constant::Utf8(s), 1)
+                }
+                7 => (Constant::Class(r.u16()?), 1),
+                3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => {
+                    r.take(4)?;
+                    (Constant::Other, 1)
+                }
+                5 | 6 => {
+                    r.take(8)?;
+                    (Constant::Other, 2)
+                }
+                8 | 16 | 19 | 20 => {
+                    r.take(2)?;
+                    (Constant::Other, 1)
+                }
+                15 => {
+                    r.take(3)?;
+                    (Constant::Other, 1)
+                }
+                _ => return Err(r.malformed()),
+            };
+            constants[i] = constant;
+            i += slots;
+        }
+
+        let utf8 = |r: &Reader, index: u16| match constants.get(index as usize) {
+            Some(Constant::Utf8(s)) => Ok(s.clone()),
+            _ => Err(r.malformed()),
+        };
+        let class_name = |r: &Reader, index: u16| match constants.get(index as usize) {
+            Some(Constant::Class(name_index)) => utf8(r, *name_index),
+            _ => Err(r.malformed()),
+        };
+
+        r.u16()?; // access flags
+        let this_class = r.u16()?;
+        let name = class_name(&r, this_class)?;
+        let super_class = match r.u16()? {
+            0 => None,
+            index => Some(class_name(&r, index)?),
+        };
+
+        let interfaces_count = r.u16()? as usize;
+        r.take(interfaces_count * 2)?;
+
+        fn skip_attributes(r: &mut Reader) -> Result<(), ClassFileError> {
+            for _ in 0..r.u16()? {
+                r.u16()?;
+                let len = r.u32()? as usize;
+                r.take(len)?;
+            }
+            Ok(())
+        }
+
+        for _ in 0..r.u16()? {
+            r.take(6)?; // access flags, name and descriptor
+            skip_attributes(&mut r)?;
+        }
+
+        let methods_count = r.u16()?;
+        let mut methods = Vec::with_capacity(methods_count as usize);
+        for _ in 0..methods_count {
+            let access_flags = r.u16()?;
+            let name_index = r.u16()?;
+            let descriptor_index = r.u16()?;
+            methods.push(ClassMethod {
+                access_flags,
+                name: utf8(&r, name_index)?,
+                descriptor: utf8(&r, descriptor_index)?,
+            });
+            skip_attributes(&mut r)?;
+        }
+
+        Ok(ClassFile {
+            name,
+            super_class,
+            methods,
+        })
+    }
+
+    /// Read and parse a class file.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, ClassFileError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path).map_err(|e| ClassFileError::Io(path.to_path_buf(), e))?;
+
+        ClassFile::parse(&bytes).map_err(|e| match e {
+            ClassFileError::Malformed(None, offset) => {
+                ClassFileError::Malformed(Some(path.to_path_buf()), offset)
+            }
+            e => e,
+        })
+    }
+}
+
+/// What's wrong with a bridged method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Problem {
+    /// The class of an exported method isn't among the given class files.
+    MissingClass,
+    /// No method has this name and descriptor, with the descriptors of the methods that have the same name.
+    MissingMethod { candidates: Vec<String> },
+    /// The Java method of an exported method isn't `native`.
+    NotNative,
+    /// The Java method is static and the Rust one isn't, or the other way around.
+    StaticMismatch,
+}
+
+/// A bridged method that doesn't match the compiled Java classes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub method: BridgeMethod,
+    /// Class file of the method's class, if it was found.
+    pub file: Option<PathBuf>,
+    pub problem: Problem,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}: ", file.display())?;
+        }
+
+        match &self.problem {
+            Problem::MissingClass => write!(f, "{}: class not found", self.method),
+            Problem::MissingMethod { candidates } if candidates.is_empty() => {
+                write!(f, "{}: method not found", self.method)
+            }
+            Problem::MissingMethod { candidates } => write!(
+                f,
+                "{}: method not found, declared descriptors are {}",
+                self.method,
+                candidates.join(", ")
+            ),
+            Problem::NotNative => write!(f, "{}: Java method isn't `native`", self.method),
+            Problem::StaticMismatch if self.method.is_static => {
+                write!(f, "{}: Java method isn't static", self.method)
+            }
+            Problem::StaticMismatch => write!(f, "{}: Java method is static", self.method),
+        }
+    }
+}
+
+/// Check bridged methods against the given class files, returning the methods that don't match.
+///
+/// Fails if a class file can't be read or parsed.
+pub fn validate<P: AsRef<Path>>(
+    methods: &[BridgeMethod],
+    class_files: &[P],
+) -> Result<Vec<Mismatch>, ClassFileError> {
+    let classes = class_files
+        .iter()
+        .map(|p| Ok((p.as_ref().to_path_buf(), ClassFile::read(p)?)))
+        .collect::<Result<Vec<_>, ClassFileError>>()?;
+    let class_named = |name: &str| classes.iter().find(|(_, c)| c.name == name);
+
+    let mut mismatches = Vec::new();
+
+    for method in methods {
+        let mismatch = |file: &Path, problem| Mismatch {
+            method: method.clone(),
+            file: Some(file.to_path_buf()),
+            problem,
+        };
+
+        match method.kind {
+            MethodKind::Exported => {
+                let (file, class) = match class_named(&method.class) {
+                    Some(c) => c,
+                    None => {
+                        mismatches.push(Mismatch {
+                            method: method.clone(),
+                            file: None,
+                            problem: Problem::MissingClass,
+                        });
+                        continue;
+                    }
+                };
+
+                match class
+                    .methods
+                    .iter()
+                    .find(|m| m.name == method.name && accepts(&method.descriptor, &m.descriptor))
+                {
+                    None => mismatches.push(mismatch(file, missing_method(&class.methods, method))),
+                    Some(m) if !m.is_native() => {
+                        mismatches.push(mismatch(file, Problem::NotNative))
+                    }
+                    Some(m) if m.is_static() != method.is_static => {
+                        mismatches.push(mismatch(file, Problem::StaticMismatch))
+                    }
+                    Some(_) => {}
+                }
+            }
+            MethodKind::Imported => {
+                /* The method can be declared by the class or by one of its superclasses */
+                let mut hierarchy = Vec::new();
+                let mut next = Some(method.class.as_str());
+                while let Some(c) = next.and_then(&class_named) {
+                    next = c.1.super_class.as_deref();
+                    hierarchy.push(c);
+                }
+
+                let file = match hierarchy.first() {
+                    Some((file, _)) => file,
+                    None => continue,
+                };
+                let declared: Vec<_> = hierarchy
+                    .iter()
+                    .flat_map(|(_, c)| c.methods.iter())
+                    .collect();
+
+                match declared
+                    .iter()
+                    .find(|m| m.name == method.name && m.descriptor == method.descriptor)
+                {
+                    Some(m) if m.is_static() != method.is_static => {
+                        mismatches.push(mismatch(file, Problem::StaticMismatch))
+                    }
+                    Some(_) => {}
+                    None if declared.iter().any(|m| m.name == method.name) => mismatches.push(
+                        mismatch(file, missing_method(declared.iter().copied(), method)),
+                    ),
+                    None => {}
+                }
+            }
+        }
+    }
+
+    Ok(mismatches)
+}
+
+/// Whether the native method declared with `declared` can be implemented by an exported method with the computed descriptor.
+///
+/// An untyped `JObject` (`Ljava/lang/Object;`) stands for any reference type, e.g. a `ClassLoader` parameter.
+fn accepts(computed: &str, declared: &str) -> bool {
+    let (computed, declared) = (field_types(computed), field_types(declared));
+    computed.len() == declared.len()
+        && computed.iter().zip(&declared).all(|(c, d)| {
+            c == d || (*c == "Ljava/lang/Object;" && (d.starts_with('L') || d.starts_with('[')))
+        })
+}
+
+/// Field types of a method descriptor, with the return type last, e.g. `["I", "[J", "Ljava/lang/String;", "V"]`.
+fn field_types(descriptor: &str) -> Vec<&str> {
+    let mut types = Vec::new();
+    let mut start = 0;
+    let mut chars = descriptor.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '(' | ')' => start = i + 1,
+            '[' => {}
+            'L' => {
+                for (j, c) in chars.by_ref() {
+                    if c == ';' {
+                        types.push(&descriptor[start..=j]);
+                        start = j + 1;
+                        break;
+                    }
+                }
+            }
+            _ => {
+                types.push(&descriptor[start..=i]);
+                start = i + 1;
+            }
+        }
+    }
+    types
+}
+
+fn missing_method<'a>(
+    declared: impl IntoIterator<Item = &'a ClassMethod>,
+    method: &BridgeMethod,
+) -> Problem {
+    Problem::MissingMethod {
+        candidates: declared
+            .into_iter()
+            .filter(|m| m.name == method.name)
+            .map(|m| m.descriptor.clone())
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Bytes of a class file declaring `methods` (access flags, name and descriptor), with a `long` and a `double`
+    /// constant before their names.
+    fn class_bytes(
+        name: &str,
+        super_class: Option<&str>,
+        methods: &[(u16, &str, &str)],
+    ) -> Vec<u8> {
+        fn utf8(bytes: &mut Vec<u8>, s: &str) {
+            bytes.push(1);
+            bytes.extend((s.len() as u16).to_be_bytes());
+            bytes.extend(s.as_bytes());
+        }
+
+        let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 52];
+        let mut pool = Vec::new();
+        utf8(&mut pool, name); // 1
+        pool.extend([7, 0, 1]); // 2
+        utf8(&mut pool, super_class.unwrap_or("")); // 3
+        pool.extend([7, 0, 3]); // 4
+        pool.push(5); // 5 and 6
+        pool.extend(42i64.to_be_bytes());
+        pool.push(6); // 7 and 8
+        pool.extend(1.5f64.to_be_bytes());
+        for (_, name, descriptor) in methods {
+            utf8(&mut pool, name);
+            utf8(&mut pool, descriptor);
+        }
+
+        bytes.extend((9 + 2 * methods.len() as u16).to_be_bytes());
+        bytes.extend(pool);
+        bytes.extend([0, 0x21, 0, 2]); // access flags, this class
+        bytes.extend(if super_class.is_some() {
+            [0, 4]
+        } else {
+            [0, 0]
+        });
+        bytes.extend([0, 0, 0, 0]); // interfaces, fields
+        bytes.extend((methods.len() as u16).to_be_bytes());
+        for (i, (access_flags, _, _)) in methods.iter().enumerate() {
+            let name_index = 9 + 2 * i as u16;
+            bytes.extend(access_flags.to_be_bytes());
+            bytes.extend(name_index.to_be_bytes());
+            bytes.extend((name_index + 1).to_be_bytes());
+            bytes.extend([0, 0]); // attributes
+        }
+        bytes.extend([0, 0]); // class attributes
+        bytes
+    }
+
+    fn exported(name: &str, descriptor: &str, is_static: bool) -> BridgeMethod {
+        BridgeMethod {
+            kind: MethodKind::Exported,
+            class: "com/example/Foo".into(),
+            name: name.into(),
+            descriptor: descriptor.into(),
+            is_static,
+        }
+    }
+
+    #[test]
+    fn field_types_of_descriptor() {
+        assert_eq!(
+            field_types("(I[JLjava/lang/String;[[Ljava/lang/Object;)V"),
+            vec!["I", "[J", "Ljava/lang/String;", "[[Ljava/lang/Object;", "V"]
+        );
+        assert_eq!(
+            field_types("()[Ljava/lang/String;"),
+            vec!["[Ljava/lang/String;"]
+        );
+    }
+
+    #[test]
+    fn untyped_objects_accept_any_reference_type() {
+        assert!(accepts(
+            "(Ljava/lang/Object;I)V",
+            "(Ljava/lang/ClassLoader;I)V"
+        ));
+        assert!(accepts("(Ljava/lang/Object;)V", "([I)V"));
+        assert!(accepts("()Ljava/lang/Object;", "()[B"));
+
+        assert!(!accepts("(Ljava/lang/Object;)V", "(I)V"));
+        assert!(!accepts("(Ljava/lang/String;)V", "(Ljava/lang/Object;)V"));
+        assert!(!accepts("()I", "()J"));
+        assert!(!accepts("(I)V", "(II)V"));
+    }
+
+    #[test]
+    fn parse_class_file() {
+        let bytes = class_bytes(
+            "com/example/Foo",
+            Some("java/lang/Object"),
+            &[
+                (ACC_NATIVE | ACC_STATIC, "answer", "(I)I"),
+                (0, "plain", "()V"),
+            ],
+        );
+        let class = ClassFile::parse(&bytes).unwrap();
+
+        assert_eq!(class.name, "com/example/Foo");
+        assert_eq!(class.super_class.as_deref(), Some("java/lang/Object"));
+        assert_eq!(
+            class.methods,
+            vec![
+                ClassMethod {
+                    access_flags: ACC_NATIVE | ACC_STATIC,
+                    name: "answer".into(),
+                    descriptor: "(I)I".into(),
+                },
+                ClassMethod {
+                    access_flags: 0,
+                    name: "plain".into(),
+                    descriptor: "()V".into(),
+                },
+            ]
+        );
+        assert!(class.methods[0].is_native() && class.methods[0].is_static());
+        assert!(!class.methods[1].is_native() && !class.methods[1].is_static());
+    }
+
+    #[test]
+    fn malformed_class_file_offset() {
+        let bytes = class_bytes("Foo", None, &[]);
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = 0;
+        assert!(matches!(
+            ClassFile::parse(&bad_magic),
+            Err(ClassFileError::Malformed(None, 0))
+        ));
+
+        /* The header and the constant pool count are read, the first constant isn't */
+        assert!(matches!(
+            ClassFile::parse(&bytes[..10]),
+            Err(ClassFileError::Malformed(None, 10))
+        ));
+    }
+
+    #[test]
+    fn validate_reports_mismatches() {
+        let path =
+            std::env::temp_dir().join(format!("robusta-classfile-{}.class", std::process::id()));
+        let bytes = class_bytes(
+            "com/example/Foo",
+            None,
+            &[
+                (ACC_NATIVE | ACC_STATIC, "answer", "(I)I"),
+                (0, "plain", "()V"),
+                (ACC_NATIVE, "size", "()I"),
+                (
+                    ACC_NATIVE,
+                    "greet",
+                    "(Ljava/lang/String;)Ljava/lang/String;",
+                ),
+            ],
+        );
+        std::fs::write(&path, bytes).unwrap();
+
+        let methods = vec![
+            exported("answer", "(I)I", true),
+            exported("greet", "(Ljava/lang/Object;)Ljava/lang/String;", false),
+            BridgeMethod {
+                class: "com/example/Bar".into(),
+                ..exported("answer", "(I)I", true)
+            },
+            exported("answer", "(J)I", true),
+            exported("plain", "()V", false),
+            exported("size", "()I", true),
+            BridgeMethod {
+                kind: MethodKind::Imported,
+                ..exported("toString", "()Ljava/lang/String;", false)
+            },
+        ];
+        let mismatches = validate(&methods, &[&path]);
+        std::fs::remove_file(&path).unwrap();
+
+        let file = path.display();
+        assert_eq!(
+            mismatches
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "exported static method `com.example.Bar.answer(I)I`: class not found".to_string(),
+                format!("{}: exported static method `com.example.Foo.answer(J)I`: method not found, declared descriptors are (I)I", file),
+                format!("{}: exported method `com.example.Foo.plain()V`: Java method isn't `native`", file),
+                format!("{}: exported static method `com.example.Foo.size()I`: Java method isn't static", file),
+            ]
+        );
+    }
+}
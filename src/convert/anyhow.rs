@@ -0,0 +1,34 @@
+//! Exceptions from `anyhow` errors.
+//!
+//! Exported methods with `call_type(safe)` can return `anyhow::Result<T>` or `Result<T, anyhow::Error>`:
+//! an `Err` is thrown as the exception specified in the `#[call_type(safe)]` attribute, with the whole context chain in its message.
+
+use std::error::Error;
+use std::fmt;
+
+/// An [`anyhow::Error`] returned by an exported method, displayed with its whole context chain.
+///
+/// The generated wrapper of methods returning `anyhow::Result<T>` or `Result<T, anyhow::Error>` (spelled as such) wraps their errors
+/// in an `AnyhowError`, so that the exception message reads `outer context: inner context: root cause`
+/// (the `{:#}` format of `anyhow`) instead of only showing the outermost context.
+/// The causes in [`anyhow::Error::chain`] are the [`source`](Error::source)s of the wrapper.
+#[derive(Debug)]
+pub struct AnyhowError(pub ::anyhow::Error);
+
+impl From<::anyhow::Error> for AnyhowError {
+    fn from(e: ::anyhow::Error) -> Self {
+        AnyhowError(e)
+    }
+}
+
+impl fmt::Display for AnyhowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}", self.0)
+    }
+}
+
+impl Error for AnyhowError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.0.chain().nth(1)
+    }
+}
@@ -0,0 +1,67 @@
+//! Conversions from references to [`std::sync::atomic`] types to Java primitives.
+//!
+//! A `&AtomicI64` is converted to a Java `long` holding its current value, read with [`Ordering::Relaxed`]:
+//! the Java side receives a snapshot, and later updates of the atomic aren't reflected in it.
+//! The other signed atomic integers are converted to the Java primitive of the same width, and `&AtomicBool` to a `boolean`.
+//! Java has no unsigned primitives, so the unsigned atomics have no conversion.
+
+use std::sync::atomic::{AtomicBool, AtomicI16, AtomicI32, AtomicI64, AtomicI8, Ordering};
+
+use jni::errors::Result;
+use jni::sys::{jboolean, jbyte, jint, jlong, jshort};
+use jni::JNIEnv;
+
+use crate::convert::{IntoJavaValue, Signature, TryIntoJavaValue};
+
+macro_rules! atomic_types {
+    ($($atomic:ty: $primitive:ty),+) => {
+        $(
+            impl Signature for &$atomic {
+                const SIG_TYPE: &'static str = <$primitive as Signature>::SIG_TYPE;
+            }
+
+            impl<'env> TryIntoJavaValue<'env> for &$atomic {
+                type Target = $primitive;
+
+                fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+                    Ok(IntoJavaValue::into(self, env))
+                }
+            }
+
+            impl<'env> IntoJavaValue<'env> for &$atomic {
+                type Target = $primitive;
+
+                fn into(self, _env: &JNIEnv<'env>) -> Self::Target {
+                    self.load(Ordering::Relaxed)
+                }
+            }
+        )+
+    };
+}
+
+atomic_types! {
+    AtomicI8: jbyte,
+    AtomicI16: jshort,
+    AtomicI32: jint,
+    AtomicI64: jlong
+}
+
+impl Signature for &AtomicBool {
+    const SIG_TYPE: &'static str = <jboolean as Signature>::SIG_TYPE;
+}
+
+impl<'env> TryIntoJavaValue<'env> for &AtomicBool {
+    type Target = jboolean;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        Ok(IntoJavaValue::into(self, env))
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for &AtomicBool {
+    type Target = jboolean;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        IntoJavaValue::into(self.load(Ordering::Relaxed), env)
+    }
+}
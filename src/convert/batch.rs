@@ -0,0 +1,148 @@
+//! Batch results.
+//!
+//! A [`BatchResult`] collects the outcome of processing several items, keeping both the successes and the errors
+//! instead of stopping at the first failure. It is converted to a Java object holding two `java.util.List`s.
+
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+
+use jni::errors::Result;
+use jni::objects::JValue;
+use jni::sys::jobject;
+use jni::JNIEnv;
+
+use crate::convert::{IntoJavaValue, JavaValue, Signature, TryIntoJavaValue};
+
+/// Java class a [`BatchResult`] is converted to.
+///
+/// The class is given by the [signature](Signature) of the implementing type, and must have a constructor
+/// receiving the list of successes and the list of errors, in this order:
+///
+/// ```ignore
+/// #[derive(Signature)]
+/// #[package(com.example)]
+/// pub struct Batch;
+///
+/// // public Batch(List<T> successes, List<E> errors) { ... }
+/// impl BatchClass for Batch {}
+/// ```
+pub trait BatchClass: Signature {
+    /// Descriptor of the constructor called with the success and error lists.
+    const CONSTRUCTOR_SIG: &'static str = "(Ljava/util/List;Ljava/util/List;)V";
+}
+
+/// `java.util.AbstractMap.SimpleImmutableEntry`, holding the successes as key and the errors as value.
+///
+/// This is the default [`BatchClass`], and can be received in Java as a `Map.Entry<List<T>, List<E>>`.
+pub struct MapEntry;
+
+impl Signature for MapEntry {
+    const SIG_TYPE: &'static str = "Ljava/util/AbstractMap$SimpleImmutableEntry;";
+}
+
+impl BatchClass for MapEntry {
+    const CONSTRUCTOR_SIG: &'static str = "(Ljava/lang/Object;Ljava/lang/Object;)V";
+}
+
+/// Successes and errors of a batch of fallible operations, converted to an instance of the [`BatchClass`] `C`.
+///
+/// Collecting an iterator of `Result<T, E>` into a `BatchResult` keeps the order of the items in both lists:
+///
+/// ```ignore
+/// pub extern "jni" fn parseAll(items: Vec<String>) -> BatchResult<i32, String> {
+///     items.iter().map(|s| s.parse().map_err(|_| s.clone())).collect()
+/// }
+/// ```
+pub struct BatchResult<T, E, C = MapEntry> {
+    pub successes: Vec<T>,
+    pub errors: Vec<E>,
+    class: PhantomData<C>,
+}
+
+impl<T, E, C> BatchResult<T, E, C> {
+    pub fn new() -> Self {
+        BatchResult {
+            successes: Vec::new(),
+            errors: Vec::new(),
+            class: PhantomData,
+        }
+    }
+
+    /// Add the outcome of an item to the batch.
+    pub fn push(&mut self, result: std::result::Result<T, E>) {
+        match result {
+            Ok(v) => self.successes.push(v),
+            Err(e) => self.errors.push(e),
+        }
+    }
+}
+
+impl<T, E, C> Default for BatchResult<T, E, C> {
+    fn default() -> Self {
+        BatchResult::new()
+    }
+}
+
+impl<T, E, C> Extend<std::result::Result<T, E>> for BatchResult<T, E, C> {
+    fn extend<I: IntoIterator<Item = std::result::Result<T, E>>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|r| self.push(r));
+    }
+}
+
+impl<T, E, C> FromIterator<std::result::Result<T, E>> for BatchResult<T, E, C> {
+    fn from_iter<I: IntoIterator<Item = std::result::Result<T, E>>>(iter: I) -> Self {
+        let mut batch = BatchResult::new();
+        batch.extend(iter);
+        batch
+    }
+}
+
+impl<T, E, C: BatchClass> Signature for BatchResult<T, E, C> {
+    const SIG_TYPE: &'static str = <C as Signature>::SIG_TYPE;
+}
+
+impl<'env, T, E, C> TryIntoJavaValue<'env> for BatchResult<T, E, C>
+where
+    T: TryIntoJavaValue<'env>,
+    E: TryIntoJavaValue<'env>,
+    C: BatchClass,
+{
+    type Target = jobject;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let successes = JavaValue::autobox(TryIntoJavaValue::try_into(self.successes, env)?, env);
+        let errors = JavaValue::autobox(TryIntoJavaValue::try_into(self.errors, env)?, env);
+
+        let class_sig = <C as Signature>::SIG_TYPE;
+        let obj = env.new_object(
+            &class_sig[1..class_sig.len() - 1],
+            C::CONSTRUCTOR_SIG,
+            &[JValue::from(successes), JValue::from(errors)],
+        )?;
+
+        Ok(obj.into_raw())
+    }
+}
+
+impl<'env, T, E, C> IntoJavaValue<'env> for BatchResult<T, E, C>
+where
+    T: IntoJavaValue<'env>,
+    E: IntoJavaValue<'env>,
+    C: BatchClass,
+{
+    type Target = jobject;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        let successes = JavaValue::autobox(IntoJavaValue::into(self.successes, env), env);
+        let errors = JavaValue::autobox(IntoJavaValue::into(self.errors, env), env);
+
+        let class_sig = <C as Signature>::SIG_TYPE;
+        env.new_object(
+            &class_sig[1..class_sig.len() - 1],
+            C::CONSTRUCTOR_SIG,
+            &[JValue::from(successes), JValue::from(errors)],
+        )
+        .unwrap()
+        .into_raw()
+    }
+}
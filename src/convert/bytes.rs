@@ -0,0 +1,91 @@
+//! Conversions between `bytes` buffers and Java `byte[]`.
+//!
+//! [`Bytes`] and [`BytesMut`] are always contiguous, so they are copied to Java arrays in a single bulk copy.
+//! Conversions from Java copy the array content once, directly into the buffer.
+
+use ::bytes::{Bytes, BytesMut};
+use jni::errors::Result;
+use jni::sys::{jbyte, jbyteArray};
+use jni::JNIEnv;
+
+use crate::convert::{FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+impl Signature for Bytes {
+    const SIG_TYPE: &'static str = "[B";
+}
+
+impl Signature for BytesMut {
+    const SIG_TYPE: &'static str = "[B";
+}
+
+impl<'env> TryIntoJavaValue<'env> for Bytes {
+    type Target = jbyteArray;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        env.byte_array_from_slice(&self)
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Bytes {
+    type Source = jbyteArray;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        // `Bytes` takes ownership of the `Vec` allocation without copying it.
+        env.convert_byte_array(s).map(Into::into)
+    }
+}
+
+impl<'env> TryIntoJavaValue<'env> for BytesMut {
+    type Target = jbyteArray;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        env.byte_array_from_slice(&self)
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for BytesMut {
+    type Source = jbyteArray;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        let len = env.get_array_length(s)?;
+        let mut buf = BytesMut::zeroed(len as usize);
+
+        let region =
+            unsafe { std::slice::from_raw_parts_mut(buf.as_mut_ptr() as *mut jbyte, buf.len()) };
+        env.get_byte_array_region(s, 0, region)?;
+
+        Ok(buf)
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for Bytes {
+    type Target = jbyteArray;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Bytes {
+    type Source = jbyteArray;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for BytesMut {
+    type Target = jbyteArray;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for BytesMut {
+    type Source = jbyteArray;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
@@ -0,0 +1,72 @@
+//! Conversions with a context.
+//!
+//! Some conversions need state that can't be reached from the [`JNIEnv`] alone, like a registry of live objects.
+//! The traits of this module give such state to the conversions of a method, as described in [`TryFromJavaValueCtx`].
+
+use std::any::Any;
+
+use jni::errors::Result;
+use jni::JNIEnv;
+
+use crate::convert::{JavaValue, TryFromJavaValue, TryIntoJavaValue};
+
+/// Conversion trait from Rust values to Java values receiving a conversion context, analogous to [`TryIntoJavaValue`].
+pub trait TryIntoJavaValueCtx<'env> {
+    /// Conversion target type.
+    type Target: JavaValue<'env>;
+
+    /// Perform the conversion, with the context given in `call_type(safe(with_context = ...))`.
+    fn try_into_ctx(self, env: &JNIEnv<'env>, ctx: &dyn Any) -> Result<Self::Target>;
+}
+
+/// Conversion trait from Java values to Rust values receiving a conversion context, analogous to [`TryFromJavaValue`].
+///
+/// Exported methods marked with `#[call_type(safe(with_context = path::to::CONTEXT))]` convert their parameters and
+/// return value with [`TryFromJavaValueCtx`] and [`TryIntoJavaValueCtx`], receiving a reference to `CONTEXT`
+/// (a `static` or `const` item) alongside the env:
+///
+/// ```ignore
+/// static HANDLES: Handles = Handles::new();
+///
+/// impl<'env: 'borrow, 'borrow> TryFromJavaValueCtx<'env, 'borrow> for Handle {
+///     type Source = jlong;
+///
+///     fn try_from_ctx(s: jlong, _env: &'borrow JNIEnv<'env>, ctx: &dyn Any) -> Result<Self> {
+///         let handles = ctx.downcast_ref::<Handles>().ok_or(Error::WrongJValueType("Handles", "context"))?;
+///         handles.get(s)
+///     }
+/// }
+///
+/// #[call_type(safe(with_context = crate::HANDLES))]
+/// pub extern "jni" fn close(handle: Handle) -> JniResult<()> { ... }
+/// ```
+///
+/// Every [`TryFromJavaValue`] and [`TryIntoJavaValue`] type implements the context traits by ignoring the context,
+/// so methods with a context can still take and return any other convertible type.
+/// The context is only available to the top-level conversions: the contents of a `Vec`, `Option` or `Result` and the
+/// fields of a derived struct are converted with the regular traits.
+pub trait TryFromJavaValueCtx<'env: 'borrow, 'borrow>: Sized {
+    /// Conversion source type.
+    type Source: JavaValue<'env>;
+
+    /// Perform the conversion, with the context given in `call_type(safe(with_context = ...))`.
+    fn try_from_ctx(s: Self::Source, env: &'borrow JNIEnv<'env>, ctx: &dyn Any) -> Result<Self>;
+}
+
+impl<'env, T: TryIntoJavaValue<'env>> TryIntoJavaValueCtx<'env> for T {
+    type Target = <T as TryIntoJavaValue<'env>>::Target;
+
+    fn try_into_ctx(self, env: &JNIEnv<'env>, _ctx: &dyn Any) -> Result<Self::Target> {
+        TryIntoJavaValue::try_into(self, env)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T: TryFromJavaValue<'env, 'borrow>> TryFromJavaValueCtx<'env, 'borrow>
+    for T
+{
+    type Source = <T as TryFromJavaValue<'env, 'borrow>>::Source;
+
+    fn try_from_ctx(s: Self::Source, env: &'borrow JNIEnv<'env>, _ctx: &dyn Any) -> Result<Self> {
+        TryFromJavaValue::try_from(s, env)
+    }
+}
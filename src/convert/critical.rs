@@ -0,0 +1,111 @@
+//! Critical access to Java byte arrays.
+//!
+//! A [`CriticalByteArray`] reads the contents of a `byte[]` in place, through `GetPrimitiveArrayCritical`, instead of
+//! copying them to a Rust buffer as `Box<[u8]>` and `Vec<u8>` do with `convert_byte_array`.
+
+use std::convert::TryInto;
+use std::ops::Deref;
+use std::slice;
+
+use jni::errors::Result;
+use jni::objects::{AutoPrimitiveArray, ReleaseMode};
+use jni::sys::jbyteArray;
+use jni::JNIEnv;
+
+use crate::convert::{FromJavaValue, Signature, TryFromJavaValue};
+
+/// Read-only view of a Java `byte[]`, pinned with `GetPrimitiveArrayCritical`.
+///
+/// The bytes are accessed through [`Deref<Target = [u8]>`](Deref), and the array is released with
+/// `ReleasePrimitiveArrayCritical` (without copying anything back) when the wrapper is dropped.
+///
+/// # Restrictions while pinned
+/// While a `CriticalByteArray` is alive the JVM is in a *critical region*: the garbage collector may be blocked and
+/// the Java array is pinned. Until the wrapper is dropped, native code **must not**:
+///
+/// * call any JNI function, including conversions of other parameters or return values,
+/// * call back into Java, or block waiting on another Java thread.
+///
+/// Breaking these rules may deadlock or crash the JVM. Keep the wrapper's scope short, and [`drop`] it before
+/// doing anything else with the `JNIEnv`.
+///
+/// ```ignore
+/// pub extern "jni" fn checksum(data: CriticalByteArray<'borrow>, seed: i64) -> i64 {
+///     data.iter().fold(seed, |sum, &b| sum + b as i64)
+/// }
+/// ```
+///
+/// The `CriticalByteArray` parameters of exported methods are pinned together with [`pin_all`](CriticalByteArray::pin_all)
+/// once the other parameters have been converted, wherever they appear in the signature, and released when the method returns.
+pub struct CriticalByteArray<'a> {
+    array: AutoPrimitiveArray<'a, 'a>,
+    len: usize,
+}
+
+impl<'a> CriticalByteArray<'a> {
+    /// Whether the JVM handed out a copy of the array instead of pinning it.
+    pub fn is_copy(&self) -> bool {
+        self.array.is_copy()
+    }
+
+    /// Pin several `byte[]`s in a single critical region.
+    ///
+    /// Converting the arrays one at a time would read the length of an array while the previous ones are already pinned,
+    /// which is forbidden: here all the lengths are read first, then the arrays are acquired one after the other.
+    /// If acquiring an array fails the ones already pinned are released.
+    pub fn pin_all<'env: 'a, const N: usize>(
+        env: &'a JNIEnv<'env>,
+        arrays: [jbyteArray; N],
+    ) -> Result<[Self; N]> {
+        let mut lengths = [0; N];
+        for (len, &array) in lengths.iter_mut().zip(arrays.iter()) {
+            *len = env.get_array_length(array)? as usize;
+        }
+
+        let mut pinned = Vec::with_capacity(N);
+        for (&array, &len) in arrays.iter().zip(lengths.iter()) {
+            let array = env.get_primitive_array_critical(array, ReleaseMode::NoCopyBack)?;
+            pinned.push(CriticalByteArray { array, len });
+        }
+
+        Ok(pinned
+            .try_into()
+            .unwrap_or_else(|_: Vec<_>| unreachable!("one array is pinned for each input")))
+    }
+}
+
+impl<'a> Deref for CriticalByteArray<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        if self.len == 0 {
+            return &[];
+        }
+
+        unsafe { slice::from_raw_parts(self.array.as_ptr() as *const u8, self.len) }
+    }
+}
+
+impl<'a> Signature for CriticalByteArray<'a> {
+    const SIG_TYPE: &'static str = "[B";
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for CriticalByteArray<'borrow> {
+    type Source = jbyteArray;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        // The length must be read before entering the critical region.
+        let len = env.get_array_length(s)? as usize;
+        let array = env.get_primitive_array_critical(s, ReleaseMode::NoCopyBack)?;
+
+        Ok(CriticalByteArray { array, len })
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for CriticalByteArray<'borrow> {
+    type Source = jbyteArray;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
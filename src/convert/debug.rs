@@ -0,0 +1,56 @@
+//! `Debug` formatting of Java-backed structs through their Java `toString()`.
+//!
+//! The [`JavaDebug`] derive macro implements [`Debug`](std::fmt::Debug) by calling `toString()` on the `#[instance]` object.
+//! As `Debug` has no access to a [`JNIEnv`](jni::JNIEnv), the JVM must be registered beforehand with [`register_java_vm`]
+//! (e.g. in an initialization native method), and the current thread must already be attached to it.
+//!
+//! If no JVM is registered, the current thread is not attached, a Java exception is pending or `toString()` throws,
+//! the struct name is printed instead.
+
+use std::fmt;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+use jni::objects::{JObject, JString};
+use jni::{sys, JavaVM};
+
+pub use robusta_codegen::JavaDebug;
+
+static JAVA_VM: AtomicPtr<sys::JavaVM> = AtomicPtr::new(ptr::null_mut());
+
+/// Register the JVM used by [`JavaDebug`] implementations to call `toString()`.
+pub fn register_java_vm(vm: &JavaVM) {
+    JAVA_VM.store(vm.get_java_vm_pointer(), Ordering::Release);
+}
+
+#[doc(hidden)]
+pub fn java_debug(obj: JObject, name: &str, f: &mut fmt::Formatter) -> fmt::Result {
+    match java_to_string(obj) {
+        Some(s) => f.write_str(&s),
+        None => f.debug_struct(name).finish_non_exhaustive(),
+    }
+}
+
+fn java_to_string(obj: JObject) -> Option<String> {
+    let raw = JAVA_VM.load(Ordering::Acquire);
+    if raw.is_null() {
+        return None;
+    }
+
+    let vm = unsafe { JavaVM::from_raw(raw) }.ok()?;
+    let env = vm.get_env().ok()?;
+    if env.exception_check().ok()? {
+        return None;
+    }
+
+    let res = env
+        .call_method(obj, "toString", "()Ljava/lang/String;", &[])
+        .and_then(|v| v.l())
+        .and_then(|s| env.get_string(JString::from(s)).map(Into::into));
+
+    if res.is_err() && env.exception_check().unwrap_or(false) {
+        let _ = env.exception_clear();
+    }
+
+    res.ok()
+}
@@ -0,0 +1,134 @@
+//! Conversions between [`VecDeque`]s and `java.util.Deque`s.
+//!
+//! A `VecDeque<T>` is converted to a `java.util.ArrayDeque` holding its elements from front to back, and any
+//! `java.util.Deque` is converted to a `VecDeque<T>` in iteration order (from first to last).
+//! Elements are converted with the conversions of `T`, boxing primitives.
+
+use std::collections::VecDeque;
+
+use jni::errors::Result;
+use jni::objects::{JObject, JValue};
+use jni::sys::jobject;
+use jni::JNIEnv;
+
+use crate::convert::{
+    FromJavaValue, IntoJavaValue, JavaValue, Signature, TryFromJavaValue, TryIntoJavaValue,
+};
+
+impl<T> Signature for VecDeque<T> {
+    const SIG_TYPE: &'static str = "Ljava/util/Deque;";
+}
+
+/// Iterate over the elements of a `java.util.Collection`, calling `f` on each one.
+fn for_each_element<'env, F>(env: &JNIEnv<'env>, collection: JObject<'env>, mut f: F) -> Result<()>
+where
+    F: FnMut(JObject<'env>) -> Result<()>,
+{
+    let iterator = env
+        .call_method(collection, "iterator", "()Ljava/util/Iterator;", &[])?
+        .l()?;
+
+    while env.call_method(iterator, "hasNext", "()Z", &[])?.z()? {
+        let el = env
+            .call_method(iterator, "next", "()Ljava/lang/Object;", &[])?
+            .l()?;
+        f(el)?;
+    }
+
+    Ok(())
+}
+
+impl<'env, T> TryIntoJavaValue<'env> for VecDeque<T>
+where
+    T: TryIntoJavaValue<'env>,
+{
+    type Target = jobject;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let deque = env.new_object(
+            "java/util/ArrayDeque",
+            "(I)V",
+            &[JValue::Int(self.len() as i32)],
+        )?;
+
+        for el in self {
+            let el = JavaValue::autobox(TryIntoJavaValue::try_into(el, env)?, env);
+            env.call_method(
+                deque,
+                "addLast",
+                "(Ljava/lang/Object;)V",
+                &[JValue::from(el)],
+            )?;
+        }
+
+        Ok(deque.into_raw())
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U> TryFromJavaValue<'env, 'borrow> for VecDeque<T>
+where
+    T: TryFromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = JObject<'env>;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        let mut deque = VecDeque::new();
+        for_each_element(env, s, |el| {
+            deque.push_back(T::try_from(U::unbox(el, env), env)?);
+            Ok(())
+        })?;
+
+        Ok(deque)
+    }
+}
+
+impl<'env, T> IntoJavaValue<'env> for VecDeque<T>
+where
+    T: IntoJavaValue<'env>,
+{
+    type Target = jobject;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        let deque = env
+            .new_object(
+                "java/util/ArrayDeque",
+                "(I)V",
+                &[JValue::Int(self.len() as i32)],
+            )
+            .unwrap();
+
+        self.into_iter()
+            .map(|el| JavaValue::autobox(IntoJavaValue::into(el, env), env))
+            .for_each(|el| {
+                env.call_method(
+                    deque,
+                    "addLast",
+                    "(Ljava/lang/Object;)V",
+                    &[JValue::from(el)],
+                )
+                .unwrap();
+            });
+
+        deque.into_raw()
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U> FromJavaValue<'env, 'borrow> for VecDeque<T>
+where
+    T: FromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = JObject<'env>;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        let mut deque = VecDeque::new();
+        for_each_element(env, s, |el| {
+            deque.push_back(T::from(U::unbox(el, env), env));
+            Ok(())
+        })
+        .unwrap();
+
+        deque
+    }
+}
@@ -0,0 +1,144 @@
+//! Invocation of exported methods by name.
+//!
+//! Frameworks dispatching calls dynamically (e.g. through reflection or method handles) can't easily bind one Java declaration
+//! to each exported method. The `#[dispatcher]` struct attribute additionally exports, for the struct's class, a
+//!
+//! ```java
+//! public static native Object invoke(String method, Object[] args);
+//! ```
+//!
+//! method, which calls the exported method of the struct named `method` and taking as many parameters as there are `args`.
+//! As with method handles, the receiver of `self` methods is the first element of `args`.
+//!
+//! Every element of `args` must be an instance of the Java type of the matching parameter, with primitives boxed (e.g. an `Integer`
+//! for an `i32`). It is then converted by the same JNI function as a direct call of the native method would be, so the call type,
+//! the `#[receiver]` strategy and the exceptions thrown are those of the exported method itself.
+//! The returned value is boxed in the same way, and `void` methods return `null`.
+//!
+//! An unknown method, a wrong number of arguments or an argument of the wrong type throws a [`DISPATCH_EXCEPTION_CLASS`].
+//! Methods with a conversion context (`with_context`) can't be dispatched, as their types don't have to implement [`Signature`](crate::convert::Signature).
+
+use std::ptr;
+
+use jni::errors::{Error, Result};
+use jni::objects::{JObject, JString};
+use jni::sys::{jobject, jobjectArray};
+use jni::JNIEnv;
+
+use crate::convert::{boxed_signature, JavaValue, TryFromJavaValue};
+
+/// Class of the exception thrown by `invoke` when no exported method matches the call.
+pub const DISPATCH_EXCEPTION_CLASS: &str = "java/lang/IllegalArgumentException";
+
+/// A call of the `invoke` method generated by `#[dispatcher]`.
+#[doc(hidden)]
+pub struct DispatchCall<'a, 'env> {
+    env: &'a JNIEnv<'env>,
+    method: String,
+    args: jobjectArray,
+    arity: i32,
+}
+
+impl<'a, 'env> DispatchCall<'a, 'env> {
+    pub fn new(env: &'a JNIEnv<'env>, method: JString<'env>, args: jobjectArray) -> Result<Self> {
+        let method = TryFromJavaValue::try_from(method, env)?;
+        let arity = if args.is_null() {
+            0
+        } else {
+            env.get_array_length(args)?
+        };
+
+        Ok(DispatchCall {
+            env,
+            method,
+            args,
+            arity,
+        })
+    }
+
+    /// Name of the called method.
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// Number of arguments of the call.
+    pub fn arity(&self) -> i32 {
+        self.arity
+    }
+
+    /// `JNIEnv` passed to the JNI function of the called method.
+    pub fn env(&self) -> Result<JNIEnv<'env>> {
+        unsafe { JNIEnv::from_raw(self.env.get_native_interface()) }
+    }
+
+    /// Unbox the argument at `index`, after checking that it is an instance of the Java type of signature `sig`.
+    pub fn arg<T: JavaValue<'env>>(&self, index: i32, sig: &'static str) -> Result<T> {
+        let boxed_sig = boxed_signature(sig);
+        let class = boxed_sig
+            .strip_prefix('L')
+            .and_then(|s| s.strip_suffix(';'))
+            .unwrap_or(boxed_sig);
+
+        let arg = self.env.get_object_array_element(self.args, index)?;
+        let matches = if arg.is_null() {
+            /* Primitives can't be `null`, while a `null` object is left to the conversion of the parameter */
+            boxed_sig == sig
+        } else {
+            self.env.is_instance_of(arg, class)?
+        };
+
+        if !matches {
+            return Err(self.throw(format!(
+                "`args[{}]` of `{}` is not a `{}`",
+                index,
+                self.method,
+                class.replace('/', ".")
+            )));
+        }
+
+        Ok(T::unbox(arg, self.env))
+    }
+
+    /// Box the value returned by the JNI function of the called method, unless it has thrown an exception.
+    pub fn returned<T: JavaValue<'env>>(&self, value: T) -> Result<JObject<'env>> {
+        if self.env.exception_check()? {
+            return Err(Error::JavaException);
+        }
+
+        Ok(value.autobox(self.env))
+    }
+
+    /// Report that no exported method matches the call.
+    pub fn no_such_method(&self) -> Error {
+        self.throw(format!(
+            "no exported method `{}` taking {} arguments",
+            self.method, self.arity
+        ))
+    }
+
+    fn throw(&self, message: String) -> Error {
+        match self.env.throw_new(DISPATCH_EXCEPTION_CLASS, message) {
+            Ok(()) => Error::JavaException,
+            Err(e) => e,
+        }
+    }
+}
+
+/// Return the result of a call of `invoke` to the JVM, throwing a `java.lang.RuntimeException` for errors not thrown yet.
+#[doc(hidden)]
+pub fn finish_dispatch(env: &JNIEnv, result: Result<JObject>) -> jobject {
+    match result {
+        Ok(value) => value.into_raw(),
+        Err(e) => {
+            if !matches!(e, Error::JavaException) && !env.exception_check().unwrap_or(true) {
+                // Nothing more can be reported to the JVM if throwing fails
+                let _ = env.throw_new(
+                    "java/lang/RuntimeException",
+                    format!("JNI call error!. Cause: {}", e),
+                );
+            }
+
+            ptr::null_mut()
+        }
+    }
+}
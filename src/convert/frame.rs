@@ -0,0 +1,92 @@
+//! Local reference frames of exported methods marked with `#[local_frame(capacity = N)]`.
+//!
+//! Local references created by a native method are only deleted when it returns, and the JVM only guarantees room for 16 of them.
+//! A `#[local_frame]` method runs in a frame pushed with `PushLocalFrame`, which reserves room for `N` local references
+//! and deletes all of them with `PopLocalFrame` when the method returns. An object returned to Java is moved to the calling frame
+//! through the result parameter of `PopLocalFrame`, so it outlives the popped frame.
+
+use jni::errors::{Error, Result};
+use jni::objects::{JObject, JString};
+use jni::sys::{jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jobject, jshort};
+use jni::JNIEnv;
+
+use crate::convert::JavaValue;
+
+/// A [`JavaValue`] that can be returned from a local reference frame.
+///
+/// Primitives are returned as they are, while objects get a new local reference in the calling frame.
+pub trait LocalFrameValue<'env>: JavaValue<'env> {
+    /// Pop the current local reference frame, returning the value to the calling frame.
+    fn pop_local_frame(self, env: &JNIEnv<'env>) -> Result<Self>
+    where
+        Self: Sized;
+}
+
+macro_rules! primitive_frame_values {
+    ($($type:ty),+) => {
+        $(
+            impl<'env> LocalFrameValue<'env> for $type {
+                fn pop_local_frame(self, env: &JNIEnv<'env>) -> Result<Self> {
+                    env.pop_local_frame(JObject::null())?;
+                    Ok(self)
+                }
+            }
+        )+
+    };
+}
+
+primitive_frame_values!(
+    jboolean,
+    jbyte,
+    jchar,
+    jdouble,
+    jfloat,
+    jint,
+    jlong,
+    jshort,
+    ()
+);
+
+impl<'env> LocalFrameValue<'env> for JObject<'env> {
+    fn pop_local_frame(self, env: &JNIEnv<'env>) -> Result<Self> {
+        env.pop_local_frame(self)
+    }
+}
+
+impl<'env> LocalFrameValue<'env> for jobject {
+    fn pop_local_frame(self, env: &JNIEnv<'env>) -> Result<Self> {
+        env.pop_local_frame(JavaValue::autobox(self, env))
+            .map(JObject::into_raw)
+    }
+}
+
+impl<'env> LocalFrameValue<'env> for JString<'env> {
+    fn pop_local_frame(self, env: &JNIEnv<'env>) -> Result<Self> {
+        env.pop_local_frame(self.into()).map(From::from)
+    }
+}
+
+/// Call `f` in a new local reference frame with room for `capacity` local references.
+///
+/// The frame is popped whether `f` succeeds or not, and only a successful result is kept.
+#[doc(hidden)]
+pub fn with_local_frame<'env, T, E, F>(
+    env: &JNIEnv<'env>,
+    capacity: i32,
+    f: F,
+) -> std::result::Result<T, E>
+where
+    T: LocalFrameValue<'env>,
+    E: From<Error>,
+    F: FnOnce() -> std::result::Result<T, E>,
+{
+    env.push_local_frame(capacity)?;
+
+    match f() {
+        Ok(v) => Ok(v.pop_local_frame(env)?),
+        Err(e) => {
+            env.pop_local_frame(JObject::null())?;
+            Err(e)
+        }
+    }
+}
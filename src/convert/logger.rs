@@ -0,0 +1,88 @@
+//! Logging of the exceptions thrown by `#[call_type(safe(log_to = "..."))]` exported methods.
+//!
+//! The logger is obtained from the static `getLogger(String)` method of the given factory class (e.g. `org.slf4j.LoggerFactory`),
+//! with the name of the bridged Java class, and the exception is passed to its `error(String, Throwable)` method before being thrown.
+//! The factory method is looked up with reflection, so that its return type doesn't need to be known.
+//!
+//! Failing to log doesn't prevent the exception from being thrown.
+
+use jni::errors::Result;
+use jni::objects::{JObject, JThrowable, JValue};
+use jni::JNIEnv;
+
+use crate::convert::JavaValue;
+
+/// Throw a new exception of class `class` with the given message, after logging it with the logger named `logger_name`
+/// from `logger_factory`.
+#[doc(hidden)]
+pub fn throw_new_logged(
+    env: &JNIEnv,
+    class: &str,
+    message: &str,
+    logger_factory: &str,
+    logger_name: &str,
+) -> Result<()> {
+    let throwable = env.new_object(
+        class,
+        "(Ljava/lang/String;)V",
+        &[JValue::from(env.new_string(message)?)],
+    )?;
+
+    if log_error(env, logger_factory, logger_name, message, throwable).is_err() {
+        // The exception thrown while logging, if any, is replaced with the one being thrown
+        if env.exception_check()? {
+            env.exception_clear()?;
+        }
+    }
+
+    env.throw(JThrowable::from(throwable))
+}
+
+fn log_error<'env>(
+    env: &JNIEnv<'env>,
+    logger_factory: &str,
+    logger_name: &str,
+    message: &str,
+    throwable: JObject<'env>,
+) -> Result<()> {
+    let factory_class = env.find_class(logger_factory)?;
+
+    let parameter_types =
+        env.new_object_array(1, "java/lang/Class", env.find_class("java/lang/String")?)?;
+    let get_logger = env
+        .call_method(
+            factory_class,
+            "getMethod",
+            "(Ljava/lang/String;[Ljava/lang/Class;)Ljava/lang/reflect/Method;",
+            &[
+                JValue::from(env.new_string("getLogger")?),
+                JValue::from(JavaValue::autobox(parameter_types, env)),
+            ],
+        )?
+        .l()?;
+
+    let arguments = env.new_object_array(1, "java/lang/Object", env.new_string(logger_name)?)?;
+    let logger = env
+        .call_method(
+            get_logger,
+            "invoke",
+            "(Ljava/lang/Object;[Ljava/lang/Object;)Ljava/lang/Object;",
+            &[
+                JValue::from(JObject::null()),
+                JValue::from(JavaValue::autobox(arguments, env)),
+            ],
+        )?
+        .l()?;
+
+    env.call_method(
+        logger,
+        "error",
+        "(Ljava/lang/String;Ljava/lang/Throwable;)V",
+        &[
+            JValue::from(env.new_string(message)?),
+            JValue::from(throwable),
+        ],
+    )?;
+
+    Ok(())
+}
@@ -8,6 +8,14 @@
 //!
 //! **If the `call_type` attribute is omitted, the fallible conversion trait family is chosen.**
 //!
+//! Parameters of exported methods can be marked with `#[unchecked]` to be converted with the infallible family even when
+//! the rest of the method uses fallible conversions.
+//! Parameters marked with `#[default_on_error]` take their `Default::default()` value when their fallible conversion fails,
+//! instead of making the method throw (this has no effect on infallible conversions, which panic).
+//!
+//! `#[call_type(both(safe_suffix = "Checked", unchecked_suffix = "Fast"))]` exports both a fallible and an infallible variant of the
+//! same method, bound to Java native methods named after it with the given suffixes (`scaleChecked` and `scaleFast` in the example below).
+//!
 //! Example usage:
 //! ```
 //! use robusta_jni::bridge;
@@ -26,6 +34,13 @@
 //!
 //!         #[call_type(safe(exception_class = "java.lang.IllegalArgumentException", message = "invalid value"))]
 //!         pub extern "jni" fn bar(foo: i32) -> ::robusta_jni::jni::errors::Result<i32> { Ok(foo) }
+//!
+//!         pub extern "jni" fn baz(foo: String, #[unchecked] count: i32) -> String { foo.repeat(count as usize) }
+//!
+//!         pub extern "jni" fn qux(foo: String, #[default_on_error] suffix: String) -> String { foo + &suffix }
+//!
+//!         #[call_type(both(safe_suffix = "Checked", unchecked_suffix = "Fast"))]
+//!         pub extern "jni" fn scale(v: i32, factor: i32) -> i32 { v * factor }
 //!     }
 //! }
 //! ```
@@ -37,8 +52,18 @@
 //! When used with `#[call_type(safe)]`, if an `Err` is returned a Java exception is thrown (the one specified in the `call_type` attribute,
 //! or `java.lang.RuntimeException` if omitted).
 //!
+//! To rethrow a Java exception caught in native code, return a `Result<T, JThrowable>` instead: the original throwable is thrown again.
+//! Returning a `Result<T, E>` where `E` converts to a Java `Throwable` (e.g. a bridged exception class) throws the converted error,
+//! which lets validation methods returning `Result<(), E>` hand structured error data to Java.
+//! For full control over the thrown object, `E` can implement [`ToThrowable`] to build the throwable itself.
+//! A `Result<T, Infallible>`, which can never be `Err`, is converted exactly as a plain `T`, with both call types.
+//! To report the failures of a batch of items without throwing, collect the per-item results in a [`BatchResult`] instead.
+//!
 
-use std::convert::TryFrom;
+use std::borrow::Cow;
+use std::convert::{Infallible, TryFrom};
+use std::marker::PhantomData;
+use std::num::Wrapping;
 use std::str::FromStr;
 
 use jni::errors::Error;
@@ -48,14 +73,48 @@ use jni::sys::{jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jobject, js
 use jni::JNIEnv;
 use paste::paste;
 
+#[cfg(feature = "anyhow")]
+pub use self::anyhow::*;
+pub use batch::*;
+pub use context::*;
+pub use critical::*;
+pub use debug::*;
+pub use dispatch::*;
 pub use field::*;
-pub use robusta_codegen::Signature;
+pub use frame::*;
+pub use logger::*;
+pub use mutable::*;
+pub use pointer::*;
+pub use robusta_codegen::{JavaRecord, Signature};
 pub use safe::*;
 pub use unchecked::*;
-
+pub use weak::*;
+
+#[cfg(feature = "anyhow")]
+mod anyhow;
+mod atomic;
+mod batch;
+#[cfg(feature = "bytes")]
+mod bytes;
+mod context;
+mod critical;
+mod debug;
+mod dispatch;
+mod deque;
 pub mod field;
+mod frame;
+mod logger;
+mod mutable;
+mod pointer;
+mod properties;
 pub mod safe;
+#[cfg(feature = "smallvec")]
+mod smallvec;
+mod std_time;
+#[cfg(feature = "chrono")]
+mod time;
 pub mod unchecked;
+mod weak;
 
 /// A trait for types that are ffi-safe to use with JNI. It is implemented for primitives, [JObject](jni::objects::JObject) and [jobject](jni::sys::jobject).
 /// Users that want automatic conversion should instead implement [FromJavaValue], [IntoJavaValue] and/or [TryFromJavaValue], [TryIntoJavaValue]
@@ -67,6 +126,14 @@ pub trait JavaValue<'env> {
     fn unbox(s: JObject<'env>, env: &JNIEnv<'env>) -> Self;
 }
 
+/// Default value of a [`JavaValue`]: zero for primitives and `null` for objects.
+///
+/// This is the value returned to the JVM by `#[call_type(safe)]` exported methods after throwing an exception.
+pub trait DefaultJavaValue<'env>: JavaValue<'env> {
+    /// Get the default value.
+    fn default_java_value() -> Self;
+}
+
 /// This trait provides [type signatures](https://docs.oracle.com/en/java/javase/15/docs/specs/jni/types.html#type-signatures) for types.
 /// It is necessary to support conversions to/from Java types.
 ///
@@ -79,6 +146,15 @@ pub trait Signature {
     const SIG_TYPE: &'static str;
 }
 
+/// This trait provides the [type signature](https://docs.oracle.com/en/java/javase/15/docs/specs/jni/types.html#type-signatures) of a Java array
+/// of the implementing type, so that `Box<[T]>` can be converted to and from `T[]`.
+///
+/// It is implemented by the [`Signature`] derive macro.
+pub trait ArraySignature: Signature {
+    /// Java type signature of an array of the implementing type.
+    const ARRAY_SIG_TYPE: &'static str;
+}
+
 macro_rules! jvalue_types {
     ($type:ty: $boxed:ident ($sig:ident) [$unbox_method:ident]) => {
         impl Signature for $type {
@@ -99,6 +175,12 @@ macro_rules! jvalue_types {
                     .unwrap()))
             }
         }
+
+        impl<'env> DefaultJavaValue<'env> for $type {
+            fn default_java_value() -> Self {
+                Default::default()
+            }
+        }
     };
 
     ($type:ty: $boxed:ident ($sig:ident) [$unbox_method:ident], $($rest:ty: $rest_boxed:ident ($rest_sig:ident) [$unbox_method_rest:ident]),+) => {
@@ -119,6 +201,21 @@ jvalue_types! {
     jshort: Short (S) [shortValue]
 }
 
+/// Signature of the wrapper class that [`JavaValue::autobox`] boxes primitives of signature `sig` to, or `sig` itself for other types.
+pub(crate) const fn boxed_signature(sig: &'static str) -> &'static str {
+    match sig.as_bytes() {
+        b"Z" => "Ljava/lang/Boolean;",
+        b"B" => "Ljava/lang/Byte;",
+        b"C" => "Ljava/lang/Character;",
+        b"D" => "Ljava/lang/Double;",
+        b"F" => "Ljava/lang/Float;",
+        b"I" => "Ljava/lang/Integer;",
+        b"J" => "Ljava/lang/Long;",
+        b"S" => "Ljava/lang/Short;",
+        _ => sig,
+    }
+}
+
 impl Signature for () {
     const SIG_TYPE: &'static str = "V";
 }
@@ -131,6 +228,10 @@ impl<'env> JavaValue<'env> for () {
     fn unbox(_s: JObject<'env>, _env: &JNIEnv<'env>) -> Self {}
 }
 
+impl<'env> DefaultJavaValue<'env> for () {
+    fn default_java_value() -> Self {}
+}
+
 impl<'env> Signature for JObject<'env> {
     const SIG_TYPE: &'static str = "Ljava/lang/Object;";
 }
@@ -145,6 +246,12 @@ impl<'env> JavaValue<'env> for JObject<'env> {
     }
 }
 
+impl<'env> DefaultJavaValue<'env> for JObject<'env> {
+    fn default_java_value() -> Self {
+        JObject::null()
+    }
+}
+
 impl<'env> JavaValue<'env> for jobject {
     fn autobox(self, _env: &JNIEnv<'env>) -> JObject<'env> {
         unsafe { JObject::from_raw(self) }
@@ -155,6 +262,12 @@ impl<'env> JavaValue<'env> for jobject {
     }
 }
 
+impl<'env> DefaultJavaValue<'env> for jobject {
+    fn default_java_value() -> Self {
+        std::ptr::null_mut()
+    }
+}
+
 impl<'env> Signature for JString<'env> {
     const SIG_TYPE: &'static str = "Ljava/lang/String;";
 }
@@ -169,10 +282,283 @@ impl<'env> JavaValue<'env> for JString<'env> {
     }
 }
 
+impl<'env> DefaultJavaValue<'env> for JString<'env> {
+    fn default_java_value() -> Self {
+        From::from(JObject::null())
+    }
+}
+
 impl<T: Signature> Signature for jni::errors::Result<T> {
     const SIG_TYPE: &'static str = <T as Signature>::SIG_TYPE;
 }
 
+impl<'env, T: Signature, E: ToThrowable<'env>> Signature for std::result::Result<T, E> {
+    const SIG_TYPE: &'static str = <T as Signature>::SIG_TYPE;
+}
+
+impl<T: Signature> Signature for std::result::Result<T, Infallible> {
+    const SIG_TYPE: &'static str = <T as Signature>::SIG_TYPE;
+}
+
+/// Primitives are boxed to their wrapper class, so that `Option<i32>` is an `Integer`.
+impl<T: Signature> Signature for Option<T> {
+    const SIG_TYPE: &'static str = boxed_signature(<T as Signature>::SIG_TYPE);
+}
+
+/// Arrays of `Option<T>` are arrays of `T` whose elements may be `null`.
+impl<T: ArraySignature> ArraySignature for Option<T> {
+    const ARRAY_SIG_TYPE: &'static str = <T as ArraySignature>::ARRAY_SIG_TYPE;
+}
+
+impl<T: Signature> Signature for Wrapping<T> {
+    const SIG_TYPE: &'static str = <T as Signature>::SIG_TYPE;
+}
+
+impl<B> Signature for Cow<'_, B>
+where
+    B: ToOwned + ?Sized,
+    <B as ToOwned>::Owned: Signature,
+{
+    const SIG_TYPE: &'static str = <<B as ToOwned>::Owned as Signature>::SIG_TYPE;
+}
+
+/// An explicit Java `null`, typed with the [signature](Signature) of `T`.
+///
+/// Useful to pass `null` to an object parameter of an imported method:
+///
+/// ```ignore
+/// pub extern "java" fn setCallback(&self, env: &JNIEnv, callback: JNull<Callback>) -> JniResult<()> {}
+///
+/// a.setCallback(&env, JNull::new())?;
+/// ```
+///
+/// `T` should be a type mapping to a Java object, as primitive types can't be `null`.
+pub struct JNull<T>(PhantomData<T>);
+
+impl<T> JNull<T> {
+    pub fn new() -> Self {
+        JNull(PhantomData)
+    }
+}
+
+impl<T> Default for JNull<T> {
+    fn default() -> Self {
+        JNull::new()
+    }
+}
+
+impl<T: Signature> Signature for JNull<T> {
+    const SIG_TYPE: &'static str = <T as Signature>::SIG_TYPE;
+}
+
+impl<'env, T: Signature> IntoJavaValue<'env> for JNull<T> {
+    type Target = JObject<'env>;
+
+    fn into(self, _env: &JNIEnv<'env>) -> Self::Target {
+        JObject::null()
+    }
+}
+
+impl<'env, T: Signature> TryIntoJavaValue<'env> for JNull<T> {
+    type Target = JObject<'env>;
+
+    fn try_into(self, _env: &JNIEnv<'env>) -> jni::errors::Result<Self::Target> {
+        Ok(JObject::null())
+    }
+}
+
+/// A pair of `i32`s packed in a single Java `long`, to avoid allocating an object on hot paths.
+///
+/// The first element is stored in the high 32 bits and the second one in the low 32 bits, so that on the Java side
+/// they can be read back with `(int) (v >> 32)` and `(int) v`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Packed<T>(pub T);
+
+impl Signature for Packed<(i32, i32)> {
+    const SIG_TYPE: &'static str = "J";
+}
+
+impl<'env> IntoJavaValue<'env> for Packed<(i32, i32)> {
+    type Target = jlong;
+
+    fn into(self, _env: &JNIEnv<'env>) -> Self::Target {
+        let (high, low) = self.0;
+        (((high as u32 as u64) << 32) | (low as u32 as u64)) as jlong
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Packed<(i32, i32)> {
+    type Source = jlong;
+
+    fn from(s: Self::Source, _env: &'borrow JNIEnv<'env>) -> Self {
+        Packed(((s >> 32) as i32, s as i32))
+    }
+}
+
+impl<'env> TryIntoJavaValue<'env> for Packed<(i32, i32)> {
+    type Target = jlong;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> jni::errors::Result<Self::Target> {
+        Ok(IntoJavaValue::into(self, env))
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Packed<(i32, i32)> {
+    type Source = jlong;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> jni::errors::Result<Self> {
+        Ok(FromJavaValue::from(s, env))
+    }
+}
+
+/// A [`Vec`] converted to and from a `java.util.List`.
+///
+/// `Vec<T>` has the signature of `java.util.ArrayList`, which doesn't match Java methods declared with a `List` parameter
+/// or return type. `JavaList<T>` is converted in the same way (to an `ArrayList`, from any `List`), with the signature of `List`:
+///
+/// ```ignore
+/// pub extern "java" fn addAll(&self, env: &JNIEnv, items: JavaList<String>) -> JniResult<bool> {}
+///
+/// pub extern "jni" fn names(self) -> JavaList<String> {
+///     JavaList(vec!["a".to_string(), "b".to_string()])
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JavaList<T>(pub Vec<T>);
+
+impl<T> From<Vec<T>> for JavaList<T> {
+    fn from(v: Vec<T>) -> Self {
+        JavaList(v)
+    }
+}
+
+impl<T> Signature for JavaList<T> {
+    const SIG_TYPE: &'static str = "Ljava/util/List;";
+}
+
+impl<'env, T> IntoJavaValue<'env> for JavaList<T>
+where
+    T: IntoJavaValue<'env>,
+{
+    type Target = jobject;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        IntoJavaValue::into(self.0, env)
+    }
+}
+
+impl<'env, T> TryIntoJavaValue<'env> for JavaList<T>
+where
+    T: TryIntoJavaValue<'env>,
+{
+    type Target = jobject;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> jni::errors::Result<Self::Target> {
+        TryIntoJavaValue::try_into(self.0, env)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U> FromJavaValue<'env, 'borrow> for JavaList<T>
+where
+    T: FromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = JObject<'env>;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        JavaList(FromJavaValue::from(s, env))
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U> TryFromJavaValue<'env, 'borrow> for JavaList<T>
+where
+    T: TryFromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = JObject<'env>;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> jni::errors::Result<Self> {
+        TryFromJavaValue::try_from(s, env).map(JavaList)
+    }
+}
+
+/// A primitive boxed to its wrapper class, e.g. `Boxed<bool>` is a `java.lang.Boolean` and `Boxed<char>` a `java.lang.Character`.
+///
+/// This is useful for generic Java APIs handing back boxed values. Unlike `Option<T>`, `null` isn't accepted:
+/// use `Option<T>` for nullable wrappers. Safe conversions from Java check the class of the object before unboxing it.
+///
+/// ```ignore
+/// pub extern "jni" fn negate(flag: Boxed<bool>) -> Boxed<bool> {
+///     Boxed(!flag.0)
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Boxed<T>(pub T);
+
+impl<T: Signature> Signature for Boxed<T> {
+    const SIG_TYPE: &'static str = boxed_signature(<T as Signature>::SIG_TYPE);
+}
+
+impl<'env, T> IntoJavaValue<'env> for Boxed<T>
+where
+    T: IntoJavaValue<'env>,
+{
+    type Target = JObject<'env>;
+    const SIG_TYPE: &'static str = boxed_signature(<T as IntoJavaValue<'env>>::SIG_TYPE);
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        JavaValue::autobox(IntoJavaValue::into(self.0, env), env)
+    }
+}
+
+impl<'env, T> TryIntoJavaValue<'env> for Boxed<T>
+where
+    T: TryIntoJavaValue<'env>,
+{
+    type Target = JObject<'env>;
+    const SIG_TYPE: &'static str = boxed_signature(<T as TryIntoJavaValue<'env>>::SIG_TYPE);
+
+    fn try_into(self, env: &JNIEnv<'env>) -> jni::errors::Result<Self::Target> {
+        Ok(JavaValue::autobox(TryIntoJavaValue::try_into(self.0, env)?, env))
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U> FromJavaValue<'env, 'borrow> for Boxed<T>
+where
+    T: FromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = JObject<'env>;
+    const SIG_TYPE: &'static str = boxed_signature(<T as FromJavaValue<'env, 'borrow>>::SIG_TYPE);
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        Boxed(T::from(U::unbox(s, env), env))
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U> TryFromJavaValue<'env, 'borrow> for Boxed<T>
+where
+    T: TryFromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = JObject<'env>;
+    const SIG_TYPE: &'static str =
+        boxed_signature(<T as TryFromJavaValue<'env, 'borrow>>::SIG_TYPE);
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> jni::errors::Result<Self> {
+        let sig = <Self as TryFromJavaValue<'env, 'borrow>>::SIG_TYPE;
+
+        if s.is_null() {
+            return Err(Error::NullPtr("boxed value"));
+        }
+        if sig.starts_with('L') && !env.is_instance_of(s, &sig[1..sig.len() - 1])? {
+            return Err(Error::WrongJValueType(sig, "object of another class"));
+        }
+
+        T::try_from(U::unbox(s, env), env).map(Boxed)
+    }
+}
+
 pub struct JValueWrapper<'a>(pub JValue<'a>);
 
 impl<'a> From<JValue<'a>> for JValueWrapper<'a> {
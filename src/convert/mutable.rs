@@ -0,0 +1,100 @@
+//! In-place writes to Java byte arrays.
+//!
+//! A [`MutableByteArray`] lets native code fill a `byte[]` allocated by the caller, the pattern of `InputStream.read(byte[])`:
+//! the array is copied to a Rust buffer, exposed as `&mut [u8]`, and the buffer is written back with `SetByteArrayRegion`.
+
+use std::ops::{Deref, DerefMut};
+use std::slice;
+
+use jni::errors::Result;
+use jni::sys::{jbyte, jbyteArray};
+use jni::JNIEnv;
+
+use crate::convert::{FromJavaValue, Signature, TryFromJavaValue};
+
+/// Mutable view of a Java `byte[]`, written back to the array when committed or dropped.
+///
+/// The bytes are accessed through [`DerefMut<Target = [u8]>`](DerefMut). If they have been mutably borrowed,
+/// the buffer is copied back to the Java array by [`commit`](MutableByteArray::commit), or when the wrapper is dropped.
+/// An error while writing back on drop is ignored, so `commit` should be preferred when it has to be reported:
+///
+/// ```ignore
+/// pub extern "jni" fn read(self, mut buf: MutableByteArray<'borrow>) -> JniResult<i32> {
+///     let count = buf.len().min(self.remaining.len());
+///     buf[..count].copy_from_slice(&self.remaining[..count]);
+///     buf.commit()?;
+///     Ok(count as i32)
+/// }
+/// ```
+pub struct MutableByteArray<'a> {
+    env: &'a JNIEnv<'a>,
+    array: jbyteArray,
+    buf: Vec<u8>,
+    dirty: bool,
+}
+
+impl<'a> MutableByteArray<'a> {
+    /// Write the buffer back to the Java array, if it has been mutably borrowed.
+    pub fn commit(mut self) -> Result<()> {
+        self.write_back()
+    }
+
+    fn write_back(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        self.dirty = false;
+
+        let region =
+            unsafe { slice::from_raw_parts(self.buf.as_ptr() as *const jbyte, self.buf.len()) };
+        self.env.set_byte_array_region(self.array, 0, region)
+    }
+}
+
+impl<'a> Deref for MutableByteArray<'a> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.buf
+    }
+}
+
+impl<'a> DerefMut for MutableByteArray<'a> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.dirty = true;
+        &mut self.buf
+    }
+}
+
+impl<'a> Drop for MutableByteArray<'a> {
+    fn drop(&mut self) {
+        let _ = self.write_back();
+    }
+}
+
+impl<'a> Signature for MutableByteArray<'a> {
+    const SIG_TYPE: &'static str = "[B";
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for MutableByteArray<'borrow> {
+    type Source = jbyteArray;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        let buf = env.convert_byte_array(s)?;
+
+        Ok(MutableByteArray {
+            env,
+            array: s,
+            buf,
+            dirty: false,
+        })
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for MutableByteArray<'borrow> {
+    type Source = jbyteArray;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
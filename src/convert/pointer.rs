@@ -0,0 +1,93 @@
+//! Rust values owned by Java objects through a native pointer.
+//!
+//! With the `#[receiver(native_pointer = "field")]` struct attribute, exported `self` methods don't convert the Java object:
+//! the struct value lives on the Rust heap, and its address is stored in a `long` field of the object.
+//! The value is moved to the heap with [`into_native_pointer`], typically in a static exported method whose result is stored in that field:
+//!
+//! ```ignore
+//! #[package(com.example)]
+//! #[receiver(native_pointer = "handle")]
+//! pub struct Counter {
+//!     count: i32,
+//! }
+//!
+//! impl Counter {
+//!     // private final long handle = Counter.create();
+//!     pub extern "jni" fn create() -> i64 {
+//!         robusta_jni::convert::into_native_pointer(Counter { count: 0 })
+//!     }
+//!
+//!     pub extern "jni" fn increment(&mut self) -> i32 {
+//!         self.count += 1;
+//!         self.count
+//!     }
+//!
+//!     // Drops the value and resets the field to `0`
+//!     pub extern "jni" fn close(self) {}
+//! }
+//! ```
+//!
+//! `&self` and `&mut self` methods borrow the value in place, while `self` methods take it back and reset the field to `0`.
+//! Calling a method after that fails with a [`Error::NullPtr`] error.
+//! The Java class is responsible for not calling `&mut self` methods concurrently with other methods.
+
+use jni::errors::{Error, Result};
+use jni::objects::{JObject, JValue};
+use jni::sys::jlong;
+use jni::JNIEnv;
+
+/// Move `value` to the heap, returning its address to be stored in the native pointer field of a Java object.
+pub fn into_native_pointer<T>(value: T) -> jlong {
+    Box::into_raw(Box::new(value)) as jlong
+}
+
+fn native_pointer<T>(env: &JNIEnv, obj: JObject, field: &str) -> Result<*mut T> {
+    match env.get_field(obj, field, "J")?.j()? {
+        0 => Err(Error::NullPtr("native pointer")),
+        pointer => Ok(pointer as *mut T),
+    }
+}
+
+/// Borrow the value whose address is stored in the `field` of `obj`.
+///
+/// # Safety
+///
+/// The field must hold a pointer returned by [`into_native_pointer`] for a value of type `T` which hasn't been taken back,
+/// and the value must not be mutably borrowed during `'a`.
+#[doc(hidden)]
+pub unsafe fn borrow_native_pointer<'a, T>(
+    env: &JNIEnv,
+    obj: JObject,
+    field: &str,
+) -> Result<&'a T> {
+    native_pointer(env, obj, field).map(|pointer| &*pointer)
+}
+
+/// Mutably borrow the value whose address is stored in the `field` of `obj`.
+///
+/// # Safety
+///
+/// The field must hold a pointer returned by [`into_native_pointer`] for a value of type `T` which hasn't been taken back,
+/// and the value must not be borrowed otherwise during `'a`.
+#[doc(hidden)]
+pub unsafe fn borrow_native_pointer_mut<'a, T>(
+    env: &JNIEnv,
+    obj: JObject,
+    field: &str,
+) -> Result<&'a mut T> {
+    native_pointer(env, obj, field).map(|pointer| &mut *pointer)
+}
+
+/// Take back the value whose address is stored in the `field` of `obj`, resetting the field to `0`.
+///
+/// # Safety
+///
+/// The field must hold a pointer returned by [`into_native_pointer`] for a value of type `T` which hasn't been taken back,
+/// and the value must not be borrowed.
+#[doc(hidden)]
+pub unsafe fn take_native_pointer<T>(env: &JNIEnv, obj: JObject, field: &str) -> Result<T> {
+    let pointer = native_pointer::<T>(env, obj, field)?;
+    env.set_field(obj, field, "J", JValue::Long(0))?;
+
+    Ok(*Box::from_raw(pointer))
+}
@@ -0,0 +1,98 @@
+//! Conversions between string maps and `java.util.Properties`.
+//!
+//! A `HashMap<String, String>` is converted to a new `Properties` with `setProperty`, one entry at a time.
+//! In the other direction the names are listed with `stringPropertyNames` and read with `getProperty`: entries whose key or value
+//! is not a `String` are skipped, and the defaults of the `Properties` are included, as they would be seen from `getProperty`.
+
+use std::collections::HashMap;
+
+use jni::errors::Result;
+use jni::objects::{JObject, JString, JValue};
+use jni::JNIEnv;
+
+use crate::convert::{unchecked, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+impl Signature for HashMap<String, String> {
+    const SIG_TYPE: &'static str = "Ljava/util/Properties;";
+}
+
+impl<'env> TryIntoJavaValue<'env> for HashMap<String, String> {
+    type Target = JObject<'env>;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let properties = env.new_object("java/util/Properties", "()V", &[])?;
+
+        for (key, value) in self {
+            let key = env.new_string(key)?;
+            let value = env.new_string(value)?;
+            let previous = env
+                .call_method(
+                    properties,
+                    "setProperty",
+                    "(Ljava/lang/String;Ljava/lang/String;)Ljava/lang/Object;",
+                    &[JValue::from(key), JValue::from(value)],
+                )?
+                .l()?;
+
+            /* Large maps would otherwise overflow the local reference table */
+            env.delete_local_ref(previous)?;
+            env.delete_local_ref(*key)?;
+            env.delete_local_ref(*value)?;
+        }
+
+        Ok(properties)
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for HashMap<String, String> {
+    type Source = JObject<'env>;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        let names = env
+            .call_method(s, "stringPropertyNames", "()Ljava/util/Set;", &[])?
+            .l()?;
+        let iterator = env
+            .call_method(names, "iterator", "()Ljava/util/Iterator;", &[])?
+            .l()?;
+
+        let mut map = HashMap::new();
+        while env.call_method(iterator, "hasNext", "()Z", &[])?.z()? {
+            let key = env
+                .call_method(iterator, "next", "()Ljava/lang/Object;", &[])?
+                .l()?;
+            let value = env
+                .call_method(
+                    s,
+                    "getProperty",
+                    "(Ljava/lang/String;)Ljava/lang/String;",
+                    &[JValue::from(key)],
+                )?
+                .l()?;
+
+            map.insert(
+                TryFromJavaValue::try_from(JString::from(key), env)?,
+                TryFromJavaValue::try_from(JString::from(value), env)?,
+            );
+            env.delete_local_ref(key)?;
+            env.delete_local_ref(value)?;
+        }
+
+        Ok(map)
+    }
+}
+
+impl<'env> unchecked::IntoJavaValue<'env> for HashMap<String, String> {
+    type Target = JObject<'env>;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> unchecked::FromJavaValue<'env, 'borrow> for HashMap<String, String> {
+    type Source = JObject<'env>;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
@@ -14,15 +14,101 @@
 //! ```
 //!
 //! Both of these parameters are optional. By default, the exception class is `java.lang.RuntimeException`.
+//! After throwing, the native function returns the [`DefaultJavaValue`](crate::convert::DefaultJavaValue) of its JNI return type (zero or `null`).
+//!
+//! The exception class can also be taken from a `&str` constant with the `exception_class_const` parameter, in place of `exception_class`:
+//!
+//! ```ignore
+//! #[call_type(safe(exception_class_const = crate::errors::IO_EXCEPTION_CLASS))]
+//! ```
+//!
+//! For methods returning a `Result<T, E>` where `E` implements [`std::error::Error`], the `derive_class` parameter throws an instance
+//! of the class named after `E` in the given package, with the error as message: here a `ParseError` is thrown as a `com.example.errors.ParseError`.
+//!
+//! ```ignore
+//! #[call_type(safe(derive_class = "com.example.errors"))]
+//! pub extern "jni" fn parse(s: String) -> Result<i32, ParseError> { ... }
+//! ```
+//!
+//! With the `anyhow` feature, methods can also return `anyhow::Result<T>` or `Result<T, anyhow::Error>` (the type must be spelled this way):
+//! errors are thrown with their whole context chain as message, e.g. `JNI call error!. Cause: loading config: reading file: not found`.
+//! See [`AnyhowError`](crate::convert::AnyhowError).
+//!
+//! Methods returning a `Result<T, ()>` throw the exception of the attribute (by default `java.lang.RuntimeException`)
+//! when returning `Err(())`, whose message then only names the method's `Err(())` as cause.
+//!
+//! Methods returning an object that may be `null` can return a `Result<Option<T>, E>`: `Err` throws, `Ok(None)` returns `null`
+//! and `Ok(Some(v))` returns `v`.
+//!
+//! For all these `Result`s the returned variant is handled first: `Err` is thrown as described, and only an `Ok` value is converted.
+//! If converting that value fails, the method's own error classes are not used: a `java.lang.IllegalStateException` is thrown instead,
+//! with a message like `failed to convert the returned value: ...`. See [`report_return_conversion`].
+//!
+//! ## Rust backtraces
+//! The Java stack trace of a thrown exception ends at the native method. Setting the `backtrace` parameter appends a Rust
+//! backtrace to the exception message:
+//!
+//! ```ignore
+//! #[call_type(safe(backtrace = true))]
+//! ```
+//!
+//! The backtrace is captured (regardless of `RUST_BACKTRACE`) when the error reaches the generated wrapper, so it shows the native
+//! frames leading to the throw rather than the place where the error was created. Capturing it is expensive, which is why it is disabled by default.
+//!
+//! ## Logging
+//! The `log_to` parameter logs exceptions before throwing them, with the logger named after the bridged class:
+//!
+//! ```ignore
+//! #[call_type(safe(log_to = "org.slf4j.LoggerFactory"))]
+//! ```
+//!
+//! The logger is obtained from the static `getLogger(String)` method of the given class, and the exception is passed to its
+//! `error(String, Throwable)` method. If logging fails the exception is thrown all the same.
+//!
+//! ## Conversion context
+//! Conversions needing state other than the `JNIEnv` can receive a reference to a `static` or `const` item with the `with_context` parameter:
+//!
+//! ```ignore
+//! #[call_type(safe(with_context = crate::REGISTRY))]
+//! ```
+//!
+//! Parameters and return value are then converted with [`TryFromJavaValueCtx`](crate::convert::TryFromJavaValueCtx) and
+//! [`TryIntoJavaValueCtx`](crate::convert::TryIntoJavaValueCtx), which are implemented by all the types converted by the traits of this module.
+//!
+//! ## Pending exceptions
+//! By default the generated wrapper checks whether a Java exception is pending after converting input parameters:
+//! if one is, the native function is not called and the pending exception is left to propagate to the caller.
+//! The same check is done before throwing, so that a new exception is never raised on top of a pending one.
+//!
+//! Advanced users that already know no exception can be pending can skip these checks with the `check_pending` parameter:
+//!
+//! ```ignore
+//! #[call_type(safe(check_pending = false))]
+//! ```
+//!
+//! **Calling most JNI functions while an exception is pending is undefined behaviour**:
+//! if a conversion leaves an exception pending and `check_pending` is disabled, the native function body and the exception throwing
+//! code will run regardless, with all the consequences of the case.
 //!
 
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::ffi::{CStr, CString};
+use std::fmt;
+use std::num::Wrapping;
+use std::ops::{ControlFlow, Range, RangeInclusive};
+use std::process::ExitStatus;
+
 use jni::errors::{Error, Result};
-use jni::objects::{JList, JObject, JString, JValue};
-use jni::sys::{jboolean, jbooleanArray, jbyteArray, jchar, jobject};
+use jni::objects::{JList, JObject, JString, JThrowable, JValue};
+use jni::sys::{
+    jboolean, jbooleanArray, jbyteArray, jchar, jcharArray, jdoubleArray, jfloatArray, jint,
+    jintArray, jlongArray, jobject, jobjectArray, jshortArray,
+};
 use jni::JNIEnv;
 
 use crate::convert::unchecked::{FromJavaValue, IntoJavaValue};
-use crate::convert::{JavaValue, Signature};
+use crate::convert::{boxed_signature, ArraySignature, JavaValue, Signature};
 
 pub use robusta_codegen::{TryFromJavaValue, TryIntoJavaValue};
 
@@ -54,7 +140,8 @@ pub trait TryIntoJavaValue<'env>: Signature {
 /// # Notes on the derive macro
 /// When using the derive macro, the deriving struct **must** have a [`AutoLocal`] field annotated with both `'env` and `'borrow` lifetimes and a `#[instance]` attribute.
 /// This fields keeps a [local reference](https://docs.oracle.com/en/java/javase/15/docs/specs/jni/design.html#global-and-local-references) to the underlying Java object.
-/// All other fields are automatically initialized from fields on the Java instance with the same name.
+/// All other fields are automatically initialized from fields on the Java instance with the same name,
+/// except for [`PhantomData`](std::marker::PhantomData) fields, which have no Java counterpart and are skipped.
 ///
 /// Example:
 ///
@@ -79,6 +166,9 @@ pub trait TryIntoJavaValue<'env>: Signature {
 /// # }
 /// ```
 ///
+/// With a `#[std_convert]` attribute on the struct, [`TryFrom<(JObject<'env>, &'borrow JNIEnv<'env>)>`](std::convert::TryFrom)
+/// is implemented as well, so that `(obj, env).try_into()` performs the same conversion outside of bridged methods.
+///
 /// [`AutoLocal`]: jni::objects::AutoLocal
 ///
 pub trait TryFromJavaValue<'env: 'borrow, 'borrow>
@@ -135,6 +225,62 @@ impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for String {
     }
 }
 
+/// Optional borrowed strings can only be passed to Java: there is no conversion from Java to `Option<&str>`, use `Option<String>` instead.
+///
+/// `&str` itself has no conversion, so that `"...".into()` stays unambiguous for code importing the conversion traits.
+impl<'env> TryIntoJavaValue<'env> for Option<&str> {
+    type Target = JObject<'env>;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        match self {
+            Some(s) => env.new_string(s).map(Into::into),
+            None => Ok(JObject::null()),
+        }
+    }
+}
+
+impl<'env> TryIntoJavaValue<'env> for fmt::Arguments<'_> {
+    type Target = JString<'env>;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        // Arguments without any formatting parameter don't need an intermediate `String`.
+        match self.as_str() {
+            Some(s) => env.new_string(s),
+            None => env.new_string(self.to_string()),
+        }
+    }
+}
+
+/// C strings are passed to Java as strings if they hold valid UTF-8.
+impl<'env> TryIntoJavaValue<'env> for &CStr {
+    type Target = JString<'env>;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let s = self
+            .to_str()
+            .map_err(|_| Error::WrongJValueType("UTF-8 string", "C string"))?;
+        env.new_string(s)
+    }
+}
+
+impl<'env> TryIntoJavaValue<'env> for CString {
+    type Target = JString<'env>;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        TryIntoJavaValue::try_into(self.as_c_str(), env)
+    }
+}
+
+/// Java strings containing `'\0'` characters can't be converted to a [`CString`].
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for CString {
+    type Source = JString<'env>;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        let s: String = env.get_string(s)?.into();
+        CString::new(s).map_err(|_| Error::WrongJValueType("CString", "string with nul characters"))
+    }
+}
+
 impl<'env> TryIntoJavaValue<'env> for bool {
     type Target = jboolean;
 
@@ -202,6 +348,40 @@ impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Box<[bool]> {
     }
 }
 
+impl Signature for Box<[char]> {
+    const SIG_TYPE: &'static str = "[C";
+}
+
+/// `char`s are encoded as UTF-16, so characters outside the Basic Multilingual Plane take two elements (a surrogate pair)
+/// of the Java array.
+///
+/// A `Vec<char>` is converted to an `ArrayList<Character>`: convert it with [`Vec::into_boxed_slice`] to get a `char[]`.
+impl<'env> TryIntoJavaValue<'env> for Box<[char]> {
+    type Target = jcharArray;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let buf: Vec<jchar> = self.iter().collect::<String>().encode_utf16().collect();
+        let raw = env.new_char_array(buf.len() as i32)?;
+        env.set_char_array_region(raw, 0, &buf)?;
+        Ok(raw)
+    }
+}
+
+/// Surrogate pairs are decoded to a single `char`, while unpaired surrogates make the conversion fail.
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Box<[char]> {
+    type Source = jcharArray;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        let len = env.get_array_length(s)?;
+        let mut buf = vec![0; len as usize];
+        env.get_char_array_region(s, 0, &mut buf)?;
+
+        std::char::decode_utf16(buf)
+            .map(|c| c.map_err(|_| Error::WrongJValueType("char", "unpaired surrogate")))
+            .collect()
+    }
+}
+
 impl<'env, T> TryIntoJavaValue<'env> for Vec<T>
 where
     T: TryIntoJavaValue<'env>,
@@ -269,6 +449,401 @@ impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Box<[u8]> {
     }
 }
 
+macro_rules! primitive_slice_types {
+    ($($type:ty: $sig:literal, $array:ty, $new_array:ident, $get_region:ident, $set_region:ident;)*) => {
+        $(
+            impl Signature for Box<[$type]> {
+                const SIG_TYPE: &'static str = $sig;
+            }
+
+            impl<'env> TryIntoJavaValue<'env> for Box<[$type]> {
+                type Target = $array;
+
+                fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+                    let raw = env.$new_array(self.len() as i32)?;
+                    env.$set_region(raw, 0, &self)?;
+                    Ok(raw)
+                }
+            }
+
+            impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Box<[$type]> {
+                type Source = $array;
+
+                fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+                    let len = env.get_array_length(s)?;
+                    let mut buf = vec![Default::default(); len as usize].into_boxed_slice();
+                    env.$get_region(s, 0, &mut buf)?;
+                    Ok(buf)
+                }
+            }
+        )*
+    };
+}
+
+/* Primitive arrays are copied in bulk with the `Get/Set<Type>ArrayRegion` functions */
+primitive_slice_types! {
+    i8: "[B", jbyteArray, new_byte_array, get_byte_array_region, set_byte_array_region;
+    i16: "[S", jshortArray, new_short_array, get_short_array_region, set_short_array_region;
+    i32: "[I", jintArray, new_int_array, get_int_array_region, set_int_array_region;
+    i64: "[J", jlongArray, new_long_array, get_long_array_region, set_long_array_region;
+    f32: "[F", jfloatArray, new_float_array, get_float_array_region, set_float_array_region;
+    f64: "[D", jdoubleArray, new_double_array, get_double_array_region, set_double_array_region;
+}
+
+impl<T: ArraySignature> Signature for Box<[T]> {
+    const SIG_TYPE: &'static str = <T as ArraySignature>::ARRAY_SIG_TYPE;
+}
+
+/// Elements are converted with the [`TryIntoJavaValue`] implementation of `T` into a Java array of `T`'s class.
+///
+/// `Vec<T>` keeps converting to an `ArrayList`: convert it with [`Vec::into_boxed_slice`] to get a Java array.
+impl<'env, T> TryIntoJavaValue<'env> for Box<[T]>
+where
+    T: ArraySignature + TryIntoJavaValue<'env>,
+{
+    type Target = jobjectArray;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let element_sig = <T as Signature>::SIG_TYPE;
+        let element_class = env.find_class(&element_sig[1..element_sig.len() - 1])?;
+        let raw = env.new_object_array(self.len() as i32, element_class, JObject::null())?;
+
+        for (i, el) in self.into_vec().into_iter().enumerate() {
+            let el = JavaValue::autobox(TryIntoJavaValue::try_into(el, env)?, env);
+            env.set_object_array_element(raw, i as i32, el)?;
+        }
+
+        Ok(raw)
+    }
+}
+
+impl<T: ArraySignature> Signature for &[T] {
+    const SIG_TYPE: &'static str = <T as ArraySignature>::ARRAY_SIG_TYPE;
+}
+
+/// Elements are converted by reference with the [`TryIntoJavaValue`] implementation of `&T` (e.g. a `#[derive(TryIntoJavaValue)]` struct),
+/// directly into a Java array of `T`'s class without collecting them first.
+///
+/// Unlike `Box<[T]>`, the array is returned as a [`JObject`], so that slices can be passed to imported methods.
+impl<'env, 'a, T> TryIntoJavaValue<'env> for &'a [T]
+where
+    T: ArraySignature,
+    &'a T: TryIntoJavaValue<'env>,
+{
+    type Target = JObject<'env>;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let element_sig = <T as Signature>::SIG_TYPE;
+        let element_class = env.find_class(&element_sig[1..element_sig.len() - 1])?;
+        let raw = env.new_object_array(self.len() as i32, element_class, JObject::null())?;
+
+        for (i, el) in self.iter().enumerate() {
+            let el = JavaValue::autobox(TryIntoJavaValue::try_into(el, env)?, env);
+            env.set_object_array_element(raw, i as i32, el)?;
+        }
+
+        Ok(JavaValue::autobox(raw, env))
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U> TryFromJavaValue<'env, 'borrow> for Box<[T]>
+where
+    T: ArraySignature + TryFromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = jobjectArray;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        let len = env.get_array_length(s)?;
+
+        (0..len)
+            .map(|i| T::try_from(U::unbox(env.get_object_array_element(s, i)?, env), env))
+            .collect()
+    }
+}
+
+/// `None` is converted to `null`, `Some(v)` to `v` converted with the [`TryIntoJavaValue`] implementation of `T`,
+/// boxed to its wrapper class if `T` is a primitive (e.g. `Option<i32>` is an `Integer`).
+impl<'env, T> TryIntoJavaValue<'env> for Option<T>
+where
+    T: TryIntoJavaValue<'env>,
+{
+    type Target = JObject<'env>;
+    const SIG_TYPE: &'static str = boxed_signature(<T as TryIntoJavaValue<'env>>::SIG_TYPE);
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        match self {
+            Some(v) => Ok(JavaValue::autobox(TryIntoJavaValue::try_into(v, env)?, env)),
+            None => Ok(JObject::null()),
+        }
+    }
+}
+
+/// `null` is converted to `None`, any other value to `Some` of the value converted with the [`TryFromJavaValue`] implementation of `T`.
+impl<'env: 'borrow, 'borrow, T, U> TryFromJavaValue<'env, 'borrow> for Option<T>
+where
+    T: TryFromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = JObject<'env>;
+    const SIG_TYPE: &'static str =
+        boxed_signature(<T as TryFromJavaValue<'env, 'borrow>>::SIG_TYPE);
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        if s.is_null() {
+            Ok(None)
+        } else {
+            T::try_from(U::unbox(s, env), env).map(Some)
+        }
+    }
+}
+
+/// [`Wrapping<T>`](Wrapping) is converted as the wrapped `T`.
+impl<'env, T> TryIntoJavaValue<'env> for Wrapping<T>
+where
+    T: TryIntoJavaValue<'env>,
+{
+    type Target = <T as TryIntoJavaValue<'env>>::Target;
+    const SIG_TYPE: &'static str = <T as TryIntoJavaValue<'env>>::SIG_TYPE;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        TryIntoJavaValue::try_into(self.0, env)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T> TryFromJavaValue<'env, 'borrow> for Wrapping<T>
+where
+    T: TryFromJavaValue<'env, 'borrow>,
+{
+    type Source = <T as TryFromJavaValue<'env, 'borrow>>::Source;
+    const SIG_TYPE: &'static str = <T as TryFromJavaValue<'env, 'borrow>>::SIG_TYPE;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        T::try_from(s, env).map(Wrapping)
+    }
+}
+
+/// [`Cow<'_, B>`](Cow) is converted as the borrowed `&B` or the owned value, which must convert to the same Java type.
+///
+/// For example `Cow<'_, CStr>` converts through `&CStr` and `CString`. `Cow<'_, str>` has no conversion, as `&str` doesn't have one.
+impl<'env, 'a, B> TryIntoJavaValue<'env> for Cow<'a, B>
+where
+    B: ToOwned + ?Sized,
+    &'a B: TryIntoJavaValue<'env>,
+    <B as ToOwned>::Owned:
+        TryIntoJavaValue<'env, Target = <&'a B as TryIntoJavaValue<'env>>::Target>,
+{
+    type Target = <&'a B as TryIntoJavaValue<'env>>::Target;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        match self {
+            Cow::Borrowed(b) => TryIntoJavaValue::try_into(b, env),
+            Cow::Owned(o) => TryIntoJavaValue::try_into(o, env),
+        }
+    }
+}
+
+/// Ranges are represented in Java as an `int[2]` array holding the start and end bounds.
+/// Whether the end bound is inclusive is determined by the Rust type.
+impl Signature for Range<i32> {
+    const SIG_TYPE: &'static str = "[I";
+}
+
+impl<'env> TryIntoJavaValue<'env> for Range<i32> {
+    type Target = jintArray;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let raw = env.new_int_array(2)?;
+        env.set_int_array_region(raw, 0, &[self.start, self.end])?;
+        Ok(raw)
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Range<i32> {
+    type Source = jintArray;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        let [start, end] = range_bounds(s, env, "Range<i32>")?;
+        Ok(start..end)
+    }
+}
+
+impl Signature for RangeInclusive<i32> {
+    const SIG_TYPE: &'static str = "[I";
+}
+
+impl<'env> TryIntoJavaValue<'env> for RangeInclusive<i32> {
+    type Target = jintArray;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let raw = env.new_int_array(2)?;
+        env.set_int_array_region(raw, 0, &[*self.start(), *self.end()])?;
+        Ok(raw)
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for RangeInclusive<i32> {
+    type Source = jintArray;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        let [start, end] = range_bounds(s, env, "RangeInclusive<i32>")?;
+        Ok(start..=end)
+    }
+}
+
+/// [`ControlFlow<B, C>`](ControlFlow) is represented in Java as an `Object[2]` array: the first element is a `Boolean`
+/// that is `true` for [`Break`](ControlFlow::Break), the second one is the boxed payload of the variant
+/// (`null` for the default `C = ()`).
+impl<B, C> Signature for ControlFlow<B, C> {
+    const SIG_TYPE: &'static str = "[Ljava/lang/Object;";
+}
+
+impl<'env, B, C> TryIntoJavaValue<'env> for ControlFlow<B, C>
+where
+    B: TryIntoJavaValue<'env>,
+    C: TryIntoJavaValue<'env>,
+{
+    type Target = jobjectArray;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let (is_break, payload) = match self {
+            ControlFlow::Break(b) => (true, control_flow_payload(b, env)?),
+            ControlFlow::Continue(c) => (false, control_flow_payload(c, env)?),
+        };
+
+        let raw = env.new_object_array(2, "java/lang/Object", JObject::null())?;
+        env.set_object_array_element(raw, 0, (is_break as jboolean).autobox(env))?;
+        env.set_object_array_element(raw, 1, payload)?;
+        Ok(raw)
+    }
+}
+
+fn control_flow_payload<'env, T: TryIntoJavaValue<'env>>(
+    v: T,
+    env: &JNIEnv<'env>,
+) -> Result<JObject<'env>> {
+    let value = TryIntoJavaValue::try_into(v, env)?;
+
+    // `()` has no boxed representation
+    if <T as TryIntoJavaValue>::SIG_TYPE == "V" {
+        Ok(JObject::null())
+    } else {
+        Ok(value.autobox(env))
+    }
+}
+
+impl<'env: 'borrow, 'borrow, B, C> TryFromJavaValue<'env, 'borrow> for ControlFlow<B, C>
+where
+    B: TryFromJavaValue<'env, 'borrow>,
+    C: TryFromJavaValue<'env, 'borrow>,
+{
+    type Source = jobjectArray;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        if env.get_array_length(s)? != 2 {
+            return Err(Error::WrongJValueType("ControlFlow", "Object[]"));
+        }
+
+        let is_break = <jboolean as JavaValue>::unbox(env.get_object_array_element(s, 0)?, env);
+        let payload = env.get_object_array_element(s, 1)?;
+
+        if is_break != 0 {
+            B::try_from(JavaValue::unbox(payload, env), env).map(ControlFlow::Break)
+        } else {
+            C::try_from(JavaValue::unbox(payload, env), env).map(ControlFlow::Continue)
+        }
+    }
+}
+
+/// [`ExitStatus`] is converted to the exit code of the process, like Java's `Process.exitValue()`.
+/// On Unix, processes terminated by a signal have an exit code of `128` plus the signal number (e.g. `137` for `SIGKILL`).
+impl Signature for ExitStatus {
+    const SIG_TYPE: &'static str = "I";
+}
+
+impl<'env> TryIntoJavaValue<'env> for ExitStatus {
+    type Target = jint;
+
+    fn try_into(self, _env: &JNIEnv<'env>) -> Result<Self::Target> {
+        if let Some(code) = self.code() {
+            return Ok(code);
+        }
+
+        #[cfg(unix)]
+        if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&self) {
+            return Ok(128 + signal);
+        }
+
+        Err(Error::WrongJValueType("exit code", "ExitStatus"))
+    }
+}
+
+fn range_bounds(s: jintArray, env: &JNIEnv, target: &'static str) -> Result<[i32; 2]> {
+    if env.get_array_length(s)? != 2 {
+        return Err(Error::WrongJValueType(target, "int[]"));
+    }
+
+    let mut bounds = [0; 2];
+    env.get_int_array_region(s, 0, &mut bounds)?;
+    Ok(bounds)
+}
+
+/// Error raised by the generated wrapper of a `#[call_type(safe)]` exported method when one of its arguments cannot be converted.
+///
+/// Its message, included in the thrown Java exception, contains the name and the (1-based) position of the argument, e.g.
+/// ``failed to convert argument `count` (#2): ...``. The receiver of `self` methods is reported as `receiver`, at position 0.
+#[derive(Debug)]
+pub struct ArgumentConversionError {
+    pub name: &'static str,
+    pub index: usize,
+    pub source: Error,
+}
+
+impl ArgumentConversionError {
+    pub fn new(name: &'static str, index: usize, source: Error) -> Self {
+        ArgumentConversionError {
+            name,
+            index,
+            source,
+        }
+    }
+}
+
+impl fmt::Display for ArgumentConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to convert argument `{}` (#{}): {}",
+            self.name, self.index, self.source
+        )
+    }
+}
+
+impl std::error::Error for ArgumentConversionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Class of the exception thrown when the `Ok` value returned by a `#[call_type(safe)]` method fails to convert.
+pub const RETURN_CONVERSION_EXCEPTION_CLASS: &str = "java/lang/IllegalStateException";
+
+/// Report a failed conversion of the `Ok` value returned by a `#[call_type(safe)]` method.
+///
+/// An error is thrown as a [`RETURN_CONVERSION_EXCEPTION_CLASS`], so that it can't be mistaken for the errors of the method itself,
+/// and turned into a [`Error::JavaException`]. If an exception is already pending (e.g. raised by a Java call made during the conversion)
+/// it is left to propagate instead.
+pub fn report_return_conversion<T>(env: &JNIEnv, converted: Result<T>) -> Result<T> {
+    match converted {
+        Err(e) if !matches!(e, Error::JavaException) && !env.exception_check().unwrap_or(true) => {
+            let message = format!("failed to convert the returned value: {}", e);
+            env.throw_new(RETURN_CONVERSION_EXCEPTION_CLASS, message)?;
+            Err(Error::JavaException)
+        }
+        converted => converted,
+    }
+}
+
 /// When returning a [`jni::errors::Result`], if the returned variant is `Ok(v)` then the value `v` is returned as usual.
 ///
 /// If the returned value is `Err`, the Java exception specified in the `#[call_type(safe)]` attribute is thrown
@@ -280,6 +855,83 @@ where
     type Target = <T as TryIntoJavaValue<'env>>::Target;
 
     fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
-        self.and_then(|s| TryIntoJavaValue::try_into(s, env))
+        self.and_then(|s| report_return_conversion(env, TryIntoJavaValue::try_into(s, env)))
+    }
+}
+
+/// An error that builds the exact Java throwable thrown for it.
+///
+/// When returning a `Result<T, E>` where `E: ToThrowable`, if the returned variant is `Err(e)` then the throwable built by
+/// [`to_throwable`](ToThrowable::to_throwable) is thrown, with any field set on it. `T` can be `()` for methods returning `void`.
+/// If building the throwable fails, the Java exception specified in the `#[call_type(safe)]` attribute is thrown instead.
+///
+/// ```ignore
+/// impl<'env> ToThrowable<'env> for QuotaError {
+///     fn to_throwable(self, env: &JNIEnv<'env>) -> JniResult<JThrowable<'env>> {
+///         let t = env.new_object("com/example/QuotaExceededException", "(Ljava/lang/String;)V", &[env.new_string(self.to_string())?.into()])?;
+///         env.set_field(t, "limit", "J", JValue::Long(self.limit))?;
+///         Ok(t.into())
+///     }
+/// }
+/// ```
+///
+/// It is implemented by [`JThrowable`], which is rethrown preserving its class and stack trace, and by the types converting
+/// to a Java object (e.g. a `#[derive(TryIntoJavaValue)]` exception class), which are thrown if the converted object is a `java.lang.Throwable`.
+pub trait ToThrowable<'env> {
+    /// Build the throwable to throw for this error.
+    fn to_throwable(self, env: &JNIEnv<'env>) -> Result<JThrowable<'env>>;
+}
+
+impl<'env> ToThrowable<'env> for JThrowable<'env> {
+    fn to_throwable(self, _env: &JNIEnv<'env>) -> Result<JThrowable<'env>> {
+        Ok(self)
+    }
+}
+
+impl<'env, E> ToThrowable<'env> for E
+where
+    E: TryIntoJavaValue<'env, Target = JObject<'env>>,
+{
+    fn to_throwable(self, env: &JNIEnv<'env>) -> Result<JThrowable<'env>> {
+        let error = TryIntoJavaValue::try_into(self, env)?;
+        if env.is_instance_of(error, "java/lang/Throwable")? {
+            Ok(JThrowable::from(error))
+        } else {
+            Err(Error::WrongJValueType("java.lang.Throwable", "object"))
+        }
+    }
+}
+
+impl<'env, T, E> TryIntoJavaValue<'env> for std::result::Result<T, E>
+where
+    T: TryIntoJavaValue<'env>,
+    E: ToThrowable<'env>,
+{
+    type Target = <T as TryIntoJavaValue<'env>>::Target;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        match self {
+            Ok(s) => report_return_conversion(env, TryIntoJavaValue::try_into(s, env)),
+            Err(e) => {
+                env.throw(e.to_throwable(env)?)?;
+                Err(Error::JavaException)
+            }
+        }
+    }
+}
+
+/// A `Result<T, Infallible>` can't be `Err`, so it is converted exactly as the `Ok` type `T`.
+impl<'env, T> TryIntoJavaValue<'env> for std::result::Result<T, Infallible>
+where
+    T: TryIntoJavaValue<'env>,
+{
+    type Target = <T as TryIntoJavaValue<'env>>::Target;
+    const SIG_TYPE: &'static str = <T as TryIntoJavaValue<'env>>::SIG_TYPE;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        match self {
+            Ok(s) => TryIntoJavaValue::try_into(s, env),
+            Err(e) => match e {},
+        }
     }
 }
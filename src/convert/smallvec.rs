@@ -0,0 +1,161 @@
+//! Conversions between [`SmallVec`]s and Java arrays.
+//!
+//! `SmallVec<[T; N]>` has the same Java representation as `Box<[T]>`: primitive arrays for primitive element types
+//! and arrays of `T`'s class otherwise.
+//! Primitive elements are copied in bulk directly from and to the `SmallVec` storage, so vectors that fit inline
+//! are converted without any heap allocation on the Rust side.
+
+use ::smallvec::SmallVec;
+use jni::errors::Result;
+use jni::objects::JObject;
+use jni::sys::{
+    jbyteArray, jdoubleArray, jfloatArray, jintArray, jlongArray, jobjectArray, jshortArray,
+};
+use jni::JNIEnv;
+
+use crate::convert::{
+    ArraySignature, FromJavaValue, IntoJavaValue, JavaValue, Signature, TryFromJavaValue,
+    TryIntoJavaValue,
+};
+
+macro_rules! primitive_smallvec_types {
+    ($($type:ty: $sig:literal, $array:ty, $new_array:ident, $get_region:ident, $set_region:ident;)*) => {
+        $(
+            impl<const N: usize> Signature for SmallVec<[$type; N]> {
+                const SIG_TYPE: &'static str = $sig;
+            }
+
+            impl<'env, const N: usize> TryIntoJavaValue<'env> for SmallVec<[$type; N]> {
+                type Target = $array;
+
+                fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+                    let raw = env.$new_array(self.len() as i32)?;
+                    env.$set_region(raw, 0, &self)?;
+                    Ok(raw)
+                }
+            }
+
+            impl<'env: 'borrow, 'borrow, const N: usize> TryFromJavaValue<'env, 'borrow> for SmallVec<[$type; N]> {
+                type Source = $array;
+
+                fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+                    let len = env.get_array_length(s)?;
+                    let mut buf = SmallVec::from_elem(Default::default(), len as usize);
+                    env.$get_region(s, 0, &mut buf)?;
+                    Ok(buf)
+                }
+            }
+
+            impl<'env, const N: usize> IntoJavaValue<'env> for SmallVec<[$type; N]> {
+                type Target = $array;
+
+                fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+                    TryIntoJavaValue::try_into(self, env).unwrap()
+                }
+            }
+
+            impl<'env: 'borrow, 'borrow, const N: usize> FromJavaValue<'env, 'borrow> for SmallVec<[$type; N]> {
+                type Source = $array;
+
+                fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+                    TryFromJavaValue::try_from(s, env).unwrap()
+                }
+            }
+        )*
+    };
+}
+
+primitive_smallvec_types! {
+    i8: "[B", jbyteArray, new_byte_array, get_byte_array_region, set_byte_array_region;
+    i16: "[S", jshortArray, new_short_array, get_short_array_region, set_short_array_region;
+    i32: "[I", jintArray, new_int_array, get_int_array_region, set_int_array_region;
+    i64: "[J", jlongArray, new_long_array, get_long_array_region, set_long_array_region;
+    f32: "[F", jfloatArray, new_float_array, get_float_array_region, set_float_array_region;
+    f64: "[D", jdoubleArray, new_double_array, get_double_array_region, set_double_array_region;
+}
+
+impl<T: ArraySignature, const N: usize> Signature for SmallVec<[T; N]> {
+    const SIG_TYPE: &'static str = <T as ArraySignature>::ARRAY_SIG_TYPE;
+}
+
+impl<'env, T, const N: usize> TryIntoJavaValue<'env> for SmallVec<[T; N]>
+where
+    T: ArraySignature + TryIntoJavaValue<'env>,
+{
+    type Target = jobjectArray;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let element_sig = <T as Signature>::SIG_TYPE;
+        let element_class = env.find_class(&element_sig[1..element_sig.len() - 1])?;
+        let raw = env.new_object_array(self.len() as i32, element_class, JObject::null())?;
+
+        for (i, el) in self.into_iter().enumerate() {
+            let el = JavaValue::autobox(TryIntoJavaValue::try_into(el, env)?, env);
+            env.set_object_array_element(raw, i as i32, el)?;
+        }
+
+        Ok(raw)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U, const N: usize> TryFromJavaValue<'env, 'borrow>
+    for SmallVec<[T; N]>
+where
+    T: ArraySignature + TryFromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = jobjectArray;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        let len = env.get_array_length(s)?;
+
+        (0..len)
+            .map(|i| T::try_from(U::unbox(env.get_object_array_element(s, i)?, env), env))
+            .collect()
+    }
+}
+
+impl<'env, T, const N: usize> IntoJavaValue<'env> for SmallVec<[T; N]>
+where
+    T: ArraySignature + IntoJavaValue<'env>,
+{
+    type Target = jobjectArray;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        let element_sig = <T as Signature>::SIG_TYPE;
+        let element_class = env
+            .find_class(&element_sig[1..element_sig.len() - 1])
+            .unwrap();
+        let raw = env
+            .new_object_array(self.len() as i32, element_class, JObject::null())
+            .unwrap();
+
+        self.into_iter()
+            .map(|el| JavaValue::autobox(IntoJavaValue::into(el, env), env))
+            .enumerate()
+            .for_each(|(i, el)| env.set_object_array_element(raw, i as i32, el).unwrap());
+
+        raw
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U, const N: usize> FromJavaValue<'env, 'borrow> for SmallVec<[T; N]>
+where
+    T: ArraySignature + FromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = jobjectArray;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        let len = env.get_array_length(s).unwrap();
+
+        (0..len)
+            .map(|i| {
+                T::from(
+                    U::unbox(env.get_object_array_element(s, i).unwrap(), env),
+                    env,
+                )
+            })
+            .collect()
+    }
+}
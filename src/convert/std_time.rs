@@ -0,0 +1,166 @@
+//! Conversions between [`std::time`] types and `java.time` types.
+//!
+//! A [`Duration`] is converted to a `java.time.Duration` and a [`SystemTime`] to a `java.time.Instant`, both with nanosecond precision.
+//!
+//! The ranges of the two sides don't match: a `Duration` can be longer than the `Long.MAX_VALUE` seconds of a `java.time.Duration`,
+//! which in turn can be negative, and a `SystemTime` can be outside of `[Instant.MIN, Instant.MAX]` (or the other way around,
+//! depending on the platform). Out of range values fail to convert with an error instead of wrapping around.
+
+use std::convert::TryFrom;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use jni::errors::{Error, Result};
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+
+use crate::convert::{FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+/// Epoch second of `java.time.Instant.MIN`.
+const INSTANT_MIN_SECONDS: i64 = -31557014167219200;
+/// Epoch second of `java.time.Instant.MAX`.
+const INSTANT_MAX_SECONDS: i64 = 31556889864403199;
+
+/// Read the seconds and nanoseconds of a `java.time.Duration` or `java.time.Instant`.
+fn seconds_and_nanos(
+    env: &JNIEnv,
+    s: JObject,
+    seconds_method: &'static str,
+    nanos_method: &'static str,
+) -> Result<(i64, u32)> {
+    let seconds = env.call_method(s, seconds_method, "()J", &[])?.j()?;
+    let nanos = env.call_method(s, nanos_method, "()I", &[])?.i()?;
+
+    Ok((seconds, nanos as u32))
+}
+
+impl Signature for Duration {
+    const SIG_TYPE: &'static str = "Ljava/time/Duration;";
+}
+
+impl<'env> TryIntoJavaValue<'env> for Duration {
+    type Target = JObject<'env>;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let seconds: i64 = TryFrom::try_from(self.as_secs())
+            .map_err(|_| Error::WrongJValueType("java.time.Duration", "out of range Duration"))?;
+
+        env.call_static_method(
+            "java/time/Duration",
+            "ofSeconds",
+            "(JJ)Ljava/time/Duration;",
+            &[
+                JValue::Long(seconds),
+                JValue::Long(self.subsec_nanos() as i64),
+            ],
+        )?
+        .l()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for Duration {
+    type Source = JObject<'env>;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        let (seconds, nanos) = seconds_and_nanos(env, s, "getSeconds", "getNano")?;
+        let seconds: u64 = TryFrom::try_from(seconds)
+            .map_err(|_| Error::WrongJValueType("Duration", "negative java.time.Duration"))?;
+
+        Ok(Duration::new(seconds, nanos))
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for Duration {
+    type Target = JObject<'env>;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Duration {
+    type Source = JObject<'env>;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
+
+impl Signature for SystemTime {
+    const SIG_TYPE: &'static str = "Ljava/time/Instant;";
+}
+
+impl<'env> TryIntoJavaValue<'env> for SystemTime {
+    type Target = JObject<'env>;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let out_of_range =
+            || Error::WrongJValueType("java.time.Instant", "out of range SystemTime");
+
+        /* Instants before the epoch have a negative second and a positive nanosecond adjustment */
+        let (seconds, nanos) = match self.duration_since(UNIX_EPOCH) {
+            Ok(after) => {
+                let seconds: i64 =
+                    TryFrom::try_from(after.as_secs()).map_err(|_| out_of_range())?;
+                (seconds, after.subsec_nanos())
+            }
+            Err(e) => {
+                let before = e.duration();
+                let seconds: i64 =
+                    TryFrom::try_from(before.as_secs()).map_err(|_| out_of_range())?;
+                match before.subsec_nanos() {
+                    0 => (-seconds, 0),
+                    nanos => (-seconds - 1, 1_000_000_000 - nanos),
+                }
+            }
+        };
+
+        if !(INSTANT_MIN_SECONDS..=INSTANT_MAX_SECONDS).contains(&seconds) {
+            return Err(out_of_range());
+        }
+
+        env.call_static_method(
+            "java/time/Instant",
+            "ofEpochSecond",
+            "(JJ)Ljava/time/Instant;",
+            &[JValue::Long(seconds), JValue::Long(nanos as i64)],
+        )?
+        .l()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for SystemTime {
+    type Source = JObject<'env>;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        let (seconds, nanos) = seconds_and_nanos(env, s, "getEpochSecond", "getNano")?;
+
+        let time = if seconds >= 0 {
+            UNIX_EPOCH.checked_add(Duration::new(seconds as u64, nanos))
+        } else {
+            UNIX_EPOCH
+                .checked_sub(Duration::from_secs(seconds.unsigned_abs()))
+                .and_then(|t| t.checked_add(Duration::from_nanos(nanos as u64)))
+        };
+
+        time.ok_or(Error::WrongJValueType(
+            "SystemTime",
+            "out of range java.time.Instant",
+        ))
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for SystemTime {
+    type Target = JObject<'env>;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for SystemTime {
+    type Source = JObject<'env>;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
@@ -0,0 +1,67 @@
+//! Conversions between `chrono` date times and `java.time.Instant`.
+//!
+//! Java `Instant`s are always in UTC: when converting from Rust, date times in any time zone are normalized to UTC first,
+//! while conversions from Java always produce a [`DateTime<Utc>`].
+//!
+//! Values are converted with nanosecond precision using `Instant.ofEpochSecond(long, long)`, `Instant.getEpochSecond()` and `Instant.getNano()`.
+
+use ::chrono::{DateTime, TimeZone, Utc};
+use jni::errors::{Error, Result};
+use jni::objects::{JObject, JValue};
+use jni::JNIEnv;
+
+use crate::convert::{
+    FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue,
+};
+
+impl<Tz: TimeZone> Signature for DateTime<Tz> {
+    const SIG_TYPE: &'static str = "Ljava/time/Instant;";
+}
+
+impl<'env, Tz: TimeZone> TryIntoJavaValue<'env> for DateTime<Tz> {
+    type Target = JObject<'env>;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        let utc = self.with_timezone(&Utc);
+
+        env.call_static_method(
+            "java/time/Instant",
+            "ofEpochSecond",
+            "(JJ)Ljava/time/Instant;",
+            &[
+                JValue::Long(utc.timestamp()),
+                JValue::Long(utc.timestamp_subsec_nanos() as i64),
+            ],
+        )?
+        .l()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValue<'env, 'borrow> for DateTime<Utc> {
+    type Source = JObject<'env>;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        let seconds = env.call_method(s, "getEpochSecond", "()J", &[])?.j()?;
+        let nanos = env.call_method(s, "getNano", "()I", &[])?.i()?;
+
+        Utc.timestamp_opt(seconds, nanos as u32)
+            .single()
+            .ok_or(Error::WrongJValueType("DateTime<Utc>", "java.time.Instant"))
+    }
+}
+
+impl<'env, Tz: TimeZone> IntoJavaValue<'env> for DateTime<Tz> {
+    type Target = JObject<'env>;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for DateTime<Utc> {
+    type Source = JObject<'env>;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
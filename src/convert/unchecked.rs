@@ -11,11 +11,23 @@
 //! **These functions *will* panic should any conversion fail.**
 //!
 
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::ffi::{CStr, CString};
+use std::fmt::Arguments;
+use std::num::Wrapping;
+use std::ops::{ControlFlow, Range, RangeInclusive};
+use std::process::ExitStatus;
+
 use jni::objects::{JList, JObject, JString, JValue};
-use jni::sys::{jboolean, jbooleanArray, jchar, jobject, jstring};
+use jni::sys::{
+    jboolean, jbooleanArray, jchar, jcharArray, jint, jintArray, jobject, jobjectArray, jstring,
+};
 use jni::JNIEnv;
 
-use crate::convert::{JavaValue, Signature};
+use crate::convert::{
+    boxed_signature, ArraySignature, JavaValue, Signature, TryFromJavaValue, TryIntoJavaValue,
+};
 
 pub use robusta_codegen::{FromJavaValue, IntoJavaValue};
 
@@ -30,6 +42,9 @@ pub use robusta_codegen::{FromJavaValue, IntoJavaValue};
 ///
 /// The same notes on [`TryIntoJavaValue`] apply.
 ///
+/// With a `#[std_convert]` attribute on the struct, [`From<T>`](From) and `From<&T>` are implemented for [`JObject<'env>`](jni::objects::JObject)
+/// as well, returning the instance object (the owned conversion keeps its local reference instead of deleting it).
+///
 /// [`TryIntoJavaValue`]: crate::convert::TryIntoJavaValue
 ///
 pub trait IntoJavaValue<'env>: Signature {
@@ -106,6 +121,62 @@ impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for String {
     }
 }
 
+impl Signature for &str {
+    const SIG_TYPE: &'static str = <String as Signature>::SIG_TYPE;
+}
+
+impl<'env> IntoJavaValue<'env> for Option<&str> {
+    type Target = JObject<'env>;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl Signature for Arguments<'_> {
+    const SIG_TYPE: &'static str = <String as Signature>::SIG_TYPE;
+}
+
+impl<'env> IntoJavaValue<'env> for Arguments<'_> {
+    type Target = jstring;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap().into_raw()
+    }
+}
+
+impl Signature for CString {
+    const SIG_TYPE: &'static str = <String as Signature>::SIG_TYPE;
+}
+
+impl<'env> IntoJavaValue<'env> for CString {
+    type Target = jstring;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap().into_raw()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for CString {
+    type Source = JString<'env>;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
+
+impl Signature for &CStr {
+    const SIG_TYPE: &'static str = <String as Signature>::SIG_TYPE;
+}
+
+impl<'env> IntoJavaValue<'env> for &CStr {
+    type Target = jstring;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap().into_raw()
+    }
+}
+
 impl<'env> IntoJavaValue<'env> for bool {
     type Target = jboolean;
 
@@ -177,6 +248,46 @@ impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Box<[bool]> {
     }
 }
 
+impl<'env> IntoJavaValue<'env> for Box<[char]> {
+    type Target = jcharArray;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Box<[char]> {
+    type Source = jcharArray;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
+
+macro_rules! primitive_slice_types {
+    ($($type:ty),*) => {
+        $(
+            impl<'env> IntoJavaValue<'env> for Box<[$type]> {
+                type Target = <Self as TryIntoJavaValue<'env>>::Target;
+
+                fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+                    TryIntoJavaValue::try_into(self, env).unwrap()
+                }
+            }
+
+            impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Box<[$type]> {
+                type Source = <Self as TryFromJavaValue<'env, 'borrow>>::Source;
+
+                fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+                    TryFromJavaValue::try_from(s, env).unwrap()
+                }
+            }
+        )*
+    };
+}
+
+primitive_slice_types!(i8, i16, i32, i64, f32, f64);
+
 impl<T> Signature for Vec<T> {
     const SIG_TYPE: &'static str = "Ljava/util/ArrayList;";
 }
@@ -224,6 +335,72 @@ where
     }
 }
 
+impl<'env, T> IntoJavaValue<'env> for Box<[T]>
+where
+    T: ArraySignature + IntoJavaValue<'env>,
+{
+    type Target = jobjectArray;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        let element_sig = <T as Signature>::SIG_TYPE;
+        let element_class = env
+            .find_class(&element_sig[1..element_sig.len() - 1])
+            .unwrap();
+        let raw = env
+            .new_object_array(self.len() as i32, element_class, JObject::null())
+            .unwrap();
+
+        self.into_vec()
+            .into_iter()
+            .map(|el| JavaValue::autobox(IntoJavaValue::into(el, env), env))
+            .enumerate()
+            .for_each(|(i, el)| env.set_object_array_element(raw, i as i32, el).unwrap());
+
+        raw
+    }
+}
+
+impl<'env, 'a, T> IntoJavaValue<'env> for &'a [T]
+where
+    T: ArraySignature,
+    &'a T: IntoJavaValue<'env>,
+{
+    type Target = JObject<'env>;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        let element_sig = <T as Signature>::SIG_TYPE;
+        let element_class = env
+            .find_class(&element_sig[1..element_sig.len() - 1])
+            .unwrap();
+        let raw = env
+            .new_object_array(self.len() as i32, element_class, JObject::null())
+            .unwrap();
+
+        self.iter()
+            .map(|el| JavaValue::autobox(IntoJavaValue::into(el, env), env))
+            .enumerate()
+            .for_each(|(i, el)| env.set_object_array_element(raw, i as i32, el).unwrap());
+
+        JavaValue::autobox(raw, env)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U> FromJavaValue<'env, 'borrow> for Box<[T]>
+where
+    T: ArraySignature + FromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = jobjectArray;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        let len = env.get_array_length(s).unwrap();
+
+        (0..len)
+            .map(|i| T::from(U::unbox(env.get_object_array_element(s, i).unwrap(), env), env))
+            .collect()
+    }
+}
+
 impl<'env, T> IntoJavaValue<'env> for jni::errors::Result<T>
 where
     T: IntoJavaValue<'env>,
@@ -234,3 +411,155 @@ where
         self.map(|s| IntoJavaValue::into(s, env)).unwrap()
     }
 }
+
+impl<'env, T> IntoJavaValue<'env> for Option<T>
+where
+    T: IntoJavaValue<'env>,
+{
+    type Target = JObject<'env>;
+    const SIG_TYPE: &'static str = boxed_signature(<T as IntoJavaValue<'env>>::SIG_TYPE);
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        match self {
+            Some(v) => JavaValue::autobox(IntoJavaValue::into(v, env), env),
+            None => JObject::null(),
+        }
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T, U> FromJavaValue<'env, 'borrow> for Option<T>
+where
+    T: FromJavaValue<'env, 'borrow, Source = U>,
+    U: JavaValue<'env>,
+{
+    type Source = JObject<'env>;
+    const SIG_TYPE: &'static str = boxed_signature(<T as FromJavaValue<'env, 'borrow>>::SIG_TYPE);
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        if s.is_null() {
+            None
+        } else {
+            Some(T::from(U::unbox(s, env), env))
+        }
+    }
+}
+
+impl<'env, T> IntoJavaValue<'env> for Wrapping<T>
+where
+    T: IntoJavaValue<'env>,
+{
+    type Target = <T as IntoJavaValue<'env>>::Target;
+    const SIG_TYPE: &'static str = <T as IntoJavaValue<'env>>::SIG_TYPE;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        IntoJavaValue::into(self.0, env)
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T> FromJavaValue<'env, 'borrow> for Wrapping<T>
+where
+    T: FromJavaValue<'env, 'borrow>,
+{
+    type Source = <T as FromJavaValue<'env, 'borrow>>::Source;
+    const SIG_TYPE: &'static str = <T as FromJavaValue<'env, 'borrow>>::SIG_TYPE;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        Wrapping(T::from(s, env))
+    }
+}
+
+/// A `Result<T, Infallible>` can't be `Err`, so it is converted exactly as the `Ok` type `T`.
+impl<'env, T> IntoJavaValue<'env> for std::result::Result<T, Infallible>
+where
+    T: IntoJavaValue<'env>,
+{
+    type Target = <T as IntoJavaValue<'env>>::Target;
+    const SIG_TYPE: &'static str = <T as IntoJavaValue<'env>>::SIG_TYPE;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        match self {
+            Ok(s) => IntoJavaValue::into(s, env),
+            Err(e) => match e {},
+        }
+    }
+}
+
+impl<'env, 'a, B> IntoJavaValue<'env> for Cow<'a, B>
+where
+    B: ToOwned + ?Sized,
+    &'a B: IntoJavaValue<'env>,
+    <B as ToOwned>::Owned: IntoJavaValue<'env, Target = <&'a B as IntoJavaValue<'env>>::Target>,
+{
+    type Target = <&'a B as IntoJavaValue<'env>>::Target;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        match self {
+            Cow::Borrowed(b) => IntoJavaValue::into(b, env),
+            Cow::Owned(o) => IntoJavaValue::into(o, env),
+        }
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for Range<i32> {
+    type Target = jintArray;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for Range<i32> {
+    type Source = jintArray;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for ExitStatus {
+    type Target = jint;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env> IntoJavaValue<'env> for RangeInclusive<i32> {
+    type Target = jintArray;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow> FromJavaValue<'env, 'borrow> for RangeInclusive<i32> {
+    type Source = jintArray;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
+
+impl<'env, B, C> IntoJavaValue<'env> for ControlFlow<B, C>
+where
+    B: TryIntoJavaValue<'env>,
+    C: TryIntoJavaValue<'env>,
+{
+    type Target = jobjectArray;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow, B, C> FromJavaValue<'env, 'borrow> for ControlFlow<B, C>
+where
+    B: TryFromJavaValue<'env, 'borrow>,
+    C: TryFromJavaValue<'env, 'borrow>,
+{
+    type Source = jobjectArray;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
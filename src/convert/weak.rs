@@ -0,0 +1,125 @@
+//! Weak global references.
+//!
+//! A [`WeakGlobal`] holds a Java object without preventing it from being garbage collected, which makes it suitable
+//! for caches living across JNI calls. The referent must be upgraded to a local reference with [`WeakGlobal::upgrade`]
+//! before being used.
+
+use std::marker::PhantomData;
+
+use jni::errors::{Error, Result};
+use jni::objects::{AutoLocal, JObject};
+use jni::sys::jweak;
+use jni::{JNIEnv, JavaVM};
+
+use crate::convert::{FromJavaValue, IntoJavaValue, Signature, TryFromJavaValue, TryIntoJavaValue};
+
+/// A weak global reference to a Java object, typed with the [signature](Signature) of `T`.
+///
+/// The reference is created with `NewWeakGlobalRef` and deleted with `DeleteWeakGlobalRef` when dropped,
+/// attaching the current thread to the JVM if needed.
+///
+/// When converted from Java, the input object is demoted to a weak reference.
+/// When converted to Java, the referent is promoted to a local reference, or passed as `null` if it has been collected.
+pub struct WeakGlobal<T> {
+    vm: JavaVM,
+    obj: jweak,
+    marker: PhantomData<T>,
+}
+
+// Weak global references are valid in every thread, as `GlobalRef`s are.
+unsafe impl<T> Send for WeakGlobal<T> {}
+unsafe impl<T> Sync for WeakGlobal<T> {}
+
+impl<T> WeakGlobal<T> {
+    /// Create a new weak global reference to `obj`, which must not be `null`.
+    pub fn new(env: &JNIEnv, obj: JObject) -> Result<Self> {
+        let internal = env.get_native_interface();
+        let new_weak_global_ref = unsafe { (**internal).NewWeakGlobalRef }
+            .ok_or(Error::JNIEnvMethodNotFound("NewWeakGlobalRef"))?;
+        let weak = unsafe { new_weak_global_ref(internal, obj.into_raw()) };
+
+        if weak.is_null() {
+            return Err(Error::NullPtr("NewWeakGlobalRef result"));
+        }
+
+        Ok(WeakGlobal {
+            vm: env.get_java_vm()?,
+            obj: weak,
+            marker: PhantomData,
+        })
+    }
+
+    /// Get a local reference to the referent, or `None` if it has been garbage collected.
+    pub fn upgrade<'env: 'b, 'b>(
+        &self,
+        env: &'b JNIEnv<'env>,
+    ) -> Result<Option<AutoLocal<'env, 'b>>> {
+        let local = env.new_local_ref::<JObject>(unsafe { JObject::from_raw(self.obj) })?;
+
+        if local.is_null() {
+            Ok(None)
+        } else {
+            Ok(Some(AutoLocal::new(env, local)))
+        }
+    }
+}
+
+impl<T> Drop for WeakGlobal<T> {
+    fn drop(&mut self) {
+        fn delete(env: &JNIEnv, obj: jweak) {
+            let internal = env.get_native_interface();
+            // This method is safe to call in case of pending exceptions (see chapter 2 of the spec)
+            if let Some(delete_weak_global_ref) = unsafe { (**internal).DeleteWeakGlobalRef } {
+                unsafe { delete_weak_global_ref(internal, obj) }
+            }
+        }
+
+        match self.vm.get_env() {
+            Ok(env) => delete(&env, self.obj),
+            Err(_) => {
+                if let Ok(env) = self.vm.attach_current_thread() {
+                    delete(&env, self.obj)
+                }
+            }
+        }
+    }
+}
+
+impl<T: Signature> Signature for WeakGlobal<T> {
+    const SIG_TYPE: &'static str = <T as Signature>::SIG_TYPE;
+}
+
+impl<'env, T: Signature> TryIntoJavaValue<'env> for WeakGlobal<T> {
+    type Target = JObject<'env>;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> Result<Self::Target> {
+        Ok(self
+            .upgrade(env)?
+            .map(AutoLocal::forget)
+            .unwrap_or_else(JObject::null))
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T: Signature> TryFromJavaValue<'env, 'borrow> for WeakGlobal<T> {
+    type Source = JObject<'env>;
+
+    fn try_from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Result<Self> {
+        WeakGlobal::new(env, s)
+    }
+}
+
+impl<'env, T: Signature> IntoJavaValue<'env> for WeakGlobal<T> {
+    type Target = JObject<'env>;
+
+    fn into(self, env: &JNIEnv<'env>) -> Self::Target {
+        TryIntoJavaValue::try_into(self, env).unwrap()
+    }
+}
+
+impl<'env: 'borrow, 'borrow, T: Signature> FromJavaValue<'env, 'borrow> for WeakGlobal<T> {
+    type Source = JObject<'env>;
+
+    fn from(s: Self::Source, env: &'borrow JNIEnv<'env>) -> Self {
+        TryFromJavaValue::try_from(s, env).unwrap()
+    }
+}
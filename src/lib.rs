@@ -13,7 +13,35 @@
 //!
 //! # Declaring classes
 //! Rust counterparts of Java classes are declared as Rust `struct`s, with a `#[package(my.package.name)]` attribute.
-//! When using the default package, just omit the package name inside parentheses.
+//! When using the default package, just omit the package name: `#[package]`, `#[package()]` and `#[package("")]` are all accepted.
+//! The package name can also be given as a string literal, e.g. `#[package("my.package.name")]`.
+//!
+//! Nested classes are declared with their outer class followed by `$`: `#[package(my.package.Outer$)] struct Inner;` binds to `my.package.Outer$Inner`.
+//! This also covers Kotlin companion objects (e.g. `#[package(com.example.Foo$)] struct Companion;`), while Kotlin file classes such as `FooKt` are plain class names.
+//!
+//! Generic Java classes are declared with the type parameters of your choice, e.g. to keep track of the element type of a list.
+//! Java generics are erased at runtime, so the descriptors of such structs only name the class, whatever the type arguments:
+//!
+//! ```rust
+//! use robusta_jni::bridge;
+//! use robusta_jni::convert::Signature;
+//!
+//! #[bridge]
+//! mod jni {
+//!     # use std::marker::PhantomData;
+//!     # use robusta_jni::convert::{Signature, TryFromJavaValue};
+//!     # use robusta_jni::jni::objects::AutoLocal;
+//!     #[derive(Signature, TryFromJavaValue)]
+//!     #[package(java.util)]
+//!     pub struct List<'env: 'borrow, 'borrow, E> {
+//!         #[instance]
+//!         raw: AutoLocal<'env, 'borrow>,
+//!         element: PhantomData<E>, // no Java counterpart, skipped by conversions
+//!     }
+//! }
+//!
+//! assert_eq!(<jni::List<String> as Signature>::SIG_TYPE, "Ljava/util/List;");
+//! ```
 //!
 //! Structs without the package attribute will be ignored by `robusta_jni`.
 //!
@@ -28,7 +56,7 @@
 //! #[bridge]
 //! mod jni {
 //!     # use robusta_jni::convert::Signature;
-//!     #[package()] // default package
+//!     #[package] // default package
 //!     struct A;
 //!
 //!     impl Signature for A {
@@ -38,6 +66,56 @@
 //!     #[derive(Signature)]
 //!     #[package(my.awesome.package)]
 //!     struct B;
+//!
+//!     #[derive(Signature)]
+//!     #[package(my.awesome.package.B$)] // nested class `my.awesome.package.B$C`
+//!     struct C;
+//! }
+//! ```
+//!
+//! Struct names must be unique within a `#[bridge]` module, even when declared in different nested modules:
+//!
+//! ```compile_fail
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge]
+//! mod jni {
+//!     mod a {
+//!         #[package(com.example.a)]
+//!         pub struct Foo;
+//!
+//!         impl Foo {
+//!             pub extern "jni" fn op() -> i32 { 0 }
+//!         }
+//!     }
+//!
+//!     mod b {
+//!         #[package(com.example.b)]
+//!         pub struct Foo; // error: struct `Foo` is bridged more than once
+//!
+//!         impl Foo {
+//!             pub extern "jni" fn op() -> i32 { 1 }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! A struct can also serve the native methods of other Java classes, e.g. while migrating from one class to another.
+//! Each `#[class_alias(my.package.Class)]` attribute takes the fully qualified name of a class, for which the native methods
+//! of the struct are exported as well. Imported methods and conversions keep using the class given by `#[package]`.
+//!
+//! ```rust
+//! use robusta_jni::bridge;
+//!
+//! #[bridge]
+//! mod jni {
+//!     #[package(com.example)]
+//!     #[class_alias(com.example.FooV2)] // `FooV2.answer()` is bound to the same function as `FooV1.answer()`
+//!     struct FooV1;
+//!
+//!     impl FooV1 {
+//!         pub extern "jni" fn answer() -> i32 { 42 }
+//!     }
 //! }
 //! ```
 //!
@@ -51,9 +129,93 @@
 //!
 //! Native methods can optionally accept a [`JNIEnv`] parameter as first parameter (after `self` if present).
 //!
-//! Methods are declared as standard Rust functions with public visibility and "jni" ABI, and are matched by name with Java methods.
+//! Parameters can also be taken by shared reference, like `&self` or `other: &Self`: the Java value is converted to the referenced type
+//! as usual, and the method borrows the converted value, which lives until the method returns.
+//!
+//! Associated functions without a `self` receiver are exported as Java `static native` methods: the generated function receives the calling
+//! class as a [`JClass`](jni::objects::JClass) after the environment, as required by JNI. Pure functions don't need to declare a [`JNIEnv`] parameter.
+//!
+//! `Self` can be used in parameter and return types, and stands for the bridged struct: a builder-style method taking `self` and
+//! returning `Self` (or `Result<Self>`) converts the receiver back to its Java object, so that calls can be chained from Java.
+//!
+//! The receiver is converted with the `(Try)FromJavaValue` implementation of the struct by default (`#[receiver(pojo)]`).
+//! Structs whose Rust value is owned by the Java object can use `#[receiver(native_pointer = "handle")]` instead:
+//! the value is boxed with [`convert::into_native_pointer`] and its address stored in the `long handle` field,
+//! `&self` and `&mut self` methods borrow it and `self` methods take it back, resetting the field to `0`.
+//! With `#[receiver(with = path::to::function)]` the receiver is built by a `fn(&JNIEnv<'env>, JObject<'env>) -> jni::errors::Result<Self>`.
+//!
+//! Java objects can be used from any thread, so the Rust values they own (e.g. through a native pointer) may have to be `Send`.
+//! `#[assert_send]` on a struct checks it at compile time:
+//!
+//! ```compile_fail
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge]
+//! mod jni {
+//!     # use std::rc::Rc;
+//!     #[package(com.example)]
+//!     #[assert_send] // error: `Rc<String>` cannot be sent between threads safely
+//!     pub struct Cache {
+//!         last: Rc<String>,
+//!     }
+//!
+//!     impl Cache {
+//!         pub extern "jni" fn size() -> i32 { 0 }
+//!     }
+//! }
+//! ```
+//!
+//! Structs marked with `#[dispatcher]` additionally export a `public static native Object invoke(String method, Object[] args)` method,
+//! for frameworks dispatching calls by name. It calls the exported method named `method` taking as many parameters as there are `args`,
+//! the receiver of `self` methods being the first one, with primitives boxed (e.g. an `Integer` for an `i32`) both in `args` and in the returned value.
+//! `void` methods return `null`, and unknown methods or mismatched arguments throw a [`DISPATCH_EXCEPTION_CLASS`](convert::DISPATCH_EXCEPTION_CLASS).
+//!
+//! Native methods can also be `unsafe fn`s: the generated JNI function keeps the `unsafe` qualifier and calls your function from an `unsafe` block.
+//!
+//! Methods on hot paths can be marked with `#[hot]`: the method and the conversion glue generated for it are then marked `#[inline(always)]`.
+//!
+//! Methods creating many local references (e.g. while building a large collection) can be marked with `#[local_frame(capacity = N)]`:
+//! the method then runs in a local reference frame with room for `N` references, which are deleted when it returns,
+//! except for the returned object. See [`LocalFrameValue`](convert::LocalFrameValue).
+//!
+//! Methods are declared as standard Rust functions with "jni" ABI, and are matched by name with Java methods.
 //! No special handling is needed.
 //!
+//! The Rust visibility of exported methods only applies to the Rust side: the generated JNI functions are always exported,
+//! so private methods are callable from Java too.
+//!
+//! Libraries loaded in the same JVM that bind classes with the same name can namespace their symbols with the `symbol_prefix`
+//! and `symbol_suffix` options of `#[bridge]`: with `#[bridge(symbol_prefix = "libA_")]` the `op` method is bound to the
+//! `libA_op` Java native method, which must be declared with that name on the Java side (e.g. `native int libA_op(boolean flag);`).
+//!
+//! A `null` object argument of a `#[call_type(safe)]` method is reported as an argument conversion error by default.
+//! The `null_policy` option of `#[bridge]` changes this for the whole module: with `#[bridge(null_policy = "panic")]` such methods
+//! panic instead, and with `#[bridge(null_policy = "default")]` the argument is replaced with `Default::default()`.
+//! Arguments of type `Option<T>` are not affected, as they convert `null` to `None`.
+//!
+//! Since exported methods are named after Java methods, the `non_snake_case` lint is allowed in the whole bridge module.
+//! `#[bridge(no_default_allows)]` leaves it to the lint levels of your crate, for example to keep warnings for the rest of the module
+//! (the generated JNI functions are never linted, as they are `#[no_mangle]`):
+//!
+//! ```compile_fail
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge(no_default_allows)]
+//! #[deny(non_snake_case)]
+//! mod jni {
+//!     #[package(com.example)]
+//!     pub struct Foo;
+//!
+//!     impl Foo {
+//!         pub extern "jni" fn camelCase() -> i32 { 0 } // error: method `camelCase` should have a snake case name
+//!     }
+//! }
+//! ```
+//!
+//! With `#[bridge(descriptors)]` the module gets a `bridge_methods()` function listing its exported and imported methods
+//! with their computed descriptors, which [`classfile::validate`] checks against the compiled Java classes
+//! (e.g. in a test of the crate), catching drift between the Java declarations and their Rust counterparts before runtime.
+//!
 //! Example:
 //!
 //! ```rust
@@ -96,6 +258,46 @@
 //! # }
 //! ```
 //!
+//! Structs with lifetime parameters must name one of them `'env` (the lifetime of the [`JNIEnv`]) to support exported `self` methods:
+//!
+//! ```compile_fail
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge]
+//! mod jni {
+//!     # use robusta_jni::convert::{Signature, TryFromJavaValue};
+//!     # use robusta_jni::jni::objects::AutoLocal;
+//!     #[derive(Signature, TryFromJavaValue)]
+//!     #[package()]
+//!     struct A<'a: 'b, 'b> { // error: struct `A` must declare an `'env` lifetime to support exported `self` methods
+//!         #[instance]
+//!         raw: AutoLocal<'a, 'b>,
+//!     }
+//!
+//!     impl<'a: 'b, 'b> A<'a, 'b> {
+//!         pub extern "jni" fn op(self) -> i32 { 0 }
+//!     }
+//! }
+//! ```
+//!
+//! Native methods must be synchronous, `async fn`s can't be exported:
+//!
+//! ```compile_fail
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge]
+//! mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     #[derive(Signature)]
+//!     #[package()]
+//!     struct A;
+//!
+//!     impl A {
+//!         pub async extern "jni" fn op() -> i32 { 0 } // error: JNI methods must be synchronous, `async fn` can't be exported
+//!     }
+//! }
+//! ```
+//!
 //! # Adding Java methods
 //! You can also declare Java methods and `robusta` will generate binding glue to convert types and call methods on the Java side.
 //! Again, **all input and output types must implement proper conversion traits**: in this case it's the reverse from the Java to Rust case
@@ -106,11 +308,25 @@
 //!
 //! Constructors can be declared via a `#[constructor]` attribute on static methods, and are matched by their type signature.
 //!
+//! Classes of static methods and constructors are looked up with `FindClass`, which can't see application classes from native threads.
+//! With `#[bridge(class_loader)]` they are looked up with the class loader registered with [`class_loader::register_class_loader`] instead,
+//! e.g. calling `register_class_loader(&env, "com/example/Foo")` in `JNI_OnLoad` or in an initialization native method.
+//! Structs marked with `#[module_loader]` look up these classes with the loader given at runtime to
+//! [`class_loader::register_module_loader`] instead, e.g. the class loader of a Java module layer: see [`class_loader`] for the setup.
+//!
 //! When using `#[call_type(safe)]` or omitting `call_type` attribute, the output type **must** be [`jni::errors::Result<T>`](jni::errors::Result)
 //! with `T` being the actual method return type. Otherwise when using `#[call_type(unchecked)]` `T` is sufficient.
+//! Java `void` methods return `Result<()>`, whose `Err` holds the exception thrown by the method (still pending in the `JNIEnv`),
+//! or nothing at all with `#[call_type(unchecked)]`.
+//!
+//! Methods that may return `null` can use `Result<Option<T>>`. `Option<Result<T>>` is accepted as well: a thrown exception is checked first
+//! and yields `Some(Err(_))`, then a `null` return value yields `None`.
 //!
 //! **When using `#[call_type(unchecked)]` if a Java exception is thrown while calling a method a panic is raised.**
 //!
+//! With `#[bridge(must_use)]`, imported methods returning a value (anything but `()` and `Result<()>`) are marked `#[must_use]`,
+//! so that ignoring their result, e.g. the object returned by a builder method, is reported by the compiler.
+//!
 //! ## Static methods
 //!
 //! Example:
@@ -188,6 +404,62 @@
 //! # }
 //! ```
 //!
+//! If the receiver can't be the first parameter, a single parameter of a method without `self` can be marked with `#[this]` instead:
+//! its value is used as the object the Java method is called on, and it is not passed as an argument.
+//!
+//! ```ignore
+//! pub extern "java" fn greet(env: &JNIEnv, greeting: String, #[this] user: &User) -> JniResult<String> {}
+//! ```
+//!
+//! ```compile_fail
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge]
+//! mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use robusta_jni::jni::JNIEnv;
+//!     #[derive(Signature)]
+//!     #[package()]
+//!     struct A;
+//!
+//!     impl A {
+//!         pub extern "java" fn op(
+//!             env: &JNIEnv,
+//!             #[this] a: &A,
+//!             #[this] b: &A, // error: only one parameter can be marked with `#[this]`
+//!         ) -> ::robusta_jni::jni::errors::Result<i32> {}
+//!     }
+//! }
+//! ```
+//!
+//! ## Explicit descriptors
+//! The method descriptor is computed from the signatures of parameter and return types. When it doesn't select the right overload,
+//! it can be given explicitly with a `#[descriptor]` attribute: conversions are still performed as usual, and the descriptor must have
+//! as many parameters as the method.
+//!
+//! ```ignore
+//! #[descriptor = "(Ljava/lang/Object;)Ljava/lang/String;"]
+//! pub extern "java" fn describe(env: &JNIEnv, s: String) -> JniResult<String> {}
+//! ```
+//!
+//! ```compile_fail
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge]
+//! mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use robusta_jni::jni::JNIEnv;
+//!     #[derive(Signature)]
+//!     #[package()]
+//!     struct A;
+//!
+//!     impl A {
+//!         #[descriptor = "(II)I"] // error: descriptor has 2 parameters, but the method has 1
+//!         pub extern "java" fn op(env: &JNIEnv, i: i32) -> ::robusta_jni::jni::errors::Result<i32> {}
+//!     }
+//! }
+//! ```
+//!
 //! ## Constructors
 //!
 //! Example:
@@ -221,6 +493,28 @@
 //! # }
 //! ```
 //!
+//! ## Static fields
+//! Static fields of the class, such as constants, are read by functions with a `#[static_field]` attribute. The function must be named
+//! like the field and only take a [`JNIEnv`] parameter, and the field descriptor is the signature of its return type.
+//!
+//! ```rust
+//! # use robusta_jni::bridge;
+//! #
+//! #[bridge]
+//! mod jni {
+//!     # use robusta_jni::convert::Signature;
+//!     # use robusta_jni::jni::JNIEnv;
+//!     #[derive(Signature)]
+//!     #[package(java.lang)]
+//!     struct Integer;
+//!
+//!     impl Integer {
+//!         #[static_field]
+//!         pub extern "java" fn MAX_VALUE(env: &JNIEnv) -> ::robusta_jni::jni::errors::Result<i32> {}
+//!     }
+//! }
+//! ```
+//!
 //! # Conversion details and special lifetimes
 //! The procedural macro handles two special lifetimes specially: `'env` and `'borrow`.
 //!
@@ -235,6 +529,27 @@
 //! When using `*FromJavaValue` derive macros your structs will be required to have both `'env` and `'borrow`,
 //! with the same bounds as in the trait definition. For more information, see the relevant traits documentation.
 //!
+//! The `JavaDebug` derive macro implements `Debug` by calling the Java `toString()` of the `#[instance]` object.
+//! It requires the JVM to be registered with [`convert::register_java_vm`], otherwise only the struct name is printed.
+//!
+//! ## Records
+//! Structs with named fields can be converted to and from Java records with the `JavaRecord` derive macro, along with `Signature`.
+//! Conversions to Java call the canonical constructor of the record class named after the struct, with the fields in declaration order,
+//! and conversions from Java read each field with the accessor of the component of the same name (in camelCase).
+//! Fields must therefore be declared in the same order as the record components:
+//!
+//! ```rust
+//! use robusta_jni::convert::{JavaRecord, Signature};
+//!
+//! // public record Measurement(String unitName, double value) {}
+//! #[derive(Signature, JavaRecord)]
+//! #[package(com.example)]
+//! pub struct Measurement {
+//!     unit_name: String,
+//!     value: f64,
+//! }
+//! ```
+//!
 //! ## Raising exceptions
 //! You can make a Rust native method raise a Java exception simply by returning a [`jni::errors::Result`] with an `Err` variant.
 //! See the [`convert`] module documentation for more information.
@@ -252,8 +567,39 @@
 //! | i64                                                                                | long                              |
 //! | i16                                                                                | short                             |
 //! | String                                                                             | String                            |
+//! | std::fmt::Arguments (output only)                                                  | String                            |
+//! | std::ffi::CString                                                                  | String                            |
+//! | &std::ffi::CStr (output only)                                                      | String                            |
 //! | Vec\<T\>†                                                                          | ArrayList\<T\>                    |
+//! | JavaList\<T\>†                                                                     | List\<T\> (ArrayList\<T\>)        |
+//! | std::collections::VecDeque\<T\>†                                                   | Deque\<T\> (ArrayDeque\<T\>)      |
+//! | std::collections::HashMap\<String, String\>                                        | java.util.Properties              |
 //! | Box<[u8]>                                                                          | byte[]                            |
+//! | CriticalByteArray (input only, pinned without copying)                             | byte[]                            |
+//! | MutableByteArray (input only, written back in place)                               | byte[]                            |
+//! | Box<[char]> (UTF-16 encoded, unpaired surrogates fail)◊                            | char[]                            |
+//! | Box<[bool]>, Box<[i8]>, Box<[i16]>, Box<[i32]>, Box<[i64]>, Box<[f32]>, Box<[f64]> | boolean[], byte[], short[], int[], long[], float[], double[] |
+//! | Box<[T]> (T deriving `Signature`)◊                                                 | T[]                               |
+//! | &[T] (output only, T deriving `Signature`)                                         | T[]                               |
+//! | Box<[Option\<T\>]> (T deriving `Signature`)                                         | T[] (with null elements)          |
+//! | Option\<T\>                                                                         | T (or null), primitives boxed     |
+//! | Option\<&str\> (output only)                                                       | String (or null for `None`)       |
+//! | Boxed\<T\> (T primitive)                                                           | Boolean, Character, Integer, ...  |
+//! | std::num::Wrapping\<T\>                                                            | T                                 |
+//! | std::borrow::Cow\<B\> (output only, &B and B::Owned must convert)                  | same as B::Owned                  |
+//! | Packed\<(i32, i32)\>                                                                | long                              |
+//! | Range\<i32\>, RangeInclusive\<i32\>                                                  | int[] (start and end bounds)      |
+//! | std::ops::ControlFlow\<B, C\>                                                      | Object[] (Boolean break, payload) |
+//! | std::process::ExitStatus (output only)                                             | int (exit code)                   |
+//! | &AtomicI8, &AtomicI16, &AtomicI32, &AtomicI64, &AtomicBool (output only)          | byte, short, int, long, boolean (current value) |
+//! | BatchResult\<T, E, C\> (output only)                                               | C (success and error lists)       |
+//! | std::time::Duration (out of range values fail)                                   | java.time.Duration                |
+//! | std::time::SystemTime (out of range values fail)                                 | java.time.Instant                 |
+//! | chrono::DateTime\<Tz\>§                                                            | java.time.Instant                 |
+//! | bytes::Bytes, bytes::BytesMut¶                                                     | byte[]                            |
+//! | smallvec::SmallVec<[T; N]>‖                                                        | same as boxed slices              |
+//! | JNull\<T\>                                                                         | null (with the signature of T)    |
+//! | WeakGlobal\<T\>                                                                    | T (held by a weak global ref)     |
 //! | [jni::JObject<'env>](jni::objects::JObject)                                      ‡ | *(any Java object as input type)* |
 //! | [jni::jobject](jni::sys::jobject)                                                    | *(any Java object as output)*     |
 //!
@@ -261,10 +607,18 @@
 //!
 //! ‡ The special `'env` lifetime **must** be used
 //!
+//! § Requires the `chrono` feature. Conversions from Java always produce a `DateTime<Utc>`
+//!
+//! ¶ Requires the `bytes` feature
+//!
+//! ‖ Requires the `smallvec` feature. Primitive arrays are copied without heap allocations when they fit inline
+//!
+//! ◊ `Vec<T>` is always converted to an `ArrayList`, arrays of structs and `char[]` are converted from and to boxed slices instead (e.g. with `Vec::into_boxed_slice`)
+//!
 //! ## Limitations
 //!
 //! Currently there are some limitations in the conversion mechanism:
-//!  * Boxed types are supported only through the opaque `JObject`/`jobject` types
+//!  * Boxed types other than the primitive wrappers (`Boxed<T>`, `Option<T>`) are supported only through the opaque `JObject`/`jobject` types
 //!  * Automatic type conversion is limited to the table outlined above, though easily extendable if needed.
 //!
 //! [`Signature`]: convert::Signature
@@ -273,6 +627,8 @@
 
 pub use robusta_codegen::bridge;
 
+pub mod class_loader;
+pub mod classfile;
 pub mod convert;
 
 pub use jni;
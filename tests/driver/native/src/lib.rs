@@ -1,26 +1,381 @@
+use std::any::Any;
+use std::sync::atomic::AtomicI64;
+
 use robusta_jni::bridge;
+use robusta_jni::convert::{
+    Signature, ToThrowable, TryFromJavaValueCtx, TryIntoJavaValue, TryIntoJavaValueCtx,
+};
+use robusta_jni::jni::errors::{Error as JniError, Result as JniResult};
+use robusta_jni::jni::objects::{JThrowable, JValue};
+use robusta_jni::jni::JNIEnv;
+
+/// A Rust-only type passed to Java as a `double`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Celsius(pub f64);
+
+impl Signature for Celsius {
+    const SIG_TYPE: &'static str = "D";
+}
+
+impl Signature for &Celsius {
+    const SIG_TYPE: &'static str = "D";
+}
+
+impl<'env> TryIntoJavaValue<'env> for Celsius {
+    type Target = f64;
+
+    fn try_into(self, env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        TryIntoJavaValue::try_into(&self, env)
+    }
+}
+
+impl<'env> TryIntoJavaValue<'env> for &Celsius {
+    type Target = f64;
+
+    fn try_into(self, _env: &JNIEnv<'env>) -> JniResult<Self::Target> {
+        Ok(self.0)
+    }
+}
+
+static FREEZING: Celsius = Celsius(0.0);
+
+/// Number of calls to `User.recordVisit`, passed to Java as a `long`.
+static VISITS: AtomicI64 = AtomicI64::new(0);
+
+/// A Rust error thrown as an `errors.NegativeValue` by `derive_class`.
+#[derive(Debug)]
+pub struct NegativeValue(pub i32);
+
+impl std::fmt::Display for NegativeValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} is negative", self.0)
+    }
+}
+
+impl std::error::Error for NegativeValue {}
+
+/// A Rust error thrown as a `QuotaExceededException` with its `limit` field set.
+#[derive(Debug)]
+pub struct QuotaExceeded {
+    pub used: i64,
+    pub limit: i64,
+}
+
+impl<'env> ToThrowable<'env> for QuotaExceeded {
+    fn to_throwable(self, env: &JNIEnv<'env>) -> JniResult<JThrowable<'env>> {
+        let message = env.new_string(format!("{} used out of {}", self.used, self.limit))?;
+        let throwable = env.new_object(
+            "QuotaExceededException",
+            "(Ljava/lang/String;)V",
+            &[JValue::from(message)],
+        )?;
+        env.set_field(throwable, "limit", "J", JValue::Long(self.limit))?;
 
-#[bridge]
+        Ok(JThrowable::from(throwable))
+    }
+}
+
+/// Conversion context of [`Scaled`] values.
+pub struct Scale(pub f64);
+
+static SCALE: Scale = Scale(10.0);
+
+/// A `double` that Java passes in units of the [`Scale`] it's converted with.
+pub struct Scaled(pub f64);
+
+fn scale_of(ctx: &dyn Any) -> JniResult<f64> {
+    ctx.downcast_ref::<Scale>()
+        .map(|s| s.0)
+        .ok_or(JniError::WrongJValueType("Scale", "conversion context"))
+}
+
+impl<'env: 'borrow, 'borrow> TryFromJavaValueCtx<'env, 'borrow> for Scaled {
+    type Source = f64;
+
+    fn try_from_ctx(s: f64, _env: &'borrow JNIEnv<'env>, ctx: &dyn Any) -> JniResult<Self> {
+        Ok(Scaled(s * scale_of(ctx)?))
+    }
+}
+
+impl<'env> TryIntoJavaValueCtx<'env> for Scaled {
+    type Target = f64;
+
+    fn try_into_ctx(self, _env: &JNIEnv<'env>, ctx: &dyn Any) -> JniResult<Self::Target> {
+        Ok(self.0 / scale_of(ctx)?)
+    }
+}
+
+#[bridge(class_loader, descriptors, must_use)]
 pub mod jni {
-    use std::convert::TryInto;
+    use std::borrow::Cow;
+    use std::collections::{HashMap, VecDeque};
+    use std::convert::{Infallible, TryInto};
+    use std::ffi::{CStr, CString};
+    use std::fmt::Arguments;
+    use std::marker::PhantomData;
+    use std::num::Wrapping;
+    use std::ops::{ControlFlow, Range, RangeInclusive};
+    use std::process::{Command, ExitStatus};
+    use std::sync::atomic::{AtomicI64, Ordering};
+    use std::time::{Duration, SystemTime};
+
+    use anyhow::Context;
+    use bytes::{BufMut, Bytes, BytesMut};
+    use chrono::{DateTime, Utc};
+    use smallvec::SmallVec;
 
     use robusta_jni::convert::{
-        IntoJavaValue, JValueWrapper, Signature, TryFromJavaValue, TryIntoJavaValue,
+        into_native_pointer, BatchClass, BatchResult, Boxed, CriticalByteArray, Field,
+        IntoJavaValue, JNull, JValueWrapper, JavaDebug, JavaList, JavaRecord, MutableByteArray,
+        Packed, Signature, TryFromJavaValue, TryIntoJavaValue,
     };
     use robusta_jni::jni::errors::Result as JniResult;
-    use robusta_jni::jni::objects::AutoLocal;
+    use robusta_jni::jni::objects::{AutoLocal, JObject, JThrowable};
     use robusta_jni::jni::JNIEnv;
 
-    #[derive(Signature, TryIntoJavaValue, IntoJavaValue, TryFromJavaValue)]
+    use super::{Celsius, NegativeValue, QuotaExceeded, Scaled, FREEZING, VISITS};
+
+    #[derive(Signature, TryIntoJavaValue, IntoJavaValue, TryFromJavaValue, JavaDebug)]
     #[package()]
+    #[std_convert]
     pub struct User<'env: 'borrow, 'borrow> {
         #[instance]
         raw: AutoLocal<'env, 'borrow>,
         password: String,
     }
 
+    #[derive(Signature, TryIntoJavaValue, TryFromJavaValue)]
+    #[package()]
+    #[module_loader]
+    pub struct ValidationException<'env: 'borrow, 'borrow> {
+        #[instance]
+        raw: AutoLocal<'env, 'borrow>,
+    }
+
+    impl<'env: 'borrow, 'borrow> ValidationException<'env, 'borrow> {
+        #[constructor]
+        pub extern "java" fn new(env: &'borrow JNIEnv<'env>, code: i32) -> JniResult<Self> {}
+    }
+
+    #[derive(Signature, TryIntoJavaValue, TryFromJavaValue)]
+    #[package(java.lang)]
+    pub struct StringBuilder<'env: 'borrow, 'borrow> {
+        #[instance]
+        raw: AutoLocal<'env, 'borrow>,
+    }
+
+    impl<'env: 'borrow, 'borrow> StringBuilder<'env, 'borrow> {
+        #[constructor]
+        pub extern "java" fn new(env: &'borrow JNIEnv<'env>, s: String) -> JniResult<Self> {}
+
+        pub extern "java" fn reverse(&self, env: &'borrow JNIEnv<'env>) -> JniResult<Self> {}
+
+        pub extern "java" fn toString(&self, env: &JNIEnv) -> JniResult<String> {}
+    }
+
+    /// `java.util.List<E>`, whose element type is erased in JNI descriptors (`Ljava/util/List;`).
+    #[derive(Signature, TryIntoJavaValue, TryFromJavaValue)]
+    #[package(java.util)]
+    pub struct List<'env: 'borrow, 'borrow, E> {
+        #[instance]
+        raw: AutoLocal<'env, 'borrow>,
+        element: PhantomData<E>,
+    }
+
+    impl<'env: 'borrow, 'borrow, E> List<'env, 'borrow, E> {
+        pub extern "java" fn size(&self, env: &JNIEnv) -> JniResult<i32> {}
+    }
+
+    #[derive(Signature)]
+    #[package()]
+    pub struct Marker;
+
+    /// A `User` carrying a Rust-only type parameter, which the derived conversions skip.
+    #[derive(Signature, TryIntoJavaValue, IntoJavaValue, TryFromJavaValue)]
+    #[package()]
+    pub struct TaggedUser<'env: 'borrow, 'borrow, T> {
+        #[instance]
+        raw: AutoLocal<'env, 'borrow>,
+        tag: PhantomData<T>,
+    }
+
+    impl Marker {
+        pub extern "jni" fn answer() -> i32 {
+            42
+        }
+    }
+
+    #[derive(Signature)]
+    #[package()]
+    pub struct Counter(pub i32);
+
+    impl Counter {
+        pub extern "jni" fn increment(v: i32) -> i32 {
+            v + 1
+        }
+    }
+
+    #[derive(Signature, TryIntoJavaValue, TryFromJavaValue)]
+    #[package()]
+    pub struct Builder<'env: 'borrow, 'borrow> {
+        #[instance]
+        raw: AutoLocal<'env, 'borrow>,
+        #[field]
+        width: Field<'env, 'borrow, i32>,
+        #[field]
+        height: Field<'env, 'borrow, i32>,
+    }
+
+    impl<'env: 'borrow, 'borrow> Builder<'env, 'borrow> {
+        pub extern "jni" fn withWidth(mut self, v: i32) -> JniResult<Self> {
+            self.width.set(v)?;
+            Ok(self)
+        }
+
+        pub extern "jni" fn withHeight(mut self, v: i32) -> Self {
+            self.height.set(v).unwrap();
+            self
+        }
+    }
+
+    impl<'env, 'borrow> Builder<'env, 'borrow>
+    where
+        'env: 'borrow,
+    {
+        pub extern "jni" fn area(self) -> JniResult<i32> {
+            Ok(self.width.get()? * self.height.get()?)
+        }
+    }
+
+    /// A running total owned by the Java object, which stores its address in the `handle` field.
+    #[derive(Signature)]
+    #[package()]
+    #[receiver(native_pointer = "handle")]
+    #[assert_send]
+    pub struct Tally {
+        total: i32,
+    }
+
+    impl Tally {
+        pub extern "jni" fn create() -> i64 {
+            into_native_pointer(Tally { total: 0 })
+        }
+
+        pub extern "jni" fn add(&mut self, v: i32) -> i32 {
+            self.total += v;
+            self.total
+        }
+
+        pub extern "jni" fn total(&self) -> i32 {
+            self.total
+        }
+
+        pub extern "jni" fn close(self) -> i32 {
+            self.total
+        }
+    }
+
+    #[derive(Signature, TryFromJavaValue)]
+    #[package()]
+    #[receiver(pojo)]
+    pub struct Point<'env: 'borrow, 'borrow> {
+        #[instance]
+        raw: AutoLocal<'env, 'borrow>,
+        x: i32,
+        y: i32,
+    }
+
+    impl<'env: 'borrow, 'borrow> Point<'env, 'borrow> {
+        pub extern "jni" fn manhattanLength(&self) -> i32 {
+            self.x.abs() + self.y.abs()
+        }
+
+        pub extern "jni" fn isSameAs(
+            &self,
+            env: &'borrow JNIEnv<'env>,
+            other: JObject<'env>,
+        ) -> JniResult<bool> {
+            env.is_same_object(self.raw.as_obj(), other)
+        }
+    }
+
+    /// Reconstructed from the accessors of the `Interval` Java class.
+    #[derive(Signature)]
+    #[package()]
+    #[receiver(with = interval_from_bounds)]
+    #[dispatcher]
+    pub struct Interval {
+        lo: i32,
+        hi: i32,
+    }
+
+    fn interval_from_bounds<'env>(env: &JNIEnv<'env>, obj: JObject<'env>) -> JniResult<Interval> {
+        Ok(Interval {
+            lo: env.call_method(obj, "lo", "()I", &[])?.i()?,
+            hi: env.call_method(obj, "hi", "()I", &[])?.i()?,
+        })
+    }
+
+    impl Interval {
+        pub extern "jni" fn length(&self) -> i32 {
+            self.hi - self.lo
+        }
+
+        pub extern "jni" fn contains(self, v: i32) -> bool {
+            (self.lo..self.hi).contains(&v)
+        }
+
+        pub extern "jni" fn describe(&self, unit: String) -> String {
+            format!("[{}, {}) {}", self.lo, self.hi, unit)
+        }
+
+        pub extern "jni" fn widthOf(lo: i32, hi: i32) -> i32 {
+            hi - lo
+        }
+
+        pub extern "jni" fn requireContains(&self, v: i32) -> JniResult<()> {
+            if (self.lo..self.hi).contains(&v) {
+                Ok(())
+            } else {
+                Err(robusta_jni::jni::errors::Error::WrongJValueType(
+                    "value in the interval",
+                    "value outside of it",
+                ))
+            }
+        }
+    }
+
+    #[derive(Signature)]
+    #[package()]
+    #[class_alias(GreeterV2)]
+    pub struct GreeterV1;
+
+    impl GreeterV1 {
+        pub extern "jni" fn greet(name: String) -> String {
+            format!("Hello, {}", name)
+        }
+    }
+
+    #[derive(Signature)]
+    #[package()]
+    pub struct Batch;
+
+    impl BatchClass for Batch {}
+
+    #[derive(Signature, JavaRecord)]
+    #[package()]
+    pub struct Measurement {
+        unit_name: String,
+        value: f64,
+    }
+
+    const ILLEGAL_ARGUMENT_EXCEPTION: &str = "java.lang.IllegalArgumentException";
+
     impl<'env: 'borrow, 'borrow> User<'env, 'borrow> {
-        pub extern "jni" fn initNative() {
+        pub extern "jni" fn initNative(env: &JNIEnv) {
+            robusta_jni::convert::register_java_vm(&env.get_java_vm().unwrap());
+            robusta_jni::class_loader::register_class_loader(env, "User").unwrap();
+
             std::env::var("RUST_LOG").unwrap_or_else(|_| {
                 std::env::set_var("RUST_LOG", "info");
                 "info".to_string()
@@ -32,6 +387,54 @@ pub mod jni {
             env_logger::init();
         }
 
+        pub extern "jni" fn scaleMeasurement(m: Measurement, factor: f64) -> Measurement {
+            Measurement {
+                unit_name: m.unit_name + " (scaled)",
+                value: m.value * factor,
+            }
+        }
+
+        pub extern "jni" fn measurementsOf(
+            unit_name: String,
+            values: JavaList<f64>,
+        ) -> JavaList<Measurement> {
+            JavaList(
+                values
+                    .0
+                    .into_iter()
+                    .map(|value| Measurement {
+                        unit_name: unit_name.clone(),
+                        value,
+                    })
+                    .collect(),
+            )
+        }
+
+        pub extern "jni" fn registerModuleLoader(
+            env: &JNIEnv,
+            loader: JObject<'env>,
+        ) -> JniResult<()> {
+            robusta_jni::class_loader::register_module_loader(env, loader)
+        }
+
+        #[hot]
+        pub extern "jni" fn staticSum(a: i32, b: i32) -> i32 {
+            a + b
+        }
+
+        #[call_type(both(safe_suffix = "Checked", unchecked_suffix = "Fast"))]
+        pub extern "jni" fn scaled(v: i32, factor: i32) -> i32 {
+            v * factor
+        }
+
+        pub extern "jni" fn staticFieldsStatus(env: &JNIEnv) -> JniResult<String> {
+            Ok(format!(
+                "{} {}",
+                User::TOTAL_USERS_COUNT(env)?,
+                User::DEFAULT_GREETING(env)
+            ))
+        }
+
         pub extern "jni" fn userCountStatus(env: &JNIEnv) -> String {
             let users_count: i32 = JValueWrapper::from(
                 env.get_static_field("User", "TOTAL_USERS_COUNT", "I")
@@ -47,6 +450,48 @@ pub mod jni {
             user_pw + "_pass"
         }
 
+        pub extern "jni" fn samePassword(&self, other: &Self) -> bool {
+            self.password == other.password
+        }
+
+        pub extern "jni" fn sameUser(
+            self,
+            env: &'borrow JNIEnv<'env>,
+            other: JObject<'env>,
+        ) -> JniResult<bool> {
+            let other: User = (other, env).try_into()?;
+            env.is_same_object(&self, other)
+        }
+
+        /// Creates up to 255 local references (one per copy) without exceeding the capacity of its local frame.
+        #[local_frame(capacity = 256)]
+        pub extern "jni" fn lastCopy(
+            self,
+            env: &JNIEnv<'env>,
+            v: String,
+            n: i32,
+        ) -> JniResult<JObject<'env>> {
+            let mut last = JObject::null();
+            for i in 0..n {
+                last = JObject::from(env.new_string(format!("{}{}", v, i))?);
+            }
+
+            Ok(last)
+        }
+
+        pub extern "jni" fn nonEmptyOf(
+            self,
+            env: &JNIEnv<'env>,
+            items: List<'env, 'borrow, String>,
+        ) -> JniResult<String> {
+            let size = items.size(env)?;
+            Ok(format!("{}/{}", User::countNonEmpty(env, items)?, size))
+        }
+
+        pub extern "jni" fn debugString(self) -> String {
+            format!("{:?}", self)
+        }
+
         pub extern "jni" fn getInt(self, v: i32) -> i32 {
             v
         }
@@ -55,6 +500,267 @@ pub mod jni {
             v
         }
 
+        #[call_type(safe(exception_class_const = ILLEGAL_ARGUMENT_EXCEPTION, message = "invalid value"))]
+        pub extern "jni" fn checkedPositive(self, v: i32) -> JniResult<i32> {
+            if v > 0 {
+                Ok(v)
+            } else {
+                Err(robusta_jni::jni::errors::Error::WrongJValueType(
+                    "positive int",
+                    "int",
+                ))
+            }
+        }
+
+        #[call_type(safe(log_to = "RecordingLoggerFactory"))]
+        pub extern "jni" fn loggedPositive(self, v: i32) -> JniResult<i32> {
+            self.checkedPositive(v)
+        }
+
+        #[call_type(safe(message = "invalid value", backtrace = true))]
+        pub extern "jni" fn checkedPositiveWithBacktrace(self, v: i32) -> JniResult<i32> {
+            self.checkedPositive(v)
+        }
+
+        pub extern "jni" fn checkedNonEmpty(self, v: String) -> JniResult<String> {
+            if !v.is_empty() {
+                Ok(v)
+            } else {
+                Err(robusta_jni::jni::errors::Error::WrongJValueType(
+                    "non-empty string",
+                    "empty string",
+                ))
+            }
+        }
+
+        pub extern "jni" fn rethrowNullableString(
+            self,
+            env: &JNIEnv<'env>,
+            mode: i32,
+        ) -> Result<String, JThrowable<'env>> {
+            User::nullableString(env, mode)
+                .map(Option::unwrap_or_default)
+                .map_err(|_| {
+                    let throwable = env.exception_occurred().unwrap();
+                    env.exception_clear().unwrap();
+                    throwable
+                })
+        }
+
+        pub extern "jni" fn validatePositive(
+            self,
+            env: &'borrow JNIEnv<'env>,
+            v: i32,
+        ) -> Result<(), ValidationException<'env, 'borrow>> {
+            if v > 0 {
+                Ok(())
+            } else {
+                Err(ValidationException::new(env, v).unwrap())
+            }
+        }
+
+        pub extern "jni" fn consumeQuota(
+            self,
+            used: i64,
+            limit: i64,
+        ) -> Result<i64, QuotaExceeded> {
+            if used <= limit {
+                Ok(limit - used)
+            } else {
+                Err(QuotaExceeded { used, limit })
+            }
+        }
+
+        pub extern "jni" fn wrappingIncrement(self, v: Wrapping<i32>) -> Wrapping<i32> {
+            v + Wrapping(1)
+        }
+
+        #[allow(clippy::type_complexity)]
+        pub extern "jni" fn swapPacked(self, v: Packed<(i32, i32)>) -> Packed<(i32, i32)> {
+            let (a, b) = v.0;
+            Packed((b, a))
+        }
+
+        pub extern "jni" fn getRange(self, v: Range<i32>) -> Range<i32> {
+            v
+        }
+
+        pub extern "jni" fn getRangeInclusive(self, v: RangeInclusive<i32>) -> RangeInclusive<i32> {
+            v
+        }
+
+        pub extern "jni" fn rangeToString(self, v: Range<i32>) -> String {
+            format!("{:?}", v)
+        }
+
+        pub extern "jni" fn rangeInclusiveToString(self, v: RangeInclusive<i32>) -> String {
+            format!("{:?}", v)
+        }
+
+        pub extern "jni" fn stopAbove(self, v: i32, threshold: i32) -> ControlFlow<i32> {
+            if v > threshold {
+                ControlFlow::Break(v)
+            } else {
+                ControlFlow::Continue(())
+            }
+        }
+
+        pub extern "jni" fn getControlFlow(
+            self,
+            v: ControlFlow<String, i32>,
+        ) -> ControlFlow<String, i32> {
+            v
+        }
+
+        pub extern "jni" fn parseAll(self, items: JavaList<String>) -> BatchResult<i32, String> {
+            items
+                .0
+                .into_iter()
+                .map(|s| s.parse().map_err(|_| s))
+                .collect()
+        }
+
+        #[allow(clippy::type_complexity)]
+        pub extern "jni" fn parseAllInto(
+            self,
+            items: JavaList<String>,
+        ) -> BatchResult<i32, String, Batch> {
+            items
+                .0
+                .into_iter()
+                .map(|s| s.parse().map_err(|_| s))
+                .collect()
+        }
+
+        pub extern "jni" fn celsiusOrFreezing(self, v: f64) -> Cow<'static, Celsius> {
+            if v.is_nan() {
+                Cow::Borrowed(&FREEZING)
+            } else {
+                Cow::Owned(Celsius(v))
+            }
+        }
+
+        #[call_type(safe(derive_class = "errors"))]
+        pub extern "jni" fn checkedNonNegative(self, v: i32) -> Result<i32, NegativeValue> {
+            if v >= 0 {
+                Ok(v)
+            } else {
+                Err(NegativeValue(v))
+            }
+        }
+
+        /// Zero stands for no timeout at all, which doesn't fit in a `java.time.Duration`.
+        #[call_type(safe(derive_class = "errors"))]
+        pub extern "jni" fn timeoutFor(self, v: i32) -> Result<Duration, NegativeValue> {
+            match v {
+                0 => Ok(Duration::MAX),
+                v if v > 0 => Ok(Duration::from_secs(v as u64)),
+                v => Err(NegativeValue(v)),
+            }
+        }
+
+        pub extern "jni" fn lookupSetting(self, key: String) -> anyhow::Result<String> {
+            if key == "greeting" {
+                return Ok("Hello".to_string());
+            }
+
+            Err(anyhow::anyhow!("no setting named `{}`", key))
+                .context("reading settings")
+                .context("loading configuration")
+        }
+
+        pub extern "jni" fn findSetting(self, key: String) -> anyhow::Result<Option<String>> {
+            match key.as_str() {
+                "greeting" => Ok(Some("Hello".to_string())),
+                "farewell" => Ok(None),
+                _ => Err(anyhow::anyhow!("no setting named `{}`", key)),
+            }
+        }
+
+        pub extern "jni" fn bridgeMismatches(classes_dir: String) -> anyhow::Result<Vec<String>> {
+            let mut class_files = Vec::new();
+            for entry in std::fs::read_dir(classes_dir)? {
+                let path = entry?.path();
+                if path.extension().is_some_and(|e| e == "class") {
+                    class_files.push(path);
+                }
+            }
+
+            let mismatches = robusta_jni::classfile::validate(&bridge_methods(), &class_files)?;
+            Ok(mismatches.iter().map(ToString::to_string).collect())
+        }
+
+        pub extern "jni" fn withSuffix(
+            self,
+            v: String,
+            #[default_on_error] suffix: String,
+        ) -> String {
+            v + &suffix
+        }
+
+        pub extern "jni" fn reversedWithBuilder(
+            self,
+            env: &'borrow JNIEnv<'env>,
+            v: String,
+        ) -> JniResult<String> {
+            StringBuilder::new(env, v)?.reverse(env)?.toString(env)
+        }
+
+        pub extern "jni" fn positivityCheck(self, env: &JNIEnv<'env>, v: i32) -> String {
+            match self.requirePositive(env, v) {
+                Ok(()) => "positive".to_string(),
+                Err(e) => {
+                    env.exception_clear().unwrap();
+                    format!("rejected: {}", e)
+                }
+            }
+        }
+
+        pub extern "jni" fn visitTwice(self, env: &JNIEnv<'env>, n: i32) -> JniResult<i32> {
+            self.addVisits(env, n);
+            self.addVisits(env, n);
+            self.getVisits(env)
+        }
+
+        #[allow(clippy::result_unit_err)]
+        pub extern "jni" fn checkedEven(self, v: i32) -> Result<i32, ()> {
+            if v % 2 == 0 {
+                Ok(v)
+            } else {
+                Err(())
+            }
+        }
+
+        pub extern "jni" fn infallibleDouble(self, v: i32) -> Result<i32, Infallible> {
+            Ok(v * 2)
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn infallibleGreeting(v: String) -> Result<String, Infallible> {
+            Ok(format!("Hello, {}!", v))
+        }
+
+        #[call_type(safe(with_context = crate::SCALE))]
+        pub extern "jni" fn addScaled(self, v: Scaled, delta: f64) -> Scaled {
+            Scaled(v.0 + delta)
+        }
+
+        pub extern "jni" fn cowCStr(self, borrowed: bool) -> Cow<'static, CStr> {
+            if borrowed {
+                Cow::Borrowed(CStr::from_bytes_with_nul(b"borrowed\0").unwrap())
+            } else {
+                Cow::Owned(CString::new("owned").unwrap())
+            }
+        }
+
+        pub extern "jni" fn shellExitStatus(self, script: String) -> ExitStatus {
+            Command::new("sh")
+                .arg("-c")
+                .arg(script)
+                .status()
+                .expect("failed to run sh")
+        }
+
         pub extern "jni" fn getChar(self, v: char) -> char {
             v
         }
@@ -83,11 +789,25 @@ pub mod jni {
             v
         }
 
-        pub extern "jni" fn getIntArray(self, v: Vec<i32>) -> Vec<i32> {
+        pub extern "jni" fn getCString(self, v: CString) -> CString {
             v
         }
 
-        pub extern "jni" fn getStringArray(self, v: Vec<String>) -> Vec<String> {
+        pub extern "jni" fn getIntArray(self, v: JavaList<i32>) -> JavaList<i32> {
+            v
+        }
+
+        pub extern "jni" fn getStringArray(self, v: JavaList<String>) -> JavaList<String> {
+            v
+        }
+
+        pub extern "jni" fn getDeque(self, v: VecDeque<String>) -> VecDeque<String> {
+            v
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn rotateDeque(mut v: VecDeque<i32>) -> VecDeque<i32> {
+            v.rotate_left(1.min(v.len()));
             v
         }
 
@@ -95,6 +815,180 @@ pub mod jni {
             v
         }
 
+        pub extern "jni" fn getDoubleArray(self, v: Box<[f64]>) -> Box<[f64]> {
+            v
+        }
+
+        pub extern "jni" fn getCharArray(self, v: Box<[char]>) -> Box<[char]> {
+            v
+        }
+
+        #[allow(clippy::boxed_local)]
+        pub extern "jni" fn charArrayLength(self, v: Box<[char]>) -> i32 {
+            v.len() as i32
+        }
+
+        pub extern "jni" fn getSmallIntArray(self, v: SmallVec<[i32; 4]>) -> SmallVec<[i32; 4]> {
+            v
+        }
+
+        pub extern "jni" fn reverseSmallUsers(
+            self,
+            mut v: SmallVec<[User<'env, 'borrow>; 2]>,
+        ) -> SmallVec<[User<'env, 'borrow>; 2]> {
+            v.reverse();
+            v
+        }
+
+        #[call_type(unchecked)]
+        #[allow(clippy::boxed_local)]
+        pub extern "jni" fn sumIntArray(v: Box<[i32]>) -> i32 {
+            v.iter().sum()
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn criticalChecksum(v: CriticalByteArray<'borrow>) -> i64 {
+            v.iter().map(|&b| b as i64).sum()
+        }
+
+        #[allow(clippy::boxed_local)]
+        pub extern "jni" fn copiedChecksum(v: Box<[u8]>) -> i64 {
+            v.iter().map(|&b| b as i64).sum()
+        }
+
+        pub extern "jni" fn readGreeting(mut buf: MutableByteArray<'borrow>) -> JniResult<i32> {
+            let greeting = b"hello";
+            let count = buf.len().min(greeting.len());
+            buf[..count].copy_from_slice(&greeting[..count]);
+            buf.commit()?;
+            Ok(count as i32)
+        }
+
+        #[call_type(unchecked)]
+        pub extern "jni" fn fillBytes(mut buf: MutableByteArray<'borrow>, v: i8) {
+            buf.fill(v as u8);
+        }
+
+        pub extern "jni" fn recordVisit() -> &'static AtomicI64 {
+            VISITS.fetch_add(1, Ordering::Relaxed);
+            &VISITS
+        }
+
+        pub extern "jni" fn weightedChecksum(
+            a: CriticalByteArray<'borrow>,
+            b: CriticalByteArray<'borrow>,
+            c: CriticalByteArray<'borrow>,
+            weight: i64,
+        ) -> i64 {
+            let sum = |v: &[u8]| v.iter().map(|&x| x as i64).sum::<i64>();
+            (sum(&a) + sum(&b) + sum(&c)) * weight
+        }
+
+        pub extern "jni" fn getBytes(self, v: Bytes) -> Bytes {
+            v
+        }
+
+        pub extern "jni" fn appendBytesMut(self, mut v: BytesMut) -> BytesMut {
+            v.put_u8(42);
+            v
+        }
+
+        #[call_type(safe(check_pending = false))]
+        pub extern "jni" fn sumWithoutPendingCheck(self, a: i32, b: i32) -> i32 {
+            a + b
+        }
+
+        pub extern "jni" fn reverseUsers(
+            self,
+            v: Box<[User<'env, 'borrow>]>,
+        ) -> Box<[User<'env, 'borrow>]> {
+            let mut v = v.into_vec();
+            v.reverse();
+            v.into_boxed_slice()
+        }
+
+        #[allow(clippy::boxed_local)]
+        pub extern "jni" fn usernamesAfter(
+            self,
+            env: &JNIEnv<'env>,
+            v: Box<[User<'env, 'borrow>]>,
+            skip: i32,
+        ) -> JniResult<String> {
+            User::joinUsernames(env, &v[skip as usize..])
+        }
+
+        pub extern "jni" fn describeBoxed(
+            self,
+            flag: Boxed<bool>,
+            c: Boxed<char>,
+            b: Boxed<i8>,
+        ) -> String {
+            format!("{} {} {}", flag.0, c.0, b.0)
+        }
+
+        pub extern "jni" fn negateBoxed(self, flag: Boxed<bool>) -> Boxed<bool> {
+            Boxed(!flag.0)
+        }
+
+        pub extern "jni" fn incrementOptional(
+            self,
+            env: &JNIEnv<'env>,
+            v: Option<i32>,
+        ) -> JniResult<Option<i32>> {
+            User::nextOrNull(env, v)
+        }
+
+        #[allow(clippy::type_complexity)]
+        pub extern "jni" fn reverseNullableUsers(
+            self,
+            v: Box<[Option<User<'env, 'borrow>>]>,
+        ) -> Box<[Option<User<'env, 'borrow>>]> {
+            let mut v = v.into_vec();
+            v.reverse();
+            v.into_boxed_slice()
+        }
+
+        pub extern "jni" fn uncheckedConcat(self, a: String, #[unchecked] b: String) -> String {
+            a + &b
+        }
+
+        /// # Safety
+        ///
+        /// Always safe to call: `v` is a valid, aligned `i32`, this only exercises `unsafe` exported methods.
+        pub unsafe extern "jni" fn unsafeRead(self, v: i32) -> i32 {
+            std::ptr::read(&v)
+        }
+
+        pub extern "jni" fn getInstant(self, v: DateTime<Utc>) -> DateTime<Utc> {
+            v
+        }
+
+        pub extern "jni" fn instantToString(self, v: DateTime<Utc>) -> String {
+            v.timestamp_millis().to_string()
+        }
+
+        pub extern "jni" fn withProperty(
+            self,
+            mut v: HashMap<String, String>,
+            key: String,
+            value: String,
+        ) -> HashMap<String, String> {
+            v.insert(key, value);
+            v
+        }
+
+        pub extern "jni" fn getDuration(self, v: Duration) -> Duration {
+            v
+        }
+
+        pub extern "jni" fn maxDuration(self) -> Duration {
+            Duration::MAX
+        }
+
+        pub extern "jni" fn getSystemTime(self, v: SystemTime) -> SystemTime {
+            v
+        }
+
         pub extern "jni" fn intToString(self, v: i32) -> String {
             format!("{}", v)
         }
@@ -123,33 +1017,120 @@ pub mod jni {
             format!("{}", v)
         }
 
-        pub extern "jni" fn shortToString(self, v: i16) -> String {
+        extern "jni" fn shortToString(self, v: i16) -> String {
             format!("{}", v)
         }
 
-        pub extern "jni" fn intArrayToString(self, v: Vec<i32>) -> String {
-            format!("{:?}", v)
+        pub extern "jni" fn intArrayToString(self, v: JavaList<i32>) -> String {
+            format!("{:?}", v.0)
         }
 
-        pub extern "jni" fn stringArrayToString(self, v: Vec<String>) -> String {
-            format!("{:?}", v)
+        pub extern "jni" fn stringArrayToString(self, v: JavaList<String>) -> String {
+            format!("{:?}", v.0)
         }
 
         pub extern "jni" fn byteArrayToString(self, v: Box<[u8]>) -> String {
             format!("{:?}", v)
         }
 
+        pub extern "java" fn requirePositive(&self, env: &JNIEnv, v: i32) -> JniResult<()> {}
+
+        #[call_type(unchecked)]
+        pub extern "java" fn addVisits(&self, env: &JNIEnv, n: i32) {}
+
+        pub extern "java" fn getVisits(&self, env: &JNIEnv) -> JniResult<i32> {}
+
         pub extern "java" fn getPassword(
             &self,
             env: &JNIEnv,
         ) -> ::robusta_jni::jni::errors::Result<String> {
         }
 
+        // Typed receivers are converted like `&self`
+        #[allow(clippy::needless_arbitrary_self_type)]
+        pub extern "java" fn getUsername(
+            self: &Self,
+            env: &JNIEnv,
+        ) -> ::robusta_jni::jni::errors::Result<String> {
+        }
+
+        #[call_type(unchecked)]
+        pub extern "java" fn toString(self, env: &JNIEnv) -> String {}
+
         pub extern "java" fn getTotalUsersCount(
             env: &JNIEnv,
         ) -> ::robusta_jni::jni::errors::Result<i32> {
         }
 
+        #[static_field]
+        pub extern "java" fn TOTAL_USERS_COUNT(env: &JNIEnv) -> JniResult<i32> {}
+
+        #[static_field]
+        #[call_type(unchecked)]
+        pub extern "java" fn DEFAULT_GREETING(env: &JNIEnv) -> String {}
+
+        pub extern "java" fn nullableString(
+            env: &JNIEnv,
+            mode: i32,
+        ) -> ::robusta_jni::jni::errors::Result<Option<String>> {
+        }
+
+        pub extern "java" fn nullableStringNested(
+            env: &JNIEnv,
+            mode: i32,
+        ) -> Option<::robusta_jni::jni::errors::Result<String>> {
+        }
+
+        pub extern "java" fn isNullString(
+            env: &JNIEnv,
+            s: JNull<String>,
+        ) -> ::robusta_jni::jni::errors::Result<bool> {
+        }
+
+        pub extern "java" fn stringLength(
+            env: &JNIEnv,
+            s: Option<&str>,
+        ) -> ::robusta_jni::jni::errors::Result<i32> {
+        }
+
+        pub extern "java" fn countNonEmpty(
+            env: &JNIEnv<'env>,
+            items: List<'env, 'borrow, String>,
+        ) -> ::robusta_jni::jni::errors::Result<i32> {
+        }
+
+        pub extern "java" fn joinUsernames(
+            env: &JNIEnv<'env>,
+            users: &[User<'env, 'borrow>],
+        ) -> ::robusta_jni::jni::errors::Result<String> {
+        }
+
+        pub extern "java" fn nextOrNull(
+            env: &JNIEnv<'env>,
+            v: Option<i32>,
+        ) -> ::robusta_jni::jni::errors::Result<Option<i32>> {
+        }
+
+        pub extern "java" fn echoString(
+            env: &JNIEnv,
+            s: Arguments,
+        ) -> ::robusta_jni::jni::errors::Result<String> {
+        }
+
+        #[descriptor = "(Ljava/lang/Object;)Ljava/lang/String;"]
+        pub extern "java" fn overloaded(
+            env: &JNIEnv,
+            s: String,
+        ) -> ::robusta_jni::jni::errors::Result<String> {
+        }
+
+        pub extern "java" fn greet(
+            env: &JNIEnv,
+            greeting: String,
+            #[this] user: &Self,
+        ) -> ::robusta_jni::jni::errors::Result<String> {
+        }
+
         pub extern "java" fn multipleParameters(
             &self,
             env: &JNIEnv,
@@ -167,3 +1148,19 @@ pub mod jni {
         }
     }
 }
+
+/// A module where `null` arguments take the default value of their type instead of throwing.
+#[bridge(null_policy = "default")]
+mod lenient {
+    use robusta_jni::convert::Signature;
+
+    #[derive(Signature)]
+    #[package()]
+    pub struct Lenient;
+
+    impl Lenient {
+        pub extern "jni" fn length(s: String) -> i32 {
+            s.len() as i32
+        }
+    }
+}
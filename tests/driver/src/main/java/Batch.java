@@ -0,0 +1,11 @@
+import java.util.List;
+
+public class Batch {
+    public final List<Integer> successes;
+    public final List<String> errors;
+
+    public Batch(List<Integer> successes, List<String> errors) {
+        this.successes = successes;
+        this.errors = errors;
+    }
+}
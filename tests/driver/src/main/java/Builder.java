@@ -0,0 +1,14 @@
+public class Builder {
+    static {
+        System.loadLibrary("native");
+    }
+
+    public int width;
+    public int height;
+
+    public native Builder withWidth(int width);
+
+    public native Builder withHeight(int height);
+
+    public native int area();
+}
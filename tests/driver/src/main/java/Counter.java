@@ -0,0 +1,7 @@
+public class Counter {
+    static {
+        System.loadLibrary("native");
+    }
+
+    public static native int increment(int x);
+}
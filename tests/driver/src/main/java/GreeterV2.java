@@ -0,0 +1,7 @@
+public class GreeterV2 {
+    static {
+        System.loadLibrary("native");
+    }
+
+    public static native String greet(String name);
+}
@@ -0,0 +1,33 @@
+public final class Interval {
+    static {
+        System.loadLibrary("native");
+    }
+
+    private final int lo;
+    private final int hi;
+
+    public Interval(int lo, int hi) {
+        this.lo = lo;
+        this.hi = hi;
+    }
+
+    public int lo() {
+        return lo;
+    }
+
+    public int hi() {
+        return hi;
+    }
+
+    public native int length();
+
+    public native boolean contains(int v);
+
+    public native String describe(String unit);
+
+    public static native int widthOf(int lo, int hi);
+
+    public native void requireContains(int v);
+
+    public static native Object invoke(String method, Object[] args);
+}
@@ -0,0 +1,7 @@
+public class Lenient {
+    static {
+        System.loadLibrary("native");
+    }
+
+    public static native int length(String s);
+}
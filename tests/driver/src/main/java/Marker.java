@@ -0,0 +1,7 @@
+public class Marker {
+    static {
+        System.loadLibrary("native");
+    }
+
+    public static native int answer();
+}
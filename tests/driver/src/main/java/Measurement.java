@@ -0,0 +1,2 @@
+public record Measurement(String unitName, double value) {
+}
@@ -0,0 +1,17 @@
+public class Point {
+    static {
+        System.loadLibrary("native");
+    }
+
+    private final int x;
+    private final int y;
+
+    public Point(int x, int y) {
+        this.x = x;
+        this.y = y;
+    }
+
+    public native int manhattanLength();
+
+    public native boolean isSameAs(Point other);
+}
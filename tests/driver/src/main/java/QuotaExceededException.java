@@ -0,0 +1,7 @@
+public class QuotaExceededException extends RuntimeException {
+    public long limit;
+
+    public QuotaExceededException(String message) {
+        super(message);
+    }
+}
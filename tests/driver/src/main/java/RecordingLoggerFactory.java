@@ -0,0 +1,27 @@
+import java.util.ArrayList;
+import java.util.List;
+
+/**
+ * A logger factory with the shape of SLF4J's {@code LoggerFactory}, recording the errors logged by native methods.
+ */
+public class RecordingLoggerFactory {
+    public static final List<String> MESSAGES = new ArrayList<>();
+    public static final List<Throwable> THROWABLES = new ArrayList<>();
+
+    public static Logger getLogger(String name) {
+        return new Logger(name);
+    }
+
+    public static class Logger {
+        private final String name;
+
+        private Logger(String name) {
+            this.name = name;
+        }
+
+        public void error(String message, Throwable t) {
+            MESSAGES.add(name + ": " + message);
+            THROWABLES.add(t);
+        }
+    }
+}
@@ -0,0 +1,15 @@
+public class Tally {
+    static {
+        System.loadLibrary("native");
+    }
+
+    private long handle = create();
+
+    private static native long create();
+
+    public native int add(int v);
+
+    public native int total();
+
+    public native int close();
+}
@@ -1,4 +1,10 @@
+import java.time.Duration;
+import java.time.Instant;
+import java.util.AbstractMap;
+import java.util.ArrayList;
+import java.util.Deque;
 import java.util.List;
+import java.util.Properties;
 
 public class User {
     static {
@@ -8,8 +14,13 @@ public class User {
 
     private static int TOTAL_USERS_COUNT = 0;
 
+    public static final String DEFAULT_GREETING = "Hello";
+
     private String username;
     private String password;
+    private int visits = 0;
+
+    public native String debugString();
 
     public native int getInt(int x);
 
@@ -29,12 +40,126 @@ public class User {
 
     public native String getString(String x);
 
+    public native String getCString(String x);
+
     public native List<Integer> getIntArray(List<Integer> x);
 
     public native List<String> getStringArray(List<String> x);
 
+    public native Deque<String> getDeque(Deque<String> x);
+
     public native byte[] getByteArray(byte[] x);
 
+    public native double[] getDoubleArray(double[] x);
+
+    public native char[] getCharArray(char[] x);
+
+    public native int charArrayLength(char[] x);
+
+    public native int[] getSmallIntArray(int[] x);
+
+    public native User[] reverseSmallUsers(User[] x);
+
+    public native byte[] getBytes(byte[] x);
+
+    public native byte[] appendBytesMut(byte[] x);
+
+    public native int sumWithoutPendingCheck(int a, int b);
+
+    public native int unsafeRead(int x);
+
+    public native String uncheckedConcat(String a, String b);
+
+    public native int checkedPositive(int x);
+
+    public native int checkedPositiveWithBacktrace(int x);
+
+    public native int loggedPositive(int x);
+
+    public native int checkedNonNegative(int x);
+
+    public native Duration timeoutFor(int x);
+
+    public native String checkedNonEmpty(String x);
+
+    public native String rethrowNullableString(int mode);
+
+    public native void validatePositive(int x) throws ValidationException;
+
+    public native long consumeQuota(long used, long limit);
+
+    public native int wrappingIncrement(int x);
+
+    public native long swapPacked(long x);
+
+    public native int[] getRange(int[] x);
+
+    public native int[] getRangeInclusive(int[] x);
+
+    public native String rangeToString(int[] x);
+
+    public native String rangeInclusiveToString(int[] x);
+
+    public native Object[] stopAbove(int x, int threshold);
+
+    public native Object[] getControlFlow(Object[] x);
+
+    public native AbstractMap.SimpleImmutableEntry<List<Integer>, List<String>> parseAll(List<String> x);
+
+    public native Batch parseAllInto(List<String> x);
+
+    public native double celsiusOrFreezing(double x);
+
+    public native String cowCStr(boolean borrowed);
+
+    public native String lookupSetting(String key);
+
+    public native String findSetting(String key);
+
+    public native static ArrayList<String> bridgeMismatches(String classesDir);
+
+    public native int checkedEven(int x);
+
+    public native int infallibleDouble(int x);
+
+    public native static String infallibleGreeting(String x);
+
+    public native String reversedWithBuilder(String x);
+
+    public native String positivityCheck(int x);
+
+    public native int visitTwice(int n);
+
+    public native String withSuffix(String x, String suffix);
+
+    public native double addScaled(double x, double delta);
+
+    public native int shellExitStatus(String script);
+
+    public native User[] reverseUsers(User[] x);
+
+    public native String usernamesAfter(User[] x, int skip);
+
+    public native String describeBoxed(Boolean flag, Character c, Byte b);
+
+    public native Boolean negateBoxed(Boolean flag);
+
+    public native Integer incrementOptional(Integer x);
+
+    public native User[] reverseNullableUsers(User[] x);
+
+    public native Instant getInstant(Instant x);
+
+    public native String instantToString(Instant x);
+
+    public native Properties withProperty(Properties x, String key, String value);
+
+    public native Duration getDuration(Duration x);
+
+    public native Duration maxDuration();
+
+    public native Instant getSystemTime(Instant x);
+
     public native String intToString(int x);
 
     public native String boolToString(boolean x);
@@ -61,8 +186,46 @@ public class User {
 
     public native static String userCountStatus();
 
+    public native static String staticFieldsStatus();
+
+    public native static Measurement scaleMeasurement(Measurement m, double factor);
+
+    public native static List<Measurement> measurementsOf(String unitName, List<Double> values);
+
+    public native static void registerModuleLoader(ClassLoader loader);
+
+    public native static int staticSum(int a, int b);
+
+    public native static int sumIntArray(int[] x);
+
+    public native static Deque<Integer> rotateDeque(Deque<Integer> x);
+
+    public native static long criticalChecksum(byte[] x);
+
+    public native static long copiedChecksum(byte[] x);
+
+    public native static int readGreeting(byte[] buf);
+
+    public native static void fillBytes(byte[] buf, byte v);
+
+    public native static long recordVisit();
+
+    public native static long weightedChecksum(byte[] a, byte[] b, byte[] c, long weight);
+
+    public native static int scaledChecked(int x, int factor);
+
+    public native static int scaledFast(int x, int factor);
+
     public native String hashedPassword(int seed);
 
+    public native boolean samePassword(User other);
+
+    public native boolean sameUser(Object other);
+
+    public native Object lastCopy(String x, int n);
+
+    public native String nonEmptyOf(List<String> x);
+
     public User(String username, String password) {
         User.TOTAL_USERS_COUNT += 1;
 
@@ -74,11 +237,88 @@ public class User {
         return TOTAL_USERS_COUNT;
     }
 
+    public static String nullableString(int mode) {
+        if (mode == 0) {
+            return "value";
+        } else if (mode == 1) {
+            return null;
+        } else {
+            throw new IllegalArgumentException("invalid mode");
+        }
+    }
+
+    public static String nullableStringNested(int mode) {
+        return nullableString(mode);
+    }
+
+    public static boolean isNullString(String s) {
+        return s == null;
+    }
+
+    public static int stringLength(String s) {
+        return s == null ? -1 : s.length();
+    }
+
+    public static int countNonEmpty(List<String> items) {
+        return (int) items.stream().filter(s -> !s.isEmpty()).count();
+    }
+
+    public static String joinUsernames(User[] users) {
+        StringBuilder joined = new StringBuilder();
+        for (User user : users) {
+            joined.append(joined.length() == 0 ? "" : ",").append(user.getUsername());
+        }
+        return joined.toString();
+    }
+
+    public static Integer nextOrNull(Integer v) {
+        return v == null ? null : v + 1;
+    }
+
+    public static String echoString(String s) {
+        return s;
+    }
+
+    public static String overloaded(String s) {
+        return "String";
+    }
+
+    public static String overloaded(Object o) {
+        return "Object";
+    }
+
+    public void requirePositive(int x) {
+        if (x <= 0) {
+            throw new IllegalArgumentException(x + " is not positive");
+        }
+    }
+
+    public void addVisits(int n) {
+        visits += n;
+    }
+
+    public int getVisits() {
+        return visits;
+    }
+
     public String getPassword() {
         return password;
     }
 
+    public String getUsername() {
+        return username;
+    }
+
+    public String greet(String greeting) {
+        return greeting + ", " + username;
+    }
+
     public String multipleParameters(int i, String s) {
         return s;
     }
+
+    @Override
+    public String toString() {
+        return "User(" + username + ")";
+    }
 }
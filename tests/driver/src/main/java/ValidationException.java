@@ -0,0 +1,8 @@
+public class ValidationException extends Exception {
+    public final int code;
+
+    public ValidationException(int code) {
+        super("validation failed with code " + code);
+        this.code = code;
+    }
+}
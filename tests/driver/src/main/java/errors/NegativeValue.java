@@ -0,0 +1,7 @@
+package errors;
+
+public class NegativeValue extends RuntimeException {
+    public NegativeValue(String message) {
+        super(message);
+    }
+}
@@ -1,11 +1,25 @@
 import org.junit.jupiter.api.BeforeEach;
 import org.junit.jupiter.api.Test;
 
+import java.time.Duration;
+import java.nio.file.Paths;
+import java.time.Instant;
+import java.util.ArrayDeque;
+import java.util.ArrayList;
+import java.util.Deque;
+import java.util.LinkedList;
 import java.util.List;
+import java.util.Map;
+import java.util.Properties;
 import java.util.function.Function;
 
 import static org.junit.jupiter.api.Assertions.assertEquals;
+import static org.junit.jupiter.api.Assertions.assertFalse;
 import static org.junit.jupiter.api.Assertions.assertArrayEquals;
+import static org.junit.jupiter.api.Assertions.assertNull;
+import static org.junit.jupiter.api.Assertions.assertSame;
+import static org.junit.jupiter.api.Assertions.assertThrows;
+import static org.junit.jupiter.api.Assertions.assertTrue;
 
 public class UserTest {
     private User u;
@@ -22,6 +36,17 @@ public class UserTest {
         assertEquals(expected, actual);
     }
 
+    @Test
+    public void borrowedParametersTest() {
+        assertTrue(u.samePassword(new User("other", "pass")));
+        assertFalse(u.samePassword(new User("other", "other_pass")));
+    }
+
+    @Test
+    public void staticPureFunction() {
+        assertEquals(3, User.staticSum(1, 2));
+    }
+
     @Test
     public void intTest() {
         assertValueRoundTrip(u::getInt, u::intToString, 0, "0");
@@ -31,6 +56,22 @@ public class UserTest {
         assertValueRoundTrip(u::getInt, u::intToString, Integer.MIN_VALUE, "-2147483648");
     }
 
+    @Test
+    public void unsafeMethodTest() {
+        assertEquals(42, u.unsafeRead(42));
+    }
+
+    @Test
+    public void uncheckedArgumentTest() {
+        assertEquals("foobar", u.uncheckedConcat("foo", "bar"));
+    }
+
+    @Test
+    public void wrappingTest() {
+        assertEquals(1, u.wrappingIncrement(0));
+        assertEquals(Integer.MIN_VALUE, u.wrappingIncrement(Integer.MAX_VALUE));
+    }
+
     @Test
     public void boolTest() {
         assertValueRoundTrip(u::getBool, u::boolToString, false, "false");
@@ -47,6 +88,345 @@ public class UserTest {
         assertValueRoundTrip(u::getChar, u::charToString, Character.MAX_VALUE, "\uffff");
     }
 
+    @Test
+    public void argumentConversionErrorTest() {
+        RuntimeException e = assertThrows(RuntimeException.class, () -> u.getChar('\ud800'));
+        assertTrue(e.getMessage().contains("failed to convert argument `v` (#1)"));
+    }
+
+    @Test
+    public void exceptionClassConstTest() {
+        assertEquals(1, u.checkedPositive(1));
+        IllegalArgumentException e = assertThrows(IllegalArgumentException.class, () -> u.checkedPositive(0));
+        assertTrue(e.getMessage().startsWith("invalid value"));
+    }
+
+    @Test
+    public void rustBacktraceTest() {
+        assertEquals(1, u.checkedPositiveWithBacktrace(1));
+        RuntimeException e = assertThrows(RuntimeException.class, () -> u.checkedPositiveWithBacktrace(0));
+        assertTrue(e.getMessage().startsWith("invalid value"));
+        assertTrue(e.getMessage().contains("Rust backtrace:"));
+        assertTrue(e.getMessage().contains("checkedPositiveWithBacktrace"));
+
+        IllegalArgumentException plain = assertThrows(IllegalArgumentException.class, () -> u.checkedPositive(0));
+        assertFalse(plain.getMessage().contains("Rust backtrace:"));
+    }
+
+    @Test
+    public void derivedExceptionClassTest() {
+        assertEquals(3, u.checkedNonNegative(3));
+        errors.NegativeValue e = assertThrows(errors.NegativeValue.class, () -> u.checkedNonNegative(-1));
+        assertEquals("JNI call error!. Cause: -1 is negative", e.getMessage());
+    }
+
+    @Test
+    public void returnedValueConversionTest() {
+        assertEquals(Duration.ofSeconds(5), u.timeoutFor(5));
+        assertThrows(errors.NegativeValue.class, () -> u.timeoutFor(-1));
+        IllegalStateException e = assertThrows(IllegalStateException.class, () -> u.timeoutFor(0));
+        assertEquals("failed to convert the returned value: Invalid JValue type cast: java.time.Duration. Actual type: out of range Duration", e.getMessage());
+    }
+
+    @Test
+    public void anyhowContextChainTest() {
+        assertEquals("Hello", u.lookupSetting("greeting"));
+        RuntimeException e = assertThrows(RuntimeException.class, () -> u.lookupSetting("missing"));
+        assertEquals("JNI call error!. Cause: loading configuration: reading settings: no setting named `missing`", e.getMessage());
+    }
+
+    @Test
+    public void loggedExceptionTest() {
+        assertEquals(1, u.loggedPositive(1));
+        assertTrue(RecordingLoggerFactory.MESSAGES.isEmpty());
+
+        RuntimeException e = assertThrows(RuntimeException.class, () -> u.loggedPositive(-1));
+        assertEquals(List.of("User: " + e.getMessage()), RecordingLoggerFactory.MESSAGES);
+        assertSame(e, RecordingLoggerFactory.THROWABLES.get(0));
+    }
+
+    @Test
+    public void erasedGenericClassTest() {
+        assertEquals("2/3", u.nonEmptyOf(List.of("a", "", "b")));
+        assertEquals("0/0", u.nonEmptyOf(new ArrayList<>()));
+    }
+
+    @Test
+    public void localFrameTest() {
+        assertEquals("x199", u.lastCopy("x", 200));
+        assertNull(u.lastCopy("x", 0));
+    }
+
+    @Test
+    public void stdConversionsTest() {
+        assertTrue(u.sameUser(u));
+        assertFalse(u.sameUser(new User("user", "password")));
+    }
+
+    @Test
+    public void nullableResultTest() {
+        assertEquals("Hello", u.findSetting("greeting"));
+        assertNull(u.findSetting("farewell"));
+        RuntimeException e = assertThrows(RuntimeException.class, () -> u.findSetting("missing"));
+        assertEquals("JNI call error!. Cause: no setting named `missing`", e.getMessage());
+    }
+
+    @Test
+    public void defaultedArgumentTest() {
+        assertEquals("ab", u.withSuffix("a", "b"));
+        assertEquals("a", u.withSuffix("a", null));
+        assertThrows(RuntimeException.class, () -> u.withSuffix(null, "b"));
+    }
+
+    @Test
+    public void importedConstructorTest() {
+        assertEquals("cba", u.reversedWithBuilder("abc"));
+        assertEquals("", u.reversedWithBuilder(""));
+    }
+
+    @Test
+    public void voidImportedMethodTest() {
+        assertEquals("positive", u.positivityCheck(1));
+        assertEquals("rejected: Java exception was thrown", u.positivityCheck(-1));
+
+        User other = new User("other", "password");
+        assertEquals(6, other.visitTwice(3));
+        assertEquals(6, other.getVisits());
+    }
+
+    @Test
+    public void nullPolicyTest() {
+        assertEquals(3, Lenient.length("abc"));
+        assertEquals(0, Lenient.length(null));
+        assertThrows(RuntimeException.class, () -> u.getString(null));
+    }
+
+    @Test
+    public void unitErrorTest() {
+        assertEquals(4, u.checkedEven(4));
+        RuntimeException e = assertThrows(RuntimeException.class, () -> u.checkedEven(3));
+        assertEquals("JNI call error!. Cause: method returned `Err(())`", e.getMessage());
+    }
+
+    @Test
+    public void infallibleResultTest() {
+        assertEquals(6, u.infallibleDouble(3));
+        assertEquals("Hello, Java!", User.infallibleGreeting("Java"));
+    }
+
+    @Test
+    public void throwingObjectReturnTest() {
+        assertEquals("value", u.checkedNonEmpty("value"));
+        assertThrows(RuntimeException.class, () -> u.checkedNonEmpty(""));
+    }
+
+    @Test
+    public void rethrowJavaExceptionTest() {
+        assertEquals("value", u.rethrowNullableString(0));
+        IllegalArgumentException e = assertThrows(IllegalArgumentException.class, () -> u.rethrowNullableString(2));
+        assertEquals("invalid mode", e.getMessage());
+    }
+
+    @Test
+    public void bothCallTypesTest() {
+        for (int v : new int[]{0, 1, -3, 1000}) {
+            assertEquals(User.scaledChecked(v, 3), User.scaledFast(v, 3));
+        }
+        assertEquals(6, User.scaledChecked(2, 3));
+    }
+
+    @Test
+    public void controlFlowTest() {
+        int visited = 0;
+        Object found = null;
+        for (int v : new int[]{1, 3, 5, 7, 9}) {
+            visited++;
+            Object[] flow = u.stopAbove(v, 4);
+            if ((Boolean) flow[0]) {
+                found = flow[1];
+                break;
+            }
+            assertNull(flow[1]);
+        }
+        assertEquals(3, visited);
+        assertEquals(5, found);
+
+        assertArrayEquals(new Object[]{true, "done"}, u.getControlFlow(new Object[]{true, "done"}));
+        assertArrayEquals(new Object[]{false, 42}, u.getControlFlow(new Object[]{false, 42}));
+    }
+
+    @Test
+    public void exitStatusTest() {
+        assertEquals(0, u.shellExitStatus("true"));
+        assertEquals(3, u.shellExitStatus("exit 3"));
+        assertEquals(128 + 9, u.shellExitStatus("kill -9 $$"));
+    }
+
+    @Test
+    public void atomicValueTest() {
+        long visits = User.recordVisit();
+        assertTrue(visits > 0);
+        assertEquals(visits + 1, User.recordVisit());
+    }
+
+    @Test
+    public void cowTest() {
+        assertEquals(21.5, u.celsiusOrFreezing(21.5));
+        assertEquals(0.0, u.celsiusOrFreezing(Double.NaN));
+        assertEquals("borrowed", u.cowCStr(true));
+        assertEquals("owned", u.cowCStr(false));
+    }
+
+    @Test
+    public void conversionContextTest() {
+        // 1.5 is scaled to 15 by the context, and the result scaled back
+        assertEquals(2.0, u.addScaled(1.5, 5.0));
+    }
+
+    @Test
+    public void batchResultTest() {
+        List<String> items = List.of("1", "x", "3", "", "5");
+
+        Map.Entry<List<Integer>, List<String>> entry = u.parseAll(items);
+        assertEquals(List.of(1, 3, 5), entry.getKey());
+        assertEquals(List.of("x", ""), entry.getValue());
+
+        Batch batch = u.parseAllInto(items);
+        assertEquals(List.of(1, 3, 5), batch.successes);
+        assertEquals(List.of("x", ""), batch.errors);
+
+        Batch empty = u.parseAllInto(List.of());
+        assertTrue(empty.successes.isEmpty());
+        assertTrue(empty.errors.isEmpty());
+    }
+
+    @Test
+    public void unitAndTupleStructsTest() {
+        assertEquals(42, Marker.answer());
+        assertEquals(2, Counter.increment(1));
+    }
+
+    @Test
+    public void receiverStrategiesTest() {
+        Tally tally = new Tally();
+        assertEquals(2, tally.add(2));
+        assertEquals(5, tally.add(3));
+        assertEquals(5, tally.total());
+        assertEquals(5, tally.close());
+        RuntimeException closed = assertThrows(RuntimeException.class, tally::total);
+        assertTrue(closed.getMessage().contains("failed to convert argument `receiver` (#0)"));
+
+        Point point = new Point(3, -4);
+        assertEquals(7, point.manhattanLength());
+        assertTrue(point.isSameAs(point));
+        assertFalse(point.isSameAs(new Point(3, -4)));
+
+        Interval interval = new Interval(2, 5);
+        assertEquals(3, interval.length());
+        assertTrue(interval.contains(4));
+        assertFalse(interval.contains(5));
+    }
+
+    @Test
+    public void dispatcherTest() {
+        Interval interval = new Interval(2, 5);
+        assertEquals(Integer.valueOf(3), Interval.invoke("length", new Object[] {interval}));
+        assertEquals(Boolean.TRUE, Interval.invoke("contains", new Object[] {interval, 4}));
+        assertEquals("[2, 5) cm", Interval.invoke("describe", new Object[] {interval, "cm"}));
+        assertEquals(Integer.valueOf(7), Interval.invoke("widthOf", new Object[] {1, 8}));
+        assertNull(Interval.invoke("requireContains", new Object[] {interval, 3}));
+
+        /* Exceptions of the method itself are thrown as with a direct call */
+        RuntimeException e = assertThrows(RuntimeException.class, () -> Interval.invoke("requireContains", new Object[] {interval, 9}));
+        assertEquals(e.getMessage(), assertThrows(RuntimeException.class, () -> interval.requireContains(9)).getMessage());
+
+        IllegalArgumentException unknown = assertThrows(IllegalArgumentException.class, () -> Interval.invoke("width", new Object[0]));
+        assertEquals("no exported method `width` taking 0 arguments", unknown.getMessage());
+        assertThrows(IllegalArgumentException.class, () -> Interval.invoke("length", new Object[0]));
+        IllegalArgumentException mismatch = assertThrows(IllegalArgumentException.class, () -> Interval.invoke("contains", new Object[] {interval, "4"}));
+        assertEquals("`args[1]` of `contains` is not a `java.lang.Integer`", mismatch.getMessage());
+        assertThrows(IllegalArgumentException.class, () -> Interval.invoke("contains", new Object[] {interval, null}));
+        assertThrows(IllegalArgumentException.class, () -> Interval.invoke("length", new Object[] {"interval"}));
+    }
+
+    @Test
+    public void classAliasTest() {
+        assertEquals("Hello, v1", GreeterV1.greet("v1"));
+        assertEquals("Hello, v2", GreeterV2.greet("v2"));
+    }
+
+    @Test
+    public void builderTest() {
+        Builder builder = new Builder();
+        Builder built = builder.withWidth(3).withHeight(4);
+        assertSame(builder, built);
+        assertEquals(3, built.width);
+        assertEquals(4, built.height);
+        assertEquals(12, built.area());
+    }
+
+    @Test
+    public void structuredErrorTest() throws ValidationException {
+        u.validatePositive(1);
+        ValidationException e = assertThrows(ValidationException.class, () -> u.validatePositive(-7));
+        assertEquals(-7, e.code);
+    }
+
+    @Test
+    public void recordTest() {
+        Measurement scaled = User.scaleMeasurement(new Measurement("mm", 1.5), 2.0);
+        assertEquals(new Measurement("mm (scaled)", 3.0), scaled);
+    }
+
+    @Test
+    public void listOfRecordsTest() {
+        List<Measurement> measurements = User.measurementsOf("mm", List.of(1.5, 2.0));
+        assertEquals(List.of(new Measurement("mm", 1.5), new Measurement("mm", 2.0)), measurements);
+        assertEquals(List.of(), User.measurementsOf("mm", new LinkedList<>()));
+    }
+
+    @Test
+    public void toThrowableTest() {
+        assertEquals(2, u.consumeQuota(3, 5));
+        QuotaExceededException e = assertThrows(QuotaExceededException.class, () -> u.consumeQuota(7, 5));
+        assertEquals("7 used out of 5", e.getMessage());
+        assertEquals(5, e.limit);
+    }
+
+    @Test
+    public void structSliceTest() {
+        User[] users = {new User("first", "p"), new User("second", "p"), new User("third", "p")};
+        assertEquals("second,third", u.usernamesAfter(users, 1));
+        assertEquals("", u.usernamesAfter(users, 3));
+    }
+
+    @Test
+    public void boxedPrimitivesTest() {
+        assertEquals("true a 7", u.describeBoxed(Boolean.TRUE, 'a', (byte) 7));
+        assertEquals(Boolean.FALSE, u.negateBoxed(true));
+        assertThrows(RuntimeException.class, () -> u.describeBoxed(null, 'a', (byte) 7));
+    }
+
+    @Test
+    public void boxedOptionTest() {
+        assertEquals(Integer.valueOf(6), u.incrementOptional(5));
+        assertNull(u.incrementOptional(null));
+    }
+
+    @Test
+    public void bridgeDescriptorsTest() throws Exception {
+        String classesDir = Paths.get(User.class.getProtectionDomain().getCodeSource().getLocation().toURI()).toString();
+        assertEquals(List.of(), User.bridgeMismatches(classesDir));
+    }
+
+    @Test
+    public void moduleLoaderTest() {
+        User.registerModuleLoader(User.class.getClassLoader());
+        ValidationException e = assertThrows(ValidationException.class, () -> u.validatePositive(-3));
+        assertEquals(-3, e.code);
+        assertThrows(RuntimeException.class, () -> User.registerModuleLoader(null));
+    }
+
     @Test
     public void byteTest() {
         assertValueRoundTrip(u::getByte, u::byteToString, (byte) 0, "0");
@@ -124,6 +504,13 @@ public class UserTest {
         assertValueRoundTrip(u::getString, Function.identity(), "️️𒅄", "️️𒅄"); // 4 bytes in utf-8
     }
 
+    @Test
+    public void cStringTest() {
+        assertEquals("hello!", u.getCString("hello!"));
+        assertEquals("", u.getCString(""));
+        assertThrows(RuntimeException.class, () -> u.getCString("a\0b"));
+    }
+
     @Test
     public void intArrayTest() {
         assertValueRoundTrip(u::getIntArray, u::intArrayToString, List.of(), "[]");
@@ -142,11 +529,198 @@ public class UserTest {
         assertArrayValueRoundTrip(u::getByteArray, u::byteArrayToString, new byte[] {1, 2, 3}, "[1, 2, 3]");
     }
 
+    @Test
+    public void primitiveArrayTest() {
+        double[] doubles = {0.1, -2.5e300, Double.MIN_VALUE, Double.NaN, Double.NEGATIVE_INFINITY};
+        assertArrayEquals(doubles, u.getDoubleArray(doubles));
+        assertArrayEquals(new double[0], u.getDoubleArray(new double[0]));
+        assertEquals(6, User.sumIntArray(new int[] {1, 2, 3}));
+    }
+
+    @Test
+    public void charArrayTest() {
+        char[] chars = "Hello, w\u00f6rld".toCharArray();
+        assertArrayEquals(chars, u.getCharArray(chars));
+        assertArrayEquals(new char[0], u.getCharArray(new char[0]));
+
+        // Surrogate pairs are decoded to a single char and encoded back
+        char[] emoji = "a\uD83D\uDE00".toCharArray();
+        assertArrayEquals(emoji, u.getCharArray(emoji));
+        assertEquals(2, u.charArrayLength(emoji));
+        assertThrows(RuntimeException.class, () -> u.getCharArray(new char[] {'\uD83D'}));
+    }
+
+    @Test
+    public void dequeTest() {
+        Deque<String> deque = new ArrayDeque<>(List.of("a", "b", "c"));
+        assertEquals(List.of("a", "b", "c"), new ArrayList<>(u.getDeque(deque)));
+        assertTrue(u.getDeque(new ArrayDeque<>()).isEmpty());
+
+        Deque<Integer> rotated = User.rotateDeque(new LinkedList<>(List.of(1, 2, 3)));
+        assertTrue(rotated instanceof ArrayDeque);
+        assertEquals(List.of(2, 3, 1), new ArrayList<>(rotated));
+    }
+
+    @Test
+    public void criticalByteArrayTest() {
+        byte[] large = new byte[1 << 20];
+        for (int i = 0; i < large.length; i++) {
+            large[i] = (byte) i;
+        }
+
+        assertEquals(User.copiedChecksum(large), User.criticalChecksum(large));
+        assertEquals(6, User.criticalChecksum(new byte[] {1, 2, 3}));
+        assertEquals(0, User.criticalChecksum(new byte[0]));
+    }
+
+    @Test
+    public void mutableByteArrayTest() {
+        byte[] buf = new byte[8];
+        assertEquals(5, User.readGreeting(buf));
+        assertArrayEquals(new byte[] {'h', 'e', 'l', 'l', 'o', 0, 0, 0}, buf);
+
+        byte[] small = new byte[2];
+        assertEquals(2, User.readGreeting(small));
+        assertArrayEquals(new byte[] {'h', 'e'}, small);
+        assertEquals(0, User.readGreeting(new byte[0]));
+
+        User.fillBytes(buf, (byte) 7);
+        assertArrayEquals(new byte[] {7, 7, 7, 7, 7, 7, 7, 7}, buf);
+    }
+
+    @Test
+    public void criticalByteArraysTest() {
+        byte[] large = new byte[1 << 20];
+        for (int i = 0; i < large.length; i++) {
+            large[i] = (byte) i;
+        }
+
+        assertEquals(2 * 3 * User.copiedChecksum(large), User.weightedChecksum(large, large, large, 2));
+        assertEquals(2 * (1 + 2 + 3 + 4), User.weightedChecksum(new byte[] {1, 2}, new byte[0], new byte[] {3, 4}, 2));
+    }
+
+    @Test
+    public void smallVecTest() {
+        // Arrays both fitting inline and spilling to the heap
+        assertArrayEquals(new int[] {1, 2}, u.getSmallIntArray(new int[] {1, 2}));
+        assertArrayEquals(new int[] {1, 2, 3, 4, 5, 6}, u.getSmallIntArray(new int[] {1, 2, 3, 4, 5, 6}));
+        assertArrayEquals(new int[0], u.getSmallIntArray(new int[0]));
+
+        User other = new User("other", "password");
+        User[] reversed = u.reverseSmallUsers(new User[] {u, other});
+        assertEquals("other", reversed[0].getUsername());
+        assertEquals("user", reversed[1].getUsername());
+    }
+
+    @Test
+    public void packedTest() {
+        long packed = ((long) -1 << 32) | (42 & 0xFFFFFFFFL);
+        long swapped = u.swapPacked(packed);
+        assertEquals(42, (int) (swapped >> 32));
+        assertEquals(-1, (int) swapped);
+    }
+
+    @Test
+    public void javaDebugTest() {
+        assertEquals("User(user)", u.debugString());
+    }
+
+    @Test
+    public void bytesTest() {
+        assertArrayEquals(new byte[] {}, u.getBytes(new byte[] {}));
+        assertArrayEquals(new byte[] {1, -2, 3}, u.getBytes(new byte[] {1, -2, 3}));
+        assertArrayEquals(new byte[] {1, -2, 42}, u.appendBytesMut(new byte[] {1, -2}));
+    }
+
+    @Test
+    public void structArrayTest() {
+        User first = new User("first", "first_password");
+        User second = new User("second", "second_password");
+        User[] reversed = u.reverseUsers(new User[] {first, second});
+        assertEquals(2, reversed.length);
+        assertEquals("second", reversed[0].getUsername());
+        assertEquals("first", reversed[1].getUsername());
+    }
+
+    @Test
+    public void nullableStructArrayTest() {
+        User first = new User("first", "first_password");
+        User third = new User("third", "third_password");
+        User[] reversed = u.reverseNullableUsers(new User[] {first, null, third});
+        assertEquals(3, reversed.length);
+        assertEquals("third", reversed[0].getUsername());
+        assertNull(reversed[1]);
+        assertEquals("first", reversed[2].getUsername());
+    }
+
+    @Test
+    public void rangeTest() {
+        assertArrayEquals(new int[] {-1, 10}, u.getRange(new int[] {-1, 10}));
+        assertEquals("-1..10", u.rangeToString(new int[] {-1, 10}));
+        assertArrayEquals(new int[] {-1, 10}, u.getRangeInclusive(new int[] {-1, 10}));
+        assertEquals("-1..=10", u.rangeInclusiveToString(new int[] {-1, 10}));
+        assertThrows(RuntimeException.class, () -> u.getRange(new int[] {1, 2, 3}));
+    }
+
+    @Test
+    public void noPendingCheckTest() {
+        assertEquals(3, u.sumWithoutPendingCheck(1, 2));
+    }
+
+    @Test
+    public void instantTest() {
+        assertValueRoundTrip(u::getInstant, u::instantToString, Instant.ofEpochMilli(0), "0");
+        assertValueRoundTrip(u::getInstant, u::instantToString, Instant.ofEpochMilli(1234567890123L), "1234567890123");
+        assertValueRoundTrip(u::getInstant, u::instantToString, Instant.ofEpochMilli(-1234567890123L), "-1234567890123");
+        assertValueRoundTrip(u::getInstant, u::instantToString, Instant.ofEpochSecond(10, 123456789), "10123");
+    }
+
+    @Test
+    public void propertiesTest() {
+        Properties config = new Properties();
+        config.setProperty("host", "localhost");
+        config.setProperty("port", "8080");
+
+        Properties expected = new Properties();
+        expected.setProperty("host", "localhost");
+        expected.setProperty("port", "8080");
+        expected.setProperty("user", "admin");
+        assertEquals(expected, u.withProperty(config, "user", "admin"));
+
+        Properties defaults = new Properties();
+        defaults.setProperty("port", "80");
+        Properties overrides = new Properties(defaults);
+        overrides.setProperty("host", "example.com");
+        assertEquals("80", u.withProperty(overrides, "user", "root").getProperty("port"));
+    }
+
+    @Test
+    public void durationTest() {
+        assertEquals(Duration.ofSeconds(5, 123), u.getDuration(Duration.ofSeconds(5, 123)));
+        assertEquals(Duration.ZERO, u.getDuration(Duration.ZERO));
+        assertThrows(RuntimeException.class, () -> u.getDuration(Duration.ofSeconds(-1)));
+        RuntimeException e = assertThrows(RuntimeException.class, u::maxDuration);
+        assertEquals("JNI call error!. Cause: Invalid JValue type cast: java.time.Duration. Actual type: out of range Duration", e.getMessage());
+    }
+
+    @Test
+    public void systemTimeTest() {
+        assertEquals(Instant.ofEpochSecond(1234567890, 999999999), u.getSystemTime(Instant.ofEpochSecond(1234567890, 999999999)));
+        assertEquals(Instant.ofEpochSecond(-10, 5), u.getSystemTime(Instant.ofEpochSecond(-10, 5)));
+        assertEquals(Instant.MAX, u.getSystemTime(Instant.MAX));
+        assertEquals(Instant.MIN, u.getSystemTime(Instant.MIN));
+    }
+
     @Test
     public void staticMethod() {
         assertEquals(String.valueOf(User.getTotalUsersCount()), User.userCountStatus());
     }
 
+    @Test
+    public void staticFieldTest() {
+        assertEquals(User.getTotalUsersCount() + " " + User.DEFAULT_GREETING, User.staticFieldsStatus());
+    }
+
     private <T> void assertValueRoundTrip(Function<T, T> func, Function<T, String> toString, T value, String text) {
         assertEquals(value, func.apply(value));
         assertEquals(text, toString.apply(value));
@@ -1,8 +1,8 @@
 use std::fs;
 use std::path::Path;
-use jni::objects::JString;
+use jni::objects::{JObject, JString};
 use native::jni::User;
-use robusta_jni::convert::FromJavaValue;
+use robusta_jni::convert::{FromJavaValue, JNull, WeakGlobal};
 use robusta_jni::jni::{InitArgsBuilder, JNIEnv, JavaVM};
 use std::process::Command;
 
@@ -73,7 +73,7 @@ fn vm_creation_and_object_usage() {
     let vm = JavaVM::new(vm_args).expect("can't create vm");
     let env = vm.attach_current_thread().expect("can't get vm env");
 
-    User::initNative();
+    User::initNative(&env);
 
     let count = User::getTotalUsersCount(&env)
         .or_else(|e| {
@@ -99,9 +99,55 @@ fn vm_creation_and_object_usage() {
         "password"
     );
 
+    assert_eq!(
+        u.getUsername(&env).expect("can't get user username"),
+        "user"
+    );
+
     assert_eq!(
         u.multipleParameters(&env, 10, "test".to_string())
             .expect("Can't test multipleParameters"),
         "test"
-    )
+    );
+
+    assert_eq!(
+        User::greet(&env, "Hello".to_string(), &u).expect("can't test greet"),
+        "Hello, user"
+    );
+
+    assert_eq!(User::nullableString(&env, 0).expect("can't test nullableString"), Some("value".to_string()));
+    assert_eq!(User::nullableString(&env, 1).expect("can't test nullableString"), None);
+    assert!(User::nullableString(&env, 2).is_err());
+    env.exception_clear().expect("can't clear exception");
+
+    assert_eq!(User::nullableStringNested(&env, 0).map(|r| r.expect("can't test nullableStringNested")), Some("value".to_string()));
+    assert!(User::nullableStringNested(&env, 1).is_none());
+    assert!(matches!(User::nullableStringNested(&env, 2), Some(Err(_))));
+    env.exception_clear().expect("can't clear exception");
+
+    assert!(User::isNullString(&env, JNull::new()).expect("can't test isNullString"));
+
+    assert_eq!(User::stringLength(&env, None).expect("can't test stringLength"), -1);
+    assert_eq!(User::stringLength(&env, Some("x")).expect("can't test stringLength"), 1);
+
+    assert_eq!(
+        User::echoString(&env, format_args!("x={}", 5)).expect("can't test echoString"),
+        "x=5"
+    );
+    assert_eq!(
+        User::echoString(&env, format_args!("literal")).expect("can't test echoString"),
+        "literal"
+    );
+
+    assert_eq!(User::overloaded(&env, "s".to_string()).expect("can't test overloaded"), "Object");
+
+    assert_eq!(format!("{:?}", u), "User(user)");
+    assert_eq!(u.toString(&env), "User(user)");
+
+    let obj = env.new_object("java/lang/Object", "()V", &[]).expect("can't create object");
+    let weak: WeakGlobal<JObject> = WeakGlobal::new(&env, obj).expect("can't create weak global reference");
+    assert!(weak.upgrade(&env).expect("can't upgrade weak global reference").is_some());
+    env.delete_local_ref(obj).expect("can't delete local reference");
+    env.call_static_method("java/lang/System", "gc", "()V", &[]).expect("can't run garbage collector");
+    assert!(weak.upgrade(&env).expect("can't upgrade weak global reference").is_none())
 }